@@ -0,0 +1,215 @@
+//! Cache of initialised SGP4 propagators
+//!
+//! Parsing elements and initialising the SGP4 constants dominate the cost of
+//! a single propagation, and clients such as the conjunction detector send the
+//! same few thousand orbits every minute. Entries are keyed by the orbit text
+//! and spread over independently locked shards; a full shard evicts its least
+//! recently used entry.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
+
+use crate::metrics;
+use crate::propagator::Sgp4Orbit;
+
+/// Default number of cached orbits
+pub const DEFAULT_CAPACITY: usize = 10_000;
+
+/// Number of independently locked shards
+const SHARD_COUNT: usize = 16;
+
+lazy_static! {
+    static ref CACHE: Sgp4Cache = Sgp4Cache::new(DEFAULT_CAPACITY);
+}
+
+/// Cached orbit for `key`, initialised with `init` on a miss. Failures are not
+/// cached.
+pub fn get_or_try_insert<E>(
+    key: &str,
+    init: impl FnOnce() -> Result<Sgp4Orbit, E>,
+) -> Result<Arc<Sgp4Orbit>, E> {
+    CACHE.get_or_try_insert(key, init)
+}
+
+/// Change the capacity of the shared cache; shrinking takes effect as new
+/// entries are inserted
+pub fn set_capacity(capacity: usize) {
+    CACHE.capacity.store(capacity, Ordering::Relaxed);
+}
+
+/// Bounded, sharded LRU cache of initialised orbits
+pub struct Sgp4Cache {
+    shards: Vec<Mutex<Shard>>,
+    capacity: AtomicUsize,
+}
+
+#[derive(Default)]
+struct Shard {
+    entries: HashMap<String, Entry>,
+    /// Logical clock for recency
+    tick: u64,
+}
+
+struct Entry {
+    orbit: Arc<Sgp4Orbit>,
+    last_used: u64,
+}
+
+impl Sgp4Cache {
+    pub fn new(capacity: usize) -> Self {
+        Self::with_shards(capacity, SHARD_COUNT)
+    }
+
+    pub fn with_shards(capacity: usize, shards: usize) -> Self {
+        Self {
+            shards: (0..shards.max(1))
+                .map(|_| Mutex::new(Shard::default()))
+                .collect(),
+            capacity: AtomicUsize::new(capacity),
+        }
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| lock(shard).entries.len())
+            .sum()
+    }
+
+    #[cfg(test)]
+    fn contains(&self, key: &str) -> bool {
+        lock(self.shard(key)).entries.contains_key(key)
+    }
+
+    pub fn get_or_try_insert<E>(
+        &self,
+        key: &str,
+        init: impl FnOnce() -> Result<Sgp4Orbit, E>,
+    ) -> Result<Arc<Sgp4Orbit>, E> {
+        let shard = self.shard(key);
+
+        {
+            let mut guard = lock(shard);
+            let shard = &mut *guard;
+            shard.tick += 1;
+            if let Some(entry) = shard.entries.get_mut(key) {
+                entry.last_used = shard.tick;
+                metrics::record_sgp4_cache_event("hit");
+                return Ok(Arc::clone(&entry.orbit));
+            }
+        }
+
+        // Initialise without holding the lock; a concurrent miss on the same
+        // key may do the same work, and the first insert wins
+        metrics::record_sgp4_cache_event("miss");
+        let orbit = Arc::new(init()?);
+
+        let mut guard = lock(shard);
+        let shard = &mut *guard;
+        shard.tick += 1;
+        if let Some(entry) = shard.entries.get_mut(key) {
+            entry.last_used = shard.tick;
+            return Ok(Arc::clone(&entry.orbit));
+        }
+
+        let shard_capacity = self
+            .capacity
+            .load(Ordering::Relaxed)
+            .div_ceil(self.shards.len());
+        while !shard.entries.is_empty() && shard.entries.len() >= shard_capacity {
+            let oldest = shard
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                shard.entries.remove(&oldest);
+                metrics::record_sgp4_cache_event("eviction");
+                metrics::add_sgp4_cache_entries(-1);
+            }
+        }
+
+        if shard_capacity > 0 {
+            shard.entries.insert(
+                key.to_string(),
+                Entry {
+                    orbit: Arc::clone(&orbit),
+                    last_used: shard.tick,
+                },
+            );
+            metrics::add_sgp4_cache_entries(1);
+        }
+        Ok(orbit)
+    }
+
+    fn shard(&self, key: &str) -> &Mutex<Shard> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % self.shards.len()]
+    }
+}
+
+/// A panic while holding a shard lock cannot leave an entry half-written, so
+/// poisoning is ignored
+fn lock(shard: &Mutex<Shard>) -> std::sync::MutexGuard<'_, Shard> {
+    shard
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::propagator::OrbitSource;
+
+    const ISS_TLE_LINE1: &str =
+        "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008";
+    const ISS_TLE_LINE2: &str =
+        "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096";
+
+    fn iss_orbit() -> Result<Sgp4Orbit, String> {
+        let source = OrbitSource::Tle {
+            line1: ISS_TLE_LINE1,
+            line2: ISS_TLE_LINE2,
+        };
+        Sgp4Orbit::from_source(&source).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_hits_share_the_orbit() {
+        let cache = Sgp4Cache::new(8);
+        let first = cache.get_or_try_insert("iss", iss_orbit).unwrap();
+        let second = cache
+            .get_or_try_insert("iss", || -> Result<Sgp4Orbit, String> {
+                panic!("cached orbit should be reused")
+            })
+            .unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(cache.len(), 1);
+
+        // Failures are not cached
+        let failed = cache.get_or_try_insert("bad", || Err("no elements".to_string()));
+        assert!(failed.is_err());
+        assert!(!cache.contains("bad"));
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = Sgp4Cache::with_shards(2, 1);
+        cache.get_or_try_insert("a", iss_orbit).unwrap();
+        cache.get_or_try_insert("b", iss_orbit).unwrap();
+        // Touch "a" so "b" is the oldest
+        cache.get_or_try_insert("a", iss_orbit).unwrap();
+        cache.get_or_try_insert("c", iss_orbit).unwrap();
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains("a") && cache.contains("c"));
+        assert!(!cache.contains("b"));
+    }
+}
@@ -0,0 +1,580 @@
+//! Registry of satellites and their current elements
+//!
+//! Clients register an object once and then refer to it by `satellite_id`
+//! instead of sending its TLE with every request. Entries are validated when
+//! they are written, so a registered orbit always parses and initialises.
+//! Every element set registered for a satellite is kept, and requests for a
+//! time are answered with the set whose epoch fits that time best, since
+//! SGP4 error grows quickly away from the epoch.
+//! Ground stations can be registered the same way. A catalog opened on a data
+//! directory journals every change there before applying it.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::propagator::{GroundStation, OrbitSource, PropagationError};
+use crate::store::{Change, Store, StoreError};
+use crate::tle;
+
+/// Registered object with one of its TLEs: the current one, or an earlier
+/// one when listed in its history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    pub satellite_id: String,
+    pub name: String,
+    /// NORAD catalog number decoded from the TLE
+    pub norad_id: u64,
+    pub tle_line1: String,
+    pub tle_line2: String,
+    /// Epoch of the TLE as a UTC Unix time
+    pub epoch_unix: f64,
+    /// When the entry was last written, as a Unix time
+    pub updated_unix: f64,
+}
+
+impl CatalogEntry {
+    /// Validate a TLE for registration under `satellite_id`. `norad_id`
+    /// (digits or Alpha-5) is checked against the TLE when given.
+    pub fn new(
+        satellite_id: &str,
+        name: &str,
+        tle_line1: &str,
+        tle_line2: &str,
+        norad_id: Option<&str>,
+    ) -> Result<Self, PropagationError> {
+        if satellite_id.trim().is_empty() {
+            return Err(PropagationError::InvalidParameter(
+                "satellite_id cannot be empty".to_string(),
+            ));
+        }
+
+        tle::validate(tle_line1, tle_line2)
+            .map_err(|e| PropagationError::TleParseError(e.to_string()))?;
+        let source = OrbitSource::Tle {
+            line1: tle_line1,
+            line2: tle_line2,
+        };
+        if let Some(norad_id) = norad_id {
+            source.check_norad_id(norad_id)?;
+        }
+        // Initialising SGP4 also warms the shared cache for later requests
+        let orbit = source.sgp4()?;
+
+        Ok(Self {
+            satellite_id: satellite_id.to_string(),
+            name: name.to_string(),
+            norad_id: source.norad_id().unwrap_or_default(),
+            tle_line1: tle_line1.to_string(),
+            tle_line2: tle_line2.to_string(),
+            epoch_unix: orbit.epoch_unix,
+            updated_unix: chrono::Utc::now().timestamp_micros() as f64 / 1e6,
+        })
+    }
+}
+
+/// How a registered satellite's element set is chosen for a time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ElementSelection {
+    /// Epoch closest to the time, before or after it
+    #[default]
+    Nearest,
+    /// Latest epoch at or before the time, as known then; the earliest set
+    /// when every epoch is later
+    LatestBefore,
+}
+
+/// Satellites and ground stations by ID; cheap to read concurrently,
+/// entries are shared
+#[derive(Default)]
+pub struct Catalog {
+    state: RwLock<CatalogState>,
+    /// Journal written before each change is applied; without one the
+    /// catalog lives in memory only
+    store: Option<Mutex<Store>>,
+}
+
+impl Catalog {
+    /// Empty catalog kept in memory only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Catalog persisted in `dir`, loaded with everything stored there
+    pub fn open(dir: &Path) -> Result<Self, StoreError> {
+        let (store, changes) = Store::open(dir)?;
+        let mut state = CatalogState::default();
+        for change in changes {
+            state.apply(change);
+        }
+
+        info!(
+            path = %dir.display(),
+            satellites = state.satellites.len(),
+            ground_stations = state.ground_stations.len(),
+            "Loaded catalog"
+        );
+        Ok(Self {
+            state: RwLock::new(state),
+            store: Some(Mutex::new(store)),
+        })
+    }
+
+    /// Initialise SGP4 for every registered satellite so the first requests
+    /// find it cached; returns the number of orbits initialised
+    pub fn warm_cache(&self) -> usize {
+        self.list()
+            .iter()
+            .filter(|entry| {
+                let source = OrbitSource::Tle {
+                    line1: &entry.tle_line1,
+                    line2: &entry.tle_line2,
+                };
+                source.sgp4().is_ok()
+            })
+            .count()
+    }
+
+    /// Insert or replace the entry with the same ID; returns the replaced one
+    pub fn upsert(
+        &self,
+        entry: CatalogEntry,
+    ) -> Result<Option<Arc<CatalogEntry>>, PropagationError> {
+        let entry = Arc::new(entry);
+        self.write(
+            || Change::PutSatellite((*entry).clone()),
+            |state| state.put_satellite(Arc::clone(&entry)),
+        )
+    }
+
+    pub fn get(&self, satellite_id: &str) -> Option<Arc<CatalogEntry>> {
+        let state = self.read();
+        state
+            .satellites
+            .get(satellite_id)
+            .map(|satellite| Arc::clone(&satellite.current))
+    }
+
+    /// Entry for `satellite_id`, or the error reported to clients
+    pub fn lookup(&self, satellite_id: &str) -> Result<Arc<CatalogEntry>, PropagationError> {
+        self.get(satellite_id)
+            .ok_or_else(|| PropagationError::SatelliteNotFound(satellite_id.to_string()))
+    }
+
+    /// Element set of `satellite_id` to propagate to `timestamp_unix` (UTC)
+    pub fn elements_at(
+        &self,
+        satellite_id: &str,
+        timestamp_unix: f64,
+        selection: ElementSelection,
+    ) -> Result<Arc<CatalogEntry>, PropagationError> {
+        let state = self.read();
+        let history = &state
+            .satellites
+            .get(satellite_id)
+            .ok_or_else(|| PropagationError::SatelliteNotFound(satellite_id.to_string()))?
+            .history;
+
+        // Sets at or before the time come first; history is never empty
+        let after = history.partition_point(|set| set.epoch_unix <= timestamp_unix);
+        let index = match (after, selection) {
+            (0, _) => 0,
+            (after, _) if after == history.len() => after - 1,
+            (after, ElementSelection::LatestBefore) => after - 1,
+            (after, ElementSelection::Nearest) => {
+                let before = timestamp_unix - history[after - 1].epoch_unix;
+                let later = history[after].epoch_unix - timestamp_unix;
+                if later < before {
+                    after
+                } else {
+                    after - 1
+                }
+            }
+        };
+        Ok(Arc::clone(&history[index]))
+    }
+
+    /// Every element set registered for `satellite_id`, ordered by epoch
+    pub fn history(&self, satellite_id: &str) -> Result<Vec<Arc<CatalogEntry>>, PropagationError> {
+        let state = self.read();
+        state
+            .satellites
+            .get(satellite_id)
+            .map(|satellite| satellite.history.clone())
+            .ok_or_else(|| PropagationError::SatelliteNotFound(satellite_id.to_string()))
+    }
+
+    /// All entries, ordered by ID
+    pub fn list(&self) -> Vec<Arc<CatalogEntry>> {
+        let state = self.read();
+        state
+            .satellites
+            .values()
+            .map(|satellite| Arc::clone(&satellite.current))
+            .collect()
+    }
+
+    /// Remove a satellite and its history; returns its current entry
+    pub fn remove(
+        &self,
+        satellite_id: &str,
+    ) -> Result<Option<Arc<CatalogEntry>>, PropagationError> {
+        if self.get(satellite_id).is_none() {
+            return Ok(None);
+        }
+        self.write(
+            || Change::DeleteSatellite {
+                satellite_id: satellite_id.to_string(),
+            },
+            |state| state.remove_satellite(satellite_id),
+        )
+    }
+
+    /// Register a validated station, replacing one with the same ID
+    pub fn upsert_ground_station(
+        &self,
+        station: GroundStation,
+    ) -> Result<Option<Arc<GroundStation>>, PropagationError> {
+        if station.id.trim().is_empty() {
+            return Err(PropagationError::InvalidGroundStation(
+                "id cannot be empty".to_string(),
+            ));
+        }
+        station.validate()?;
+
+        let station = Arc::new(station);
+        self.write(
+            || Change::PutGroundStation((*station).clone()),
+            |state| state.put_ground_station(Arc::clone(&station)),
+        )
+    }
+
+    /// Registered station, or the error reported to clients
+    pub fn ground_station(&self, station_id: &str) -> Result<Arc<GroundStation>, PropagationError> {
+        let state = self.read();
+        state
+            .ground_stations
+            .get(station_id)
+            .cloned()
+            .ok_or_else(|| PropagationError::GroundStationNotFound(station_id.to_string()))
+    }
+
+    /// All stations, ordered by ID
+    pub fn ground_stations(&self) -> Vec<Arc<GroundStation>> {
+        self.read().ground_stations.values().cloned().collect()
+    }
+
+    pub fn remove_ground_station(
+        &self,
+        station_id: &str,
+    ) -> Result<Option<Arc<GroundStation>>, PropagationError> {
+        if self.ground_station(station_id).is_err() {
+            return Ok(None);
+        }
+        self.write(
+            || Change::DeleteGroundStation {
+                station_id: station_id.to_string(),
+            },
+            |state| state.remove_ground_station(station_id),
+        )
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, CatalogState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn state_mut(&self) -> RwLockWriteGuard<'_, CatalogState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Journal the change, then `apply` it in memory. The store lock orders
+    /// writers, so memory and disk see changes in the same order.
+    fn write<R>(
+        &self,
+        change: impl FnOnce() -> Change,
+        apply: impl FnOnce(&mut CatalogState) -> R,
+    ) -> Result<R, PropagationError> {
+        let Some(store) = &self.store else {
+            return Ok(apply(&mut self.state_mut()));
+        };
+
+        let mut store = store.lock().unwrap_or_else(|e| e.into_inner());
+        store
+            .append(&change())
+            .map_err(|e| PropagationError::Internal(e.to_string()))?;
+        let result = apply(&mut self.state_mut());
+
+        if store.needs_compaction() {
+            let changes = self.read().changes();
+            // The journal is kept on failure, so nothing is lost
+            if let Err(e) = store.compact(&changes) {
+                warn!("Failed to compact the data store: {}", e);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Run a catalog write off the async runtime; journal writes wait for the
+/// disk
+pub async fn blocking_write<T, F>(write: F) -> Result<T, PropagationError>
+where
+    F: FnOnce() -> Result<T, PropagationError> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(write)
+        .await
+        .unwrap_or_else(|e| Err(PropagationError::Internal(e.to_string())))
+}
+
+#[derive(Default)]
+struct CatalogState {
+    satellites: BTreeMap<String, Registered>,
+    ground_stations: BTreeMap<String, Arc<GroundStation>>,
+}
+
+/// A satellite's newest element set and every set registered for it
+struct Registered {
+    current: Arc<CatalogEntry>,
+    /// Ordered by epoch; a set with the epoch of an earlier one replaces it
+    history: Vec<Arc<CatalogEntry>>,
+}
+
+impl CatalogState {
+    fn apply(&mut self, change: Change) {
+        match change {
+            Change::PutSatellite(entry) => {
+                self.put_satellite(Arc::new(entry));
+            }
+            Change::DeleteSatellite { satellite_id } => {
+                self.remove_satellite(&satellite_id);
+            }
+            Change::PutGroundStation(station) => {
+                self.put_ground_station(Arc::new(station));
+            }
+            Change::DeleteGroundStation { station_id } => {
+                self.remove_ground_station(&station_id);
+            }
+        }
+    }
+
+    fn put_satellite(&mut self, entry: Arc<CatalogEntry>) -> Option<Arc<CatalogEntry>> {
+        match self.satellites.get_mut(&entry.satellite_id) {
+            Some(satellite) => {
+                let history = &mut satellite.history;
+                let index = history.partition_point(|set| set.epoch_unix < entry.epoch_unix);
+                if history
+                    .get(index)
+                    .is_some_and(|set| set.epoch_unix == entry.epoch_unix)
+                {
+                    history[index] = Arc::clone(&entry);
+                } else {
+                    history.insert(index, Arc::clone(&entry));
+                }
+                // A set older than the current one only fills in the history
+                if entry.epoch_unix >= satellite.current.epoch_unix {
+                    Some(std::mem::replace(&mut satellite.current, entry))
+                } else {
+                    Some(Arc::clone(&satellite.current))
+                }
+            }
+            None => {
+                let satellite = Registered {
+                    current: Arc::clone(&entry),
+                    history: vec![Arc::clone(&entry)],
+                };
+                self.satellites
+                    .insert(entry.satellite_id.clone(), satellite);
+                None
+            }
+        }
+    }
+
+    fn remove_satellite(&mut self, satellite_id: &str) -> Option<Arc<CatalogEntry>> {
+        self.satellites
+            .remove(satellite_id)
+            .map(|satellite| satellite.current)
+    }
+
+    fn put_ground_station(&mut self, station: Arc<GroundStation>) -> Option<Arc<GroundStation>> {
+        self.ground_stations.insert(station.id.clone(), station)
+    }
+
+    fn remove_ground_station(&mut self, station_id: &str) -> Option<Arc<GroundStation>> {
+        self.ground_stations.remove(station_id)
+    }
+
+    /// Changes that rebuild this state from empty
+    fn changes(&self) -> Vec<Change> {
+        let mut changes: Vec<Change> = self
+            .satellites
+            .values()
+            .flat_map(|satellite| &satellite.history)
+            .map(|set| Change::PutSatellite((**set).clone()))
+            .collect();
+        changes.extend(
+            self.ground_stations
+                .values()
+                .map(|station| Change::PutGroundStation((**station).clone())),
+        );
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::propagator::ErrorCode;
+
+    const ISS_TLE_LINE1: &str =
+        "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008";
+    const ISS_TLE_LINE2: &str =
+        "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096";
+
+    #[test]
+    fn test_upsert_replaces_by_id() {
+        let catalog = Catalog::new();
+        let iss =
+            CatalogEntry::new("ISS", "ISS (ZARYA)", ISS_TLE_LINE1, ISS_TLE_LINE2, None).unwrap();
+        assert_eq!(iss.norad_id, 25544);
+        assert!((iss.epoch_unix - 1704110400.0).abs() < 1e-3);
+        assert!(catalog.upsert(iss.clone()).unwrap().is_none());
+
+        let renamed = CatalogEntry {
+            name: "ZARYA".to_string(),
+            ..iss
+        };
+        let replaced = catalog.upsert(renamed).unwrap().unwrap();
+        assert_eq!(replaced.name, "ISS (ZARYA)");
+        assert_eq!(catalog.get("ISS").unwrap().name, "ZARYA");
+        assert_eq!(catalog.list().len(), 1);
+
+        assert!(catalog.remove("ISS").unwrap().is_some());
+        assert!(catalog.remove("ISS").unwrap().is_none());
+        let missing = catalog.lookup("ISS").unwrap_err();
+        assert_eq!(missing.code(), ErrorCode::SatelliteNotFound);
+    }
+
+    #[test]
+    fn test_elements_at_selects_from_history() {
+        use ElementSelection::{LatestBefore, Nearest};
+
+        let catalog = Catalog::new();
+        let next_day = "1 25544U 98067A   24002.50000000  .00016717  00000+0  10270-3 0  9009";
+        for line1 in [next_day, ISS_TLE_LINE1] {
+            let entry = CatalogEntry::new("ISS", "", line1, ISS_TLE_LINE2, None).unwrap();
+            catalog.upsert(entry).unwrap();
+        }
+        let epochs: Vec<f64> = catalog
+            .history("ISS")
+            .unwrap()
+            .iter()
+            .map(|set| set.epoch_unix)
+            .collect();
+        assert_eq!(epochs.len(), 2);
+        assert!((epochs[1] - epochs[0] - 86400.0).abs() < 1e-3);
+
+        let epoch_at = |hours: f64, selection| {
+            let time = epochs[0] + hours * 3600.0;
+            catalog
+                .elements_at("ISS", time, selection)
+                .unwrap()
+                .epoch_unix
+        };
+        assert_eq!(epoch_at(-48.0, Nearest), epochs[0]);
+        assert_eq!(epoch_at(-48.0, LatestBefore), epochs[0]);
+        assert_eq!(epoch_at(11.0, Nearest), epochs[0]);
+        assert_eq!(epoch_at(13.0, Nearest), epochs[1]);
+        assert_eq!(epoch_at(13.0, LatestBefore), epochs[0]);
+        assert_eq!(epoch_at(25.0, LatestBefore), epochs[1]);
+        assert_eq!(epoch_at(240.0, Nearest), epochs[1]);
+
+        let missing = catalog.elements_at("HST", epochs[0], Nearest).unwrap_err();
+        assert_eq!(missing.code(), ErrorCode::SatelliteNotFound);
+    }
+
+    #[test]
+    fn test_backfill_keeps_newest_current() {
+        let catalog = Catalog::new();
+        let next_day = "1 25544U 98067A   24002.50000000  .00016717  00000+0  10270-3 0  9009";
+        let newest =
+            CatalogEntry::new("ISS", "ISS (ZARYA)", next_day, ISS_TLE_LINE2, None).unwrap();
+        catalog.upsert(newest).unwrap();
+
+        // An older set joins the history without becoming current
+        let older = CatalogEntry::new("ISS", "", ISS_TLE_LINE1, ISS_TLE_LINE2, None).unwrap();
+        let current = catalog.upsert(older).unwrap().unwrap();
+        assert_eq!(current.tle_line1, next_day);
+        assert_eq!(catalog.get("ISS").unwrap().tle_line1, next_day);
+        assert_eq!(catalog.history("ISS").unwrap().len(), 2);
+
+        // A set with the current epoch still replaces it
+        let renamed = CatalogEntry::new("ISS", "ZARYA", next_day, ISS_TLE_LINE2, None).unwrap();
+        catalog.upsert(renamed).unwrap();
+        assert_eq!(catalog.get("ISS").unwrap().name, "ZARYA");
+        assert_eq!(catalog.history("ISS").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_catalog_survives_reopen() {
+        let dir = std::env::temp_dir().join(format!("orbital-catalog-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let station = GroundStation {
+            id: "SVALBARD".to_string(),
+            name: "Svalbard".to_string(),
+            latitude_deg: 78.23,
+            longitude_deg: 15.39,
+            altitude_m: 500.0,
+            min_elevation_deg: 5.0,
+            horizon_mask: Vec::new(),
+        };
+        let next_day = "1 25544U 98067A   24002.50000000  .00016717  00000+0  10270-3 0  9009";
+
+        let catalog = Catalog::open(&dir).unwrap();
+        for line1 in [next_day, ISS_TLE_LINE1] {
+            let entry = CatalogEntry::new("ISS", "", line1, ISS_TLE_LINE2, None).unwrap();
+            catalog.upsert(entry).unwrap();
+        }
+        let hubble = CatalogEntry {
+            satellite_id: "HST".to_string(),
+            ..(*catalog.get("ISS").unwrap()).clone()
+        };
+        catalog.upsert(hubble).unwrap();
+        catalog.remove("HST").unwrap();
+        catalog.upsert_ground_station(station.clone()).unwrap();
+        drop(catalog);
+
+        let catalog = Catalog::open(&dir).unwrap();
+        assert_eq!(catalog.list().len(), 1);
+        assert_eq!(catalog.get("ISS").unwrap().tle_line1, next_day);
+        assert_eq!(catalog.read().satellites["ISS"].history.len(), 2);
+        assert_eq!(*catalog.ground_station("SVALBARD").unwrap(), station);
+        assert_eq!(catalog.warm_cache(), 1);
+
+        // A snapshot of the state replays to the same state
+        let changes = catalog.read().changes();
+        let mut replayed = CatalogState::default();
+        changes
+            .into_iter()
+            .for_each(|change| replayed.apply(change));
+        assert_eq!(replayed.changes(), catalog.read().changes());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_entries_are_validated() {
+        let bad_checksum = ISS_TLE_LINE1.replace("9008", "9000");
+        let error = CatalogEntry::new("ISS", "", &bad_checksum, ISS_TLE_LINE2, None).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::TleParseError);
+
+        let error =
+            CatalogEntry::new("ISS", "", ISS_TLE_LINE1, ISS_TLE_LINE2, Some("25545")).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::InvalidArgument);
+
+        let error = CatalogEntry::new(" ", "", ISS_TLE_LINE1, ISS_TLE_LINE2, None).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::InvalidArgument);
+    }
+}
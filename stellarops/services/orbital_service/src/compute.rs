@@ -0,0 +1,267 @@
+//! Dedicated worker pool for CPU-bound propagation
+//!
+//! SGP4, pass searches and eclipse scans can take milliseconds to seconds.
+//! Running them inline in async handlers stalls the tokio workers that also
+//! drive the gRPC and HTTP servers, so handlers hand the work to this pool and
+//! await the result instead.
+
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+
+use tokio::sync::{oneshot, Semaphore};
+use tracing::info;
+
+/// Chunks per worker when fanning out a batch; more than one so an expensive
+/// chunk does not leave the other workers idle
+const CHUNKS_PER_THREAD: usize = 4;
+
+/// Jobs queued or running per worker; further callers wait for a slot rather
+/// than growing the queue without bound
+const QUEUE_SLOTS_PER_THREAD: usize = 16;
+/// Items per chunk when streaming results to a client
+pub const STREAM_CHUNK_ITEMS: usize = 1000;
+
+/// Chunks buffered ahead of a slow client before production pauses
+pub const STREAM_BUFFER_CHUNKS: usize = 4;
+
+static POOL: OnceLock<ComputePool> = OnceLock::new();
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Start the shared pool with `threads` workers. Returns false when the pool
+/// is already running; it is started with one worker per core on first use
+/// otherwise.
+pub fn init(threads: usize) -> bool {
+    let mut started = false;
+    POOL.get_or_init(|| {
+        started = true;
+        ComputePool::new(threads)
+    });
+    started
+}
+
+fn pool() -> &'static ComputePool {
+    POOL.get_or_init(|| {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        ComputePool::new(threads)
+    })
+}
+
+/// Run `f` on the shared pool
+pub async fn run<T, F>(f: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    pool().spawn(f).await.await
+}
+
+/// Apply `f` to every item on the shared pool, preserving order
+pub async fn map<I, T, F>(items: Vec<I>, f: F) -> Vec<T>
+where
+    I: Send + 'static,
+    T: Send + 'static,
+    F: Fn(I) -> T + Send + Sync + 'static,
+{
+    pool().map(items, f).await
+}
+
+/// Produce `iter` on the shared pool in chunks of up to `chunk_size` items,
+/// converting each item there, and hand every chunk to `send` on the async
+/// side. Production waits while `send` is pending, so a bounded channel gives
+/// backpressure, and stops once it returns false. The iterator is returned
+/// for its final state.
+pub async fn drain_chunks<I, T, C, S, Fut>(
+    mut iter: I,
+    chunk_size: usize,
+    convert: C,
+    mut send: S,
+) -> I
+where
+    I: Iterator + Send + 'static,
+    T: Send + 'static,
+    C: Fn(I::Item) -> T + Send + Sync + 'static,
+    S: FnMut(Vec<T>) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let convert = Arc::new(convert);
+    loop {
+        let convert = Arc::clone(&convert);
+        let (rest, chunk) = run(move || {
+            let chunk: Vec<T> = iter
+                .by_ref()
+                .take(chunk_size.max(1))
+                .map(&*convert)
+                .collect();
+            (iter, chunk)
+        })
+        .await;
+        iter = rest;
+
+        if chunk.is_empty() || !send(chunk).await {
+            return iter;
+        }
+    }
+}
+
+/// Fixed set of worker threads fed from a shared, bounded queue
+pub struct ComputePool {
+    sender: mpsc::Sender<Job>,
+    slots: Arc<Semaphore>,
+    threads: usize,
+}
+
+impl ComputePool {
+    pub fn new(threads: usize) -> Self {
+        let threads = threads.max(1);
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for index in 0..threads {
+            let receiver = Arc::clone(&receiver);
+            thread::Builder::new()
+                .name(format!("compute-{}", index))
+                .spawn(move || loop {
+                    // The lock is released as soon as a job is taken
+                    let job = match receiver.lock() {
+                        Ok(receiver) => receiver.recv(),
+                        Err(poisoned) => poisoned.into_inner().recv(),
+                    };
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => break,
+                    }
+                })
+                .expect("failed to spawn compute worker");
+        }
+
+        info!("Compute pool started with {} threads", threads);
+        Self {
+            sender,
+            slots: Arc::new(Semaphore::new(threads * QUEUE_SLOTS_PER_THREAD)),
+            threads,
+        }
+    }
+
+    /// Wait for a queue slot and queue `f`; the returned future resolves to
+    /// its result. The job is skipped when that future is dropped before a
+    /// worker picks it up. A panic in `f` is resumed in the awaiting task and
+    /// leaves the worker running.
+    pub async fn spawn<T, F>(&self, f: F) -> impl Future<Output = T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let slot = Arc::clone(&self.slots)
+            .acquire_owned()
+            .await
+            .expect("compute queue is never closed");
+        let (sender, receiver) = oneshot::channel();
+        let job: Job = Box::new(move || {
+            let _slot = slot;
+            if sender.is_closed() {
+                return;
+            }
+            let _ = sender.send(panic::catch_unwind(AssertUnwindSafe(f)));
+        });
+        self.sender.send(job).expect("compute workers have exited");
+
+        async move {
+            match receiver.await.expect("compute job was dropped") {
+                Ok(value) => value,
+                Err(payload) => panic::resume_unwind(payload),
+            }
+        }
+    }
+
+    /// Apply `f` to every item, split into chunks across the workers, and
+    /// return the results in input order
+    pub async fn map<I, T, F>(&self, items: Vec<I>, f: F) -> Vec<T>
+    where
+        I: Send + 'static,
+        T: Send + 'static,
+        F: Fn(I) -> T + Send + Sync + 'static,
+    {
+        let total = items.len();
+        let chunk_size = total.div_ceil(self.threads * CHUNKS_PER_THREAD).max(1);
+        let f = Arc::new(f);
+
+        let mut pending = Vec::new();
+        let mut items = items.into_iter();
+        loop {
+            let chunk: Vec<I> = items.by_ref().take(chunk_size).collect();
+            if chunk.is_empty() {
+                break;
+            }
+            let f = Arc::clone(&f);
+            pending.push(
+                self.spawn(move || chunk.into_iter().map(&*f).collect::<Vec<T>>())
+                    .await,
+            );
+        }
+
+        let mut results = Vec::with_capacity(total);
+        for chunk in pending {
+            results.extend(chunk.await);
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_map_preserves_order() {
+        let pool = ComputePool::new(3);
+        // Earlier items take longer, so completion order differs from input order
+        let items: Vec<u64> = (0..40).collect();
+        let results = pool
+            .map(items, |n| {
+                thread::sleep(Duration::from_micros((40 - n) * 100));
+                (n * n, thread::current().name().map(str::to_string))
+            })
+            .await;
+
+        assert_eq!(results.len(), 40);
+        for (n, (square, worker)) in results.into_iter().enumerate() {
+            assert_eq!(square, (n * n) as u64);
+            assert!(worker.unwrap().starts_with("compute-"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_panic_reaches_caller() {
+        let pool = ComputePool::new(1);
+        let failed = tokio::spawn(pool.spawn(|| -> u32 { panic!("bad orbit") }).await).await;
+        assert!(failed.unwrap_err().is_panic());
+
+        // The worker survives the panic
+        assert_eq!(pool.spawn(|| 7).await.await, 7);
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_jobs_are_skipped() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let pool = ComputePool::new(1);
+        let (release, blocked) = mpsc::channel::<()>();
+        let busy = pool.spawn(move || blocked.recv().unwrap()).await;
+
+        // Queued behind the busy worker, then abandoned by its caller
+        let ran = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&ran);
+        drop(pool.spawn(move || flag.store(true, Ordering::SeqCst)).await);
+
+        release.send(()).unwrap();
+        busy.await;
+        assert_eq!(pool.spawn(|| 7).await.await, 7);
+        assert!(!ran.load(Ordering::SeqCst));
+        assert_eq!(pool.slots.available_permits(), QUEUE_SLOTS_PER_THREAD);
+    }
+}
@@ -0,0 +1,280 @@
+//! Earth Orientation Parameters (UT1-UTC and polar motion)
+//!
+//! Loads IERS `finals2000A` files and interpolates daily values for frame
+//! conversions. Times outside the loaded table fall back to UT1 = UTC and no
+//! polar motion; callers report that through a warning flag.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use lazy_static::lazy_static;
+use tracing::info;
+
+/// Modified Julian date at Unix epoch (1970-01-01 00:00:00 UTC)
+const MJD_UNIX_EPOCH: f64 = 40587.0;
+
+lazy_static! {
+    static ref EOP_STATE: RwLock<EopState> = RwLock::new(EopState::default());
+}
+
+#[derive(Default)]
+struct EopState {
+    path: Option<PathBuf>,
+    table: Option<Arc<EopTable>>,
+}
+
+/// Earth orientation at an instant
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EarthOrientation {
+    pub ut1_minus_utc_s: f64,
+    pub x_pole_arcsec: f64,
+    pub y_pole_arcsec: f64,
+}
+
+/// Daily EOP record (0h UTC)
+#[derive(Debug, Clone, Copy)]
+pub struct EopEntry {
+    pub mjd: f64,
+    pub orientation: EarthOrientation,
+}
+
+/// Parsed EOP table, sorted by date
+#[derive(Debug, Clone)]
+pub struct EopTable {
+    entries: Vec<EopEntry>,
+}
+
+impl EopTable {
+    /// Parse the fixed-width IERS `finals2000A` format.
+    ///
+    /// Rows without polar motion or UT1-UTC (far predictions) are skipped.
+    pub fn parse_finals(text: &str) -> Result<Self, EopError> {
+        let mut entries: Vec<EopEntry> = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let line_number = index + 1;
+            let mjd = fixed_field(line, 7, 15)
+                .ok_or_else(|| EopError::Parse {
+                    line: line_number,
+                    message: "missing MJD (columns 8-15)".to_string(),
+                })?
+                .parse::<f64>()
+                .map_err(|e| EopError::Parse {
+                    line: line_number,
+                    message: format!("invalid MJD: {}", e),
+                })?;
+
+            let (Some(x_pole), Some(y_pole), Some(ut1_utc)) = (
+                fixed_field(line, 18, 27),
+                fixed_field(line, 37, 46),
+                fixed_field(line, 58, 68),
+            ) else {
+                continue;
+            };
+
+            let parse = |field: &str, name: &str| {
+                field.parse::<f64>().map_err(|e| EopError::Parse {
+                    line: line_number,
+                    message: format!("invalid {}: {}", name, e),
+                })
+            };
+
+            let orientation = EarthOrientation {
+                x_pole_arcsec: parse(x_pole, "PM-x")?,
+                y_pole_arcsec: parse(y_pole, "PM-y")?,
+                ut1_minus_utc_s: parse(ut1_utc, "UT1-UTC")?,
+            };
+
+            if let Some(last) = entries.last() {
+                if mjd <= last.mjd {
+                    return Err(EopError::Parse {
+                        line: line_number,
+                        message: format!("MJD {} is not after {}", mjd, last.mjd),
+                    });
+                }
+            }
+
+            entries.push(EopEntry { mjd, orientation });
+        }
+
+        if entries.is_empty() {
+            return Err(EopError::Empty);
+        }
+
+        Ok(Self { entries })
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// First and last covered Unix times
+    pub fn coverage_unix(&self) -> (f64, f64) {
+        let first = self.entries.first().map_or(0.0, |e| e.mjd);
+        let last = self.entries.last().map_or(0.0, |e| e.mjd);
+        (mjd_to_unix(first), mjd_to_unix(last))
+    }
+
+    /// Linearly interpolated orientation, or None outside the table
+    pub fn interpolate(&self, timestamp_unix: f64) -> Option<EarthOrientation> {
+        let mjd = MJD_UNIX_EPOCH + timestamp_unix / 86400.0;
+
+        let upper = self.entries.partition_point(|e| e.mjd < mjd);
+        if upper == 0 {
+            return (self.entries[0].mjd == mjd).then_some(self.entries[0].orientation);
+        }
+        if upper == self.entries.len() {
+            return None;
+        }
+
+        let before = &self.entries[upper - 1];
+        let after = &self.entries[upper];
+        let fraction = (mjd - before.mjd) / (after.mjd - before.mjd);
+        let lerp = |a: f64, b: f64| a + (b - a) * fraction;
+
+        // Remove a leap second step so UT1-UTC is continuous across the interval
+        let mut ut1_after = after.orientation.ut1_minus_utc_s;
+        let step = ut1_after - before.orientation.ut1_minus_utc_s;
+        if step > 0.5 {
+            ut1_after -= 1.0;
+        } else if step < -0.5 {
+            ut1_after += 1.0;
+        }
+
+        Some(EarthOrientation {
+            ut1_minus_utc_s: lerp(before.orientation.ut1_minus_utc_s, ut1_after),
+            x_pole_arcsec: lerp(before.orientation.x_pole_arcsec, after.orientation.x_pole_arcsec),
+            y_pole_arcsec: lerp(before.orientation.y_pole_arcsec, after.orientation.y_pole_arcsec),
+        })
+    }
+}
+
+/// Load an EOP file and make it the active table; the path is kept for reloads
+pub fn load_file(path: &Path) -> Result<usize, EopError> {
+    let table = read_table(path)?;
+    let entries = table.entry_count();
+
+    let mut state = EOP_STATE.write().unwrap_or_else(|e| e.into_inner());
+    state.path = Some(path.to_path_buf());
+    state.table = Some(Arc::new(table));
+
+    info!(path = %path.display(), entries, "Loaded EOP table");
+    Ok(entries)
+}
+
+/// Re-read the configured EOP file; the previous table stays active on error
+pub fn reload() -> Result<usize, EopError> {
+    let path = {
+        let state = EOP_STATE.read().unwrap_or_else(|e| e.into_inner());
+        state.path.clone().ok_or(EopError::NotConfigured)?
+    };
+    load_file(&path)
+}
+
+/// Currently active table, if any
+pub fn current_table() -> Option<Arc<EopTable>> {
+    EOP_STATE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .table
+        .clone()
+}
+
+/// Orientation at the given time, or the UT1 = UTC / zero polar motion fallback
+pub fn orientation_at(timestamp_unix: f64) -> EarthOrientation {
+    current_table()
+        .and_then(|table| table.interpolate(timestamp_unix))
+        .unwrap_or_default()
+}
+
+/// Whether the active table covers the whole interval
+pub fn covers(start_unix: f64, end_unix: f64) -> bool {
+    current_table().is_some_and(|table| {
+        table.interpolate(start_unix).is_some() && table.interpolate(end_unix).is_some()
+    })
+}
+
+fn read_table(path: &Path) -> Result<EopTable, EopError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| EopError::Io(format!("{}: {}", path.display(), e)))?;
+    EopTable::parse_finals(&text)
+}
+
+/// Trimmed fixed-width field (0-based, end exclusive), None when blank
+fn fixed_field(line: &str, start: usize, end: usize) -> Option<&str> {
+    let field = line.get(start..end.min(line.len()))?.trim();
+    (!field.is_empty()).then_some(field)
+}
+
+fn mjd_to_unix(mjd: f64) -> f64 {
+    (mjd - MJD_UNIX_EPOCH) * 86400.0
+}
+
+/// EOP loading errors
+#[derive(Debug, Clone)]
+pub enum EopError {
+    Io(String),
+    Parse { line: usize, message: String },
+    Empty,
+    NotConfigured,
+}
+
+impl std::fmt::Display for EopError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EopError::Io(msg) => write!(f, "EOP file error: {}", msg),
+            EopError::Parse { line, message } => write!(f, "EOP parse error on line {}: {}", line, message),
+            EopError::Empty => write!(f, "EOP file contains no usable entries"),
+            EopError::NotConfigured => write!(f, "No EOP file configured (set EOP_FILE)"),
+        }
+    }
+}
+
+impl std::error::Error for EopError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // finals2000A rows around the 2016-12-31 leap second
+    const FINALS: &str = "\
+161230 57752.00 I  0.030044 0.000029  0.275936 0.000031  I-0.4078016 0.0000058  0.7780 0.0048  I     0.188    0.279     0.079    0.140  0.030046  0.275933 -0.4078027     0.173     0.073
+161231 57753.00 I  0.029143 0.000028  0.276634 0.000032  I-0.4083895 0.0000069  0.5939 0.0049  I     0.185    0.279     0.081    0.140  0.029144  0.276630 -0.4083904     0.171     0.076
+170101 57754.00 I  0.028140 0.000029  0.277378 0.000031  I 0.5911416 0.0000068  0.6071 0.0043  I     0.182    0.278     0.084    0.141  0.028138  0.277373  0.5911413     0.169     0.079
+170102 57755.00 P  0.027300           0.277900           P
+";
+
+    #[test]
+    fn test_parse_finals_skips_rows_without_values() {
+        let table = EopTable::parse_finals(FINALS).unwrap();
+        assert_eq!(table.entry_count(), 3);
+        assert_eq!(table.coverage_unix(), (mjd_to_unix(57752.0), mjd_to_unix(57754.0)));
+    }
+
+    #[test]
+    fn test_interpolation_across_leap_second() {
+        let table = EopTable::parse_finals(FINALS).unwrap();
+
+        let at_entry = table.interpolate(mjd_to_unix(57753.0)).unwrap();
+        assert!((at_entry.ut1_minus_utc_s + 0.4083895).abs() < 1e-9);
+
+        // Midday before the leap second stays continuous with the previous day
+        let midday = table.interpolate(mjd_to_unix(57753.5)).unwrap();
+        assert!((midday.ut1_minus_utc_s + 0.40862).abs() < 1e-4, "got {}", midday.ut1_minus_utc_s);
+        assert!((midday.x_pole_arcsec - 0.0286415).abs() < 1e-6);
+
+        assert!(table.interpolate(mjd_to_unix(57751.0)).is_none());
+        assert!(table.interpolate(mjd_to_unix(57754.5)).is_none());
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_rows() {
+        let err = EopTable::parse_finals("161230 5775x.00 I  0.030044").unwrap_err();
+        assert!(matches!(err, EopError::Parse { line: 1, .. }));
+        assert!(matches!(EopTable::parse_finals("\n"), Err(EopError::Empty)));
+    }
+}
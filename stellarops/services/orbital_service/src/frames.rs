@@ -0,0 +1,381 @@
+//! Reference frame transformations for SGP4 state vectors
+//!
+//! SGP4 produces states in TEME (True Equator, Mean Equinox). Earth-fixed
+//! frames follow TEME -> PEF -> ITRF (GMST rotation, then polar motion) and
+//! inertial frames follow TEME -> TOD -> MOD -> J2000 -> GCRF (equation of the
+//! equinoxes, IAU-1980 nutation, IAU-1976 precession, frame bias).
+//! Earth-fixed conversions use UT1-UTC and polar motion from the EOP table.
+
+use crate::eop::{self, EarthOrientation};
+use crate::timescale::{self, TimeScale};
+
+/// Julian date at Unix epoch (1970-01-01 00:00:00 UTC)
+const JD_UNIX_EPOCH: f64 = 2440587.5;
+
+/// Julian date of the J2000.0 epoch
+const JD_J2000: f64 = 2451545.0;
+
+/// Arcseconds to radians
+const ARCSEC_TO_RAD: f64 = std::f64::consts::PI / (180.0 * 3600.0);
+
+/// Earth rotation rate in rad/s
+const EARTH_ROTATION_RAD_S: f64 = 7.292_115_146_706_979e-5;
+
+/// Output reference frame for propagated states
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Frame {
+    /// True Equator, Mean Equinox - native SGP4 output
+    #[default]
+    Teme,
+    /// Pseudo Earth-Fixed (TEME rotated by GMST, no polar motion)
+    Pef,
+    /// International Terrestrial Reference Frame (Earth-fixed)
+    Itrf,
+    /// Mean equator and equinox of J2000.0 (EME2000)
+    J2000,
+    /// Geocentric Celestial Reference Frame
+    Gcrf,
+}
+
+impl Frame {
+    /// Whether the transformation needs UT1-UTC or polar motion from EOP data
+    pub fn uses_eop(self) -> bool {
+        matches!(self, Frame::Pef | Frame::Itrf)
+    }
+}
+
+type Matrix3 = [[f64; 3]; 3];
+
+/// Greenwich Mean Sidereal Time (IAU-1982) in radians, from a UT1 Unix time
+pub fn gmst(timestamp_unix: f64) -> f64 {
+    // Convert Unix timestamp to Julian date
+    let jd = JD_UNIX_EPOCH + (timestamp_unix / 86400.0);
+
+    // Julian centuries from J2000.0
+    let t = (jd - JD_J2000) / 36525.0;
+
+    // GMST in degrees
+    let gmst_deg = 280.46061837
+        + 360.98564736629 * (jd - JD_J2000)
+        + 0.000387933 * t * t
+        - t * t * t / 38710000.0;
+
+    // Normalize to [0, 360)
+    gmst_deg.rem_euclid(360.0).to_radians()
+}
+
+/// Transform a TEME state into `frame`; velocities in Earth-fixed frames are
+/// relative to the rotating Earth
+pub fn teme_to_frame(
+    frame: Frame,
+    position: &[f64; 3],
+    velocity: &[f64; 3],
+    timestamp_unix: f64,
+) -> ([f64; 3], [f64; 3]) {
+    let orientation = match frame {
+        Frame::Pef | Frame::Itrf => eop::orientation_at(timestamp_unix),
+        _ => EarthOrientation::default(),
+    };
+    teme_to_frame_with(frame, position, velocity, timestamp_unix, &orientation)
+}
+
+/// [`teme_to_frame`] with explicit Earth orientation parameters
+pub fn teme_to_frame_with(
+    frame: Frame,
+    position: &[f64; 3],
+    velocity: &[f64; 3],
+    timestamp_unix: f64,
+    orientation: &EarthOrientation,
+) -> ([f64; 3], [f64; 3]) {
+    match frame {
+        Frame::Teme => (*position, *velocity),
+        Frame::Pef | Frame::Itrf => {
+            let rotation = rot3(gmst(timestamp_unix + orientation.ut1_minus_utc_s));
+            let r_pef = mat_vec(&rotation, position);
+            let v_rotated = mat_vec(&rotation, velocity);
+
+            // Remove the frame rotation (v - w x r)
+            let v_pef = [
+                v_rotated[0] + EARTH_ROTATION_RAD_S * r_pef[1],
+                v_rotated[1] - EARTH_ROTATION_RAD_S * r_pef[0],
+                v_rotated[2],
+            ];
+
+            if frame == Frame::Pef {
+                return (r_pef, v_pef);
+            }
+
+            let polar_motion = transpose(&polar_motion_matrix(
+                orientation.x_pole_arcsec,
+                orientation.y_pole_arcsec,
+            ));
+            (mat_vec(&polar_motion, &r_pef), mat_vec(&polar_motion, &v_pef))
+        }
+        Frame::J2000 | Frame::Gcrf => {
+            let rotation = teme_to_inertial_matrix(frame, timestamp_unix);
+            (mat_vec(&rotation, position), mat_vec(&rotation, velocity))
+        }
+    }
+}
+
+/// Rotate a TEME position into ITRF
+pub fn teme_to_itrf(position: &[f64; 3], timestamp_unix: f64) -> [f64; 3] {
+    teme_to_frame(Frame::Itrf, position, &[0.0; 3], timestamp_unix).0
+}
+
+/// Rotate an ITRF position into TEME
+pub fn itrf_to_teme(position: &[f64; 3], timestamp_unix: f64) -> [f64; 3] {
+    let orientation = eop::orientation_at(timestamp_unix);
+    let r_pef = mat_vec(
+        &polar_motion_matrix(orientation.x_pole_arcsec, orientation.y_pole_arcsec),
+        position,
+    );
+    mat_vec(
+        &transpose(&rot3(gmst(timestamp_unix + orientation.ut1_minus_utc_s))),
+        &r_pef,
+    )
+}
+
+/// Polar motion matrix W with r_pef = W r_itrf (pole offsets in arcseconds)
+fn polar_motion_matrix(x_pole_arcsec: f64, y_pole_arcsec: f64) -> Matrix3 {
+    mat_mul(
+        &rot2(x_pole_arcsec * ARCSEC_TO_RAD),
+        &rot1(y_pole_arcsec * ARCSEC_TO_RAD),
+    )
+}
+
+/// Rotation from TEME to J2000 or GCRF
+fn teme_to_inertial_matrix(frame: Frame, timestamp_unix: f64) -> Matrix3 {
+    let jd_tt = JD_UNIX_EPOCH + timescale::from_utc(timestamp_unix, TimeScale::Tt) / 86400.0;
+    let t = (jd_tt - JD_J2000) / 36525.0;
+
+    let (delta_psi, delta_epsilon, mean_obliquity, moon_node) = nutation(t);
+    let true_obliquity = mean_obliquity + delta_epsilon;
+
+    // TEME -> TOD: equation of the equinoxes (with the 1994 kinematic terms)
+    let equation_of_equinoxes = delta_psi * mean_obliquity.cos()
+        + (0.00264 * moon_node.sin() + 0.000063 * (2.0 * moon_node).sin()) * ARCSEC_TO_RAD;
+    let teme_to_tod = rot3(-equation_of_equinoxes);
+
+    // TOD -> MOD: transpose of N = R1(-eps) R3(-dpsi) R1(eps_mean)
+    let nutation_matrix = mat_mul(
+        &rot1(-true_obliquity),
+        &mat_mul(&rot3(-delta_psi), &rot1(mean_obliquity)),
+    );
+
+    // MOD -> J2000: transpose of P = R3(-z) R2(theta) R3(-zeta)
+    let zeta = (2306.2181 * t + 0.30188 * t * t + 0.017998 * t * t * t) * ARCSEC_TO_RAD;
+    let theta = (2004.3109 * t - 0.42665 * t * t - 0.041833 * t * t * t) * ARCSEC_TO_RAD;
+    let z = (2306.2181 * t + 1.09468 * t * t + 0.018203 * t * t * t) * ARCSEC_TO_RAD;
+    let precession_matrix = mat_mul(&rot3(-z), &mat_mul(&rot2(theta), &rot3(-zeta)));
+
+    let teme_to_j2000 = mat_mul(
+        &transpose(&precession_matrix),
+        &mat_mul(&transpose(&nutation_matrix), &teme_to_tod),
+    );
+
+    match frame {
+        Frame::Gcrf => mat_mul(&frame_bias_j2000_to_gcrf(), &teme_to_j2000),
+        _ => teme_to_j2000,
+    }
+}
+
+/// Frame bias from the dynamical J2000 frame to GCRF (IERS 2003 offsets)
+fn frame_bias_j2000_to_gcrf() -> Matrix3 {
+    let d_alpha = -0.0146 * ARCSEC_TO_RAD;
+    let xi = -0.016617 * ARCSEC_TO_RAD;
+    let eta = -0.0068192 * ARCSEC_TO_RAD;
+
+    // Transpose of the first-order bias matrix B (r_j2000 = B r_gcrf)
+    [
+        [1.0, -d_alpha, xi],
+        [d_alpha, 1.0, eta],
+        [-xi, -eta, 1.0],
+    ]
+}
+
+/// IAU-1980 nutation terms larger than 1 mas:
+/// multipliers of (l, l', F, D, Omega), then dpsi and deps coefficients in
+/// 0.0001 arcsec as (constant, per Julian century)
+#[rustfmt::skip]
+const NUTATION_TERMS: [([f64; 5], [f64; 4]); 33] = [
+    ([ 0.0,  0.0, 0.0,  0.0, 1.0], [-171996.0, -174.2, 92025.0,  8.9]),
+    ([ 0.0,  0.0, 2.0, -2.0, 2.0], [ -13187.0,   -1.6,  5736.0, -3.1]),
+    ([ 0.0,  0.0, 2.0,  0.0, 2.0], [  -2274.0,   -0.2,   977.0, -0.5]),
+    ([ 0.0,  0.0, 0.0,  0.0, 2.0], [   2062.0,    0.2,  -895.0,  0.5]),
+    ([ 0.0,  1.0, 0.0,  0.0, 0.0], [   1426.0,   -3.4,    54.0, -0.1]),
+    ([ 1.0,  0.0, 0.0,  0.0, 0.0], [    712.0,    0.1,    -7.0,  0.0]),
+    ([ 0.0,  1.0, 2.0, -2.0, 2.0], [   -517.0,    1.2,   224.0, -0.6]),
+    ([ 0.0,  0.0, 2.0,  0.0, 1.0], [   -386.0,   -0.4,   200.0,  0.0]),
+    ([ 1.0,  0.0, 2.0,  0.0, 2.0], [   -301.0,    0.0,   129.0, -0.1]),
+    ([ 0.0, -1.0, 2.0, -2.0, 2.0], [    217.0,   -0.5,   -95.0,  0.3]),
+    ([ 1.0,  0.0, 0.0, -2.0, 0.0], [   -158.0,    0.0,     0.0,  0.0]),
+    ([ 0.0,  0.0, 2.0, -2.0, 1.0], [    129.0,    0.1,   -70.0,  0.0]),
+    ([-1.0,  0.0, 2.0,  0.0, 2.0], [    123.0,    0.0,   -53.0,  0.0]),
+    ([ 0.0,  0.0, 0.0,  2.0, 0.0], [     63.0,    0.0,     0.0,  0.0]),
+    ([ 1.0,  0.0, 0.0,  0.0, 1.0], [     63.0,    0.1,   -33.0,  0.0]),
+    ([-1.0,  0.0, 2.0,  2.0, 2.0], [    -59.0,    0.0,    26.0,  0.0]),
+    ([-1.0,  0.0, 0.0,  0.0, 1.0], [    -58.0,   -0.1,    32.0,  0.0]),
+    ([ 1.0,  0.0, 2.0,  0.0, 1.0], [    -51.0,    0.0,    27.0,  0.0]),
+    ([ 2.0,  0.0, 0.0, -2.0, 0.0], [     48.0,    0.0,     0.0,  0.0]),
+    ([-2.0,  0.0, 2.0,  0.0, 1.0], [     46.0,    0.0,   -24.0,  0.0]),
+    ([ 0.0,  0.0, 2.0,  2.0, 2.0], [    -38.0,    0.0,    16.0,  0.0]),
+    ([ 2.0,  0.0, 2.0,  0.0, 2.0], [    -31.0,    0.0,    13.0,  0.0]),
+    ([ 2.0,  0.0, 0.0,  0.0, 0.0], [     29.0,    0.0,     0.0,  0.0]),
+    ([ 1.0,  0.0, 2.0, -2.0, 2.0], [     29.0,    0.0,   -12.0,  0.0]),
+    ([ 0.0,  0.0, 2.0,  0.0, 0.0], [     26.0,    0.0,     0.0,  0.0]),
+    ([ 0.0,  0.0, 2.0, -2.0, 0.0], [    -22.0,    0.0,     0.0,  0.0]),
+    ([-1.0,  0.0, 2.0,  0.0, 1.0], [     21.0,    0.0,   -10.0,  0.0]),
+    ([ 0.0,  2.0, 0.0,  0.0, 0.0], [     17.0,   -0.1,     0.0,  0.0]),
+    ([-1.0,  0.0, 0.0,  2.0, 1.0], [     16.0,    0.0,    -8.0,  0.0]),
+    ([ 0.0,  2.0, 2.0, -2.0, 2.0], [    -16.0,    0.1,     7.0,  0.0]),
+    ([ 0.0,  1.0, 0.0,  0.0, 1.0], [    -15.0,    0.0,     9.0,  0.0]),
+    ([ 1.0,  0.0, 0.0, -2.0, 1.0], [    -13.0,    0.0,     7.0,  0.0]),
+    ([ 0.0, -1.0, 0.0,  0.0, 1.0], [    -12.0,    0.0,     6.0,  0.0]),
+];
+
+/// Nutation in longitude and obliquity, mean obliquity and the lunar node
+/// longitude (all radians) at `t` Julian centuries TT from J2000.0
+fn nutation(t: f64) -> (f64, f64, f64, f64) {
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    // Delaunay arguments (degrees)
+    let l = 134.96298 + 477198.867398 * t + 0.0086972 * t2 + t3 / 56250.0;
+    let l_prime = 357.52772 + 35999.050340 * t - 0.0001603 * t2 - t3 / 300000.0;
+    let f = 93.27191 + 483202.017538 * t - 0.0036825 * t2 + t3 / 327270.0;
+    let d = 297.85036 + 445267.111480 * t - 0.0019142 * t2 + t3 / 189474.0;
+    let omega = 125.04452 - 1934.136261 * t + 0.0020708 * t2 + t3 / 450000.0;
+    let arguments = [l, l_prime, f, d, omega].map(|angle: f64| angle.to_radians());
+
+    let mut delta_psi = 0.0;
+    let mut delta_epsilon = 0.0;
+    for (multipliers, coefficients) in NUTATION_TERMS.iter() {
+        let argument: f64 = multipliers
+            .iter()
+            .zip(arguments.iter())
+            .map(|(multiplier, angle)| multiplier * angle)
+            .sum();
+        delta_psi += (coefficients[0] + coefficients[1] * t) * argument.sin();
+        delta_epsilon += (coefficients[2] + coefficients[3] * t) * argument.cos();
+    }
+
+    let mean_obliquity = (84381.448 - 46.8150 * t - 0.00059 * t2 + 0.001813 * t3) * ARCSEC_TO_RAD;
+
+    (
+        delta_psi * 1e-4 * ARCSEC_TO_RAD,
+        delta_epsilon * 1e-4 * ARCSEC_TO_RAD,
+        mean_obliquity,
+        arguments[4],
+    )
+}
+
+fn rot1(angle: f64) -> Matrix3 {
+    let (s, c) = angle.sin_cos();
+    [[1.0, 0.0, 0.0], [0.0, c, s], [0.0, -s, c]]
+}
+
+fn rot2(angle: f64) -> Matrix3 {
+    let (s, c) = angle.sin_cos();
+    [[c, 0.0, -s], [0.0, 1.0, 0.0], [s, 0.0, c]]
+}
+
+fn rot3(angle: f64) -> Matrix3 {
+    let (s, c) = angle.sin_cos();
+    [[c, s, 0.0], [-s, c, 0.0], [0.0, 0.0, 1.0]]
+}
+
+fn mat_mul(a: &Matrix3, b: &Matrix3) -> Matrix3 {
+    let mut result = [[0.0; 3]; 3];
+    for (i, row) in result.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate() {
+            *cell = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    result
+}
+
+fn mat_vec(m: &Matrix3, v: &[f64; 3]) -> [f64; 3] {
+    [
+        m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+        m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+        m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+    ]
+}
+
+fn transpose(m: &Matrix3) -> Matrix3 {
+    [
+        [m[0][0], m[1][0], m[2][0]],
+        [m[0][1], m[1][1], m[2][1]],
+        [m[0][2], m[1][2], m[2][2]],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Vallado, "Revisiting Spacetrack Report #3" (AIAA 2006-6753), example
+    // state at 2004-04-06 07:51:28.386009 UTC
+    const EPOCH_UNIX: f64 = 1081237888.386009;
+    const R_TEME: [f64; 3] = [5094.18016210, 6127.64465950, 6380.34453270];
+    const V_TEME: [f64; 3] = [-4.746131487, 0.785818041, 5.531931288];
+    const R_PEF: [f64; 3] = [-1033.47503130, 7901.30558560, 6380.34453270];
+    const R_ITRF: [f64; 3] = [-1033.4793830, 7901.2952754, 6380.3565958];
+    const R_J2000: [f64; 3] = [5102.50895790, 6123.01140070, 6378.13692820];
+    const ORIENTATION: EarthOrientation = EarthOrientation {
+        ut1_minus_utc_s: -0.4399619,
+        x_pole_arcsec: -0.140682,
+        y_pole_arcsec: 0.333309,
+    };
+
+    fn distance(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+        ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn test_teme_to_earth_fixed_matches_reference() {
+        let (r_pef, _) = teme_to_frame_with(Frame::Pef, &R_TEME, &V_TEME, EPOCH_UNIX, &ORIENTATION);
+        assert!(distance(&r_pef, &R_PEF) < 1e-3, "got {:?}", r_pef);
+
+        let (r_itrf, _) = teme_to_frame_with(Frame::Itrf, &R_TEME, &V_TEME, EPOCH_UNIX, &ORIENTATION);
+        assert!(distance(&r_itrf, &R_ITRF) < 1e-3, "got {:?}", r_itrf);
+
+        // Without EOP (UT1 = UTC) the Earth-fixed position is off by ~0.3 km
+        let (r_fallback, _) = teme_to_frame_with(
+            Frame::Itrf,
+            &R_TEME,
+            &V_TEME,
+            EPOCH_UNIX,
+            &EarthOrientation::default(),
+        );
+        assert!(distance(&r_fallback, &R_ITRF) > 0.1);
+    }
+
+    #[test]
+    fn test_teme_to_j2000_matches_reference() {
+        let (r_j2000, _) = teme_to_frame(Frame::J2000, &R_TEME, &V_TEME, EPOCH_UNIX);
+        assert!(distance(&r_j2000, &R_J2000) < 0.02, "got {:?}", r_j2000);
+
+        // GCRF differs from J2000 by the frame bias only (tens of mas)
+        let (r_gcrf, _) = teme_to_frame(Frame::Gcrf, &R_TEME, &V_TEME, EPOCH_UNIX);
+        let offset = distance(&r_gcrf, &r_j2000);
+        assert!(offset > 0.0 && offset < 0.01, "got {}", offset);
+    }
+
+    #[test]
+    fn test_rotations_preserve_magnitude() {
+        let magnitude = distance(&R_TEME, &[0.0; 3]);
+        for frame in [Frame::Teme, Frame::Pef, Frame::Itrf, Frame::J2000, Frame::Gcrf] {
+            let (position, _) = teme_to_frame_with(frame, &R_TEME, &V_TEME, EPOCH_UNIX, &ORIENTATION);
+            assert!((distance(&position, &[0.0; 3]) - magnitude).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_itrf_round_trip() {
+        let r_itrf = teme_to_itrf(&R_TEME, EPOCH_UNIX);
+        let r_teme = itrf_to_teme(&r_itrf, EPOCH_UNIX);
+        assert!(distance(&r_teme, &R_TEME) < 1e-6);
+    }
+}
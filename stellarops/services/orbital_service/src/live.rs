@@ -0,0 +1,273 @@
+//! Live position feeds
+//!
+//! The gRPC LiveFeed stream and the HTTP Server-Sent Events endpoint serve
+//! the same sessions: a set of subscribed orbits, an output frame and an
+//! update interval. Each transport applies its client's commands to a
+//! `LiveSession`, restarts its ticker when the interval changes and formats
+//! the positions produced on every tick.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio::time::{Interval, MissedTickBehavior};
+
+use crate::compute;
+use crate::frames::Frame;
+use crate::propagator::{self, OrbitSource, OwnedOrbitSource, PropagationError, PropagationResult};
+
+/// Time between updates unless the client sets one
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Shortest and longest update intervals; requests outside are clamped
+pub const MIN_INTERVAL: Duration = Duration::from_millis(100);
+pub const MAX_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Satellites one session may subscribe, to prevent DoS
+pub const MAX_SATELLITES: usize = 10_000;
+
+/// Sessions open at once over HTTP
+pub const MAX_SESSIONS: usize = 1_000;
+
+/// Refusals held for the next gRPC update; later ones are dropped
+pub const MAX_REJECTED: usize = 100;
+
+/// Ticks start immediately; a slow client delays later ones rather than
+/// receiving a burst
+pub fn ticker(interval: Duration) -> Interval {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    ticker
+}
+
+/// Orbit of a subscribed satellite, parsed when it was added
+pub struct LiveOrbit {
+    pub norad_id: Option<u64>,
+    pub orbit: OwnedOrbitSource,
+}
+
+impl LiveOrbit {
+    /// Validate the orbit and initialise SGP4, so a bad orbit is refused once
+    /// rather than failing every update. Run it on the compute pool; the
+    /// constants stay cached for the updates.
+    pub fn new(source: OrbitSource<'_>) -> Result<Self, PropagationError> {
+        source.sgp4()?;
+        Ok(Self {
+            norad_id: source.norad_id(),
+            orbit: OwnedOrbitSource::from(source),
+        })
+    }
+}
+
+/// Subscriptions and settings of one live feed, shared by the task producing
+/// its updates and whatever applies the client's commands
+pub struct LiveSession {
+    subscriptions: Mutex<Subscriptions>,
+    // The producing task restarts its ticker when this changes
+    interval: watch::Sender<Duration>,
+}
+
+struct Subscriptions {
+    satellites: BTreeMap<String, Arc<LiveOrbit>>,
+    frame: Frame,
+}
+
+impl LiveSession {
+    pub fn new() -> Self {
+        Self {
+            subscriptions: Mutex::new(Subscriptions {
+                satellites: BTreeMap::new(),
+                frame: Frame::default(),
+            }),
+            interval: watch::Sender::new(DEFAULT_INTERVAL),
+        }
+    }
+
+    // Subscriptions are only read or replaced under the lock, so a panicking
+    // holder cannot leave them half-written
+    fn lock(&self) -> MutexGuard<'_, Subscriptions> {
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Receiver notified of interval changes, starting at the current one
+    pub fn interval_changes(&self) -> watch::Receiver<Duration> {
+        self.interval.subscribe()
+    }
+
+    pub fn interval(&self) -> Duration {
+        *self.interval.borrow()
+    }
+
+    /// Set the update interval, clamped to the allowed range; returns true
+    /// when it changed
+    pub fn set_interval(&self, interval: Duration) -> bool {
+        let interval = interval.clamp(MIN_INTERVAL, MAX_INTERVAL);
+        self.interval.send_if_modified(|current| {
+            let changed = *current != interval;
+            *current = interval;
+            changed
+        })
+    }
+
+    pub fn frame(&self) -> Frame {
+        self.lock().frame
+    }
+
+    pub fn set_frame(&self, frame: Frame) {
+        self.lock().frame = frame;
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().satellites.len()
+    }
+
+    /// Refuse a change that would leave more than `MAX_SATELLITES` subscribed,
+    /// before any of its orbits are parsed
+    pub fn check_capacity<'a>(
+        &self,
+        add: impl IntoIterator<Item = &'a str>,
+        remove: impl IntoIterator<Item = &'a str>,
+    ) -> Result<(), PropagationError> {
+        let subscriptions = self.lock();
+        let mut satellite_ids: BTreeSet<&str> = subscriptions
+            .satellites
+            .keys()
+            .map(String::as_str)
+            .collect();
+        for satellite_id in remove {
+            satellite_ids.remove(satellite_id);
+        }
+        for satellite_id in add {
+            satellite_ids.insert(satellite_id);
+        }
+
+        if satellite_ids.len() > MAX_SATELLITES {
+            return Err(capacity_error());
+        }
+        Ok(())
+    }
+
+    /// Subscribe a satellite, replacing the orbit of a subscribed ID
+    pub fn insert(&self, satellite_id: String, orbit: LiveOrbit) -> Result<(), PropagationError> {
+        let mut subscriptions = self.lock();
+        if subscriptions.satellites.len() >= MAX_SATELLITES
+            && !subscriptions.satellites.contains_key(&satellite_id)
+        {
+            return Err(capacity_error());
+        }
+        subscriptions
+            .satellites
+            .insert(satellite_id, Arc::new(orbit));
+        Ok(())
+    }
+
+    pub fn remove(&self, satellite_id: &str) {
+        self.lock().satellites.remove(satellite_id);
+    }
+
+    /// Propagate every subscribed satellite to the current UTC time on the
+    /// compute pool, in satellite ID order, and format each outcome there.
+    /// Returns the time and the formatted positions, or None when nothing is
+    /// subscribed.
+    pub async fn positions<T, F>(&self, format: F) -> Option<(f64, Vec<T>)>
+    where
+        T: Send + 'static,
+        F: Fn(LivePosition) -> T + Send + Sync + 'static,
+    {
+        let (satellites, frame) = {
+            let subscriptions = self.lock();
+            let satellites: Vec<(String, Arc<LiveOrbit>)> = subscriptions
+                .satellites
+                .iter()
+                .map(|(satellite_id, orbit)| (satellite_id.clone(), Arc::clone(orbit)))
+                .collect();
+            (satellites, subscriptions.frame)
+        };
+        if satellites.is_empty() {
+            return None;
+        }
+
+        let time = now_unix();
+        let positions = compute::map(satellites, move |(satellite_id, satellite)| {
+            let outcome = propagator::propagate_at(&satellite.orbit.as_source(), time);
+            format(LivePosition {
+                satellite_id,
+                norad_id: satellite.norad_id,
+                time,
+                frame,
+                outcome,
+            })
+        })
+        .await;
+        Some((time, positions))
+    }
+}
+
+impl Default for LiveSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Outcome of propagating one subscribed satellite for an update
+pub struct LivePosition {
+    pub satellite_id: String,
+    pub norad_id: Option<u64>,
+    /// UTC time of the update in fractional Unix seconds
+    pub time: f64,
+    pub frame: Frame,
+    pub outcome: Result<PropagationResult, PropagationError>,
+}
+
+/// Current UTC time in fractional Unix seconds
+pub fn now_unix() -> f64 {
+    chrono::Utc::now().timestamp_micros() as f64 / 1e6
+}
+
+fn capacity_error() -> PropagationError {
+    PropagationError::InvalidParameter(format!(
+        "Live sessions are limited to {} satellites",
+        MAX_SATELLITES
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_limits() {
+        let session = LiveSession::new();
+        let changes = session.interval_changes();
+        assert_eq!(*changes.borrow(), DEFAULT_INTERVAL);
+
+        // Out-of-range intervals are clamped
+        assert!(session.set_interval(Duration::MAX));
+        assert_eq!(session.interval(), MAX_INTERVAL);
+        assert!(session.set_interval(Duration::ZERO));
+        assert_eq!(session.interval(), MIN_INTERVAL);
+        assert!(!session.set_interval(Duration::from_millis(1)));
+        assert!(changes.has_changed().unwrap());
+
+        // Removals are counted before additions
+        let iss = OrbitSource::Tle {
+            line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008",
+            line2: "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096",
+        };
+        session
+            .insert("ISS".to_string(), LiveOrbit::new(iss).unwrap())
+            .unwrap();
+        let ids: Vec<String> = (1..MAX_SATELLITES)
+            .map(|index| format!("SAT-{}", index))
+            .collect();
+        let add = || ids.iter().map(String::as_str);
+        assert!(session.check_capacity(add(), []).is_ok());
+        assert!(session.check_capacity(add().chain(["EXTRA"]), []).is_err());
+        assert!(session
+            .check_capacity(add().chain(["EXTRA"]), ["ISS"])
+            .is_ok());
+    }
+}
@@ -5,45 +5,82 @@
 //!
 //! Additionally, HTTP/JSON endpoints are provided for easy integration.
 
+mod cache;
+mod catalog;
+mod compute;
+mod eop;
+mod frames;
 mod generated;
+mod live;
 mod metrics;
+mod omm;
 mod propagator;
 mod service;
+mod solar;
+mod store;
+mod timescale;
+mod tle;
 
 #[cfg(test)]
 mod tests;
 
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::hash::{BuildHasher, Hasher};
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use axum::{
-    extract::State,
-    http::StatusCode,
+    body::Body,
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     routing::{get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::transport::Server;
 use tracing::{info, Level};
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
+use crate::catalog::{Catalog, CatalogEntry};
+use crate::eop::EopError;
+use crate::frames::Frame;
 use crate::generated::orbital::orbital_service_server::OrbitalServiceServer;
+use crate::live::{LiveOrbit, LiveSession};
 use crate::metrics::MetricsState;
+use crate::propagator::{ErrorCode, OrbitSource, OwnedOrbitSource, PropagationError};
 use crate::service::OrbitalServiceImpl;
 
 /// Application state shared across services
 pub struct AppState {
     pub start_time: Instant,
     pub metrics: MetricsState,
+    pub catalog: Arc<Catalog>,
+    // Open /api/live streams by session ID
+    live_sessions: HashMap<String, Arc<LiveSession>>,
 }
 
 impl AppState {
     pub fn new() -> Self {
+        Self::with_catalog(Arc::new(Catalog::new()))
+    }
+
+    /// State around an existing catalog, e.g. one loaded from disk
+    pub fn with_catalog(catalog: Arc<Catalog>) -> Self {
         Self {
             start_time: Instant::now(),
             metrics: MetricsState::new(),
+            catalog,
+            live_sessions: HashMap::new(),
         }
     }
 
@@ -65,9 +102,81 @@ impl Default for AppState {
 #[derive(Debug, Deserialize)]
 struct PropagateRequest {
     satellite_id: String,
+    #[serde(default)]
     tle_line1: String,
+    #[serde(default)]
     tle_line2: String,
+    // CCSDS OMM (KVN/XML string or JSON object) in place of the TLE lines
+    omm: Option<OmmDocument>,
+    // Expected NORAD ID (digits or Alpha-5); rejected when it disagrees with the orbit
+    norad_id: Option<NoradId>,
+    #[serde(default)]
     timestamp_unix: i64,
+    // Sub-second time (fractional seconds or ISO 8601); overrides timestamp_unix
+    timestamp: Option<Timestamp>,
+    // Frame for position and velocity (TEME unless requested)
+    #[serde(default)]
+    output_frame: ReferenceFrame,
+    // Scale of the request and response timestamps (UTC unless requested)
+    #[serde(default)]
+    time_scale: TimeScale,
+    // Element set of a registered satellite to use (nearest epoch unless requested)
+    #[serde(default)]
+    element_selection: ElementSelection,
+}
+
+// Change to the subscriptions of a live session; removals apply before
+// additions, and unset fields keep their current value
+#[derive(Debug, Deserialize)]
+struct LiveSubscriptionRequest {
+    #[serde(default)]
+    add: Vec<LiveSatellite>,
+    #[serde(default)]
+    remove: Vec<String>,
+    // Milliseconds between updates (default 1000, at least 100)
+    interval_ms: Option<u64>,
+    // Frame for position and velocity of later updates
+    output_frame: Option<ReferenceFrame>,
+}
+
+// Satellite to add to a live session; replaces the orbit of a subscribed ID
+#[derive(Debug, Deserialize)]
+struct LiveSatellite {
+    satellite_id: String,
+    #[serde(default)]
+    tle_line1: String,
+    #[serde(default)]
+    tle_line2: String,
+    // CCSDS OMM (KVN/XML string or JSON object) in place of the TLE lines
+    omm: Option<OmmDocument>,
+    // Expected NORAD ID (digits or Alpha-5); rejected when it disagrees with the orbit
+    norad_id: Option<NoradId>,
+}
+
+// Satellite to register (or replace) under the ID in the path
+#[derive(Debug, Deserialize)]
+struct CatalogUpsertRequest {
+    #[serde(default)]
+    name: String,
+    tle_line1: String,
+    tle_line2: String,
+    // Expected NORAD ID (digits or Alpha-5); rejected when it disagrees with the TLE
+    norad_id: Option<NoradId>,
+}
+
+// Ground station to register (or replace) under the ID in the path
+#[derive(Debug, Deserialize)]
+struct GroundStationUpsertRequest {
+    #[serde(default)]
+    name: String,
+    latitude_deg: f64,
+    longitude_deg: f64,
+    #[serde(default)]
+    altitude_m: f64,
+    #[serde(default)]
+    min_elevation_deg: f64,
+    #[serde(default)]
+    horizon_mask: Vec<HorizonMaskPoint>,
 }
 
 // TASK-157: Batch propagation request
@@ -80,33 +189,357 @@ struct BatchPropagateRequest {
 #[derive(Debug, Deserialize)]
 struct TrajectoryRequest {
     satellite_id: String,
+    #[serde(default)]
     tle_line1: String,
+    #[serde(default)]
     tle_line2: String,
+    // CCSDS OMM (KVN/XML string or JSON object) in place of the TLE lines
+    omm: Option<OmmDocument>,
+    // Expected NORAD ID (digits or Alpha-5); rejected when it disagrees with the orbit
+    norad_id: Option<NoradId>,
     // Support both naming conventions
-    #[serde(alias = "start_unix")]
+    #[serde(default, alias = "start_unix")]
     start_timestamp_unix: i64,
-    #[serde(alias = "end_unix")]
+    #[serde(default, alias = "end_unix")]
     end_timestamp_unix: i64,
+    // Sub-second bounds (fractional seconds or ISO 8601); override the fields above
+    start_time: Option<Timestamp>,
+    end_time: Option<Timestamp>,
     #[serde(default = "default_step")]
-    step_seconds: i64,
+    step_seconds: f64,
+    // Attach the sunlit fraction of the solar disc to each point
+    #[serde(default)]
+    include_illumination: bool,
+    #[serde(default)]
+    output_frame: ReferenceFrame,
+    #[serde(default)]
+    time_scale: TimeScale,
+    // Registered satellites: choice among the TLE history for the window start
+    #[serde(default)]
+    element_selection: ElementSelection,
 }
 
-fn default_step() -> i64 {
-    60  // Default 1 minute intervals
+fn default_step() -> f64 {
+    60.0  // Default 1 minute intervals
 }
 
 // TASK-159: Visibility calculation request
 #[derive(Debug, Deserialize)]
 struct VisibilityRequest {
     satellite_id: String,
+    #[serde(default)]
     tle_line1: String,
+    #[serde(default)]
     tle_line2: String,
-    ground_station: GroundStation,
+    // CCSDS OMM (KVN/XML string or JSON object) in place of the TLE lines
+    omm: Option<OmmDocument>,
+    // Expected NORAD ID (digits or Alpha-5); rejected when it disagrees with the orbit
+    norad_id: Option<NoradId>,
+    // Inline station, or the ID of a registered one
+    ground_station: Option<GroundStation>,
+    ground_station_id: Option<String>,
     // Support both naming conventions
-    #[serde(alias = "start_unix")]
+    #[serde(default, alias = "start_unix")]
+    start_timestamp_unix: i64,
+    #[serde(default, alias = "end_unix")]
+    end_timestamp_unix: i64,
+    start_time: Option<Timestamp>,
+    end_time: Option<Timestamp>,
+    // Optional range/range-rate/Doppler curve per pass
+    pass_sampling: Option<PassSampling>,
+    // Radio (default) or optical visibility
+    #[serde(default)]
+    visibility_mode: VisibilityMode,
+    // Optical mode: darkness limit at the station
+    #[serde(default)]
+    twilight: Twilight,
+    // Optical mode: magnitude at 1000 km and 90 deg phase
+    standard_magnitude: Option<f64>,
+    #[serde(default)]
+    time_scale: TimeScale,
+    // Registered satellites: choice among the TLE history for the window start
+    #[serde(default)]
+    element_selection: ElementSelection,
+}
+
+// Output reference frame for state vectors
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+enum ReferenceFrame {
+    #[default]
+    #[serde(alias = "teme")]
+    Teme,
+    #[serde(alias = "pef")]
+    Pef,
+    #[serde(alias = "itrf")]
+    Itrf,
+    #[serde(alias = "j2000", alias = "eme2000")]
+    J2000,
+    #[serde(alias = "gcrf")]
+    Gcrf,
+}
+
+impl From<Frame> for ReferenceFrame {
+    fn from(frame: Frame) -> Self {
+        match frame {
+            Frame::Teme => ReferenceFrame::Teme,
+            Frame::Pef => ReferenceFrame::Pef,
+            Frame::Itrf => ReferenceFrame::Itrf,
+            Frame::J2000 => ReferenceFrame::J2000,
+            Frame::Gcrf => ReferenceFrame::Gcrf,
+        }
+    }
+}
+
+impl From<ReferenceFrame> for Frame {
+    fn from(frame: ReferenceFrame) -> Self {
+        match frame {
+            ReferenceFrame::Teme => Frame::Teme,
+            ReferenceFrame::Pef => Frame::Pef,
+            ReferenceFrame::Itrf => Frame::Itrf,
+            ReferenceFrame::J2000 => Frame::J2000,
+            ReferenceFrame::Gcrf => Frame::Gcrf,
+        }
+    }
+}
+
+// Time scale of request and response timestamps
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+enum TimeScale {
+    #[default]
+    #[serde(alias = "utc")]
+    Utc,
+    #[serde(alias = "tai")]
+    Tai,
+    #[serde(alias = "tt")]
+    Tt,
+    #[serde(alias = "gps")]
+    Gps,
+    #[serde(alias = "ut1")]
+    Ut1,
+}
+
+impl From<TimeScale> for timescale::TimeScale {
+    fn from(scale: TimeScale) -> Self {
+        match scale {
+            TimeScale::Utc => timescale::TimeScale::Utc,
+            TimeScale::Tai => timescale::TimeScale::Tai,
+            TimeScale::Tt => timescale::TimeScale::Tt,
+            TimeScale::Gps => timescale::TimeScale::Gps,
+            TimeScale::Ut1 => timescale::TimeScale::Ut1,
+        }
+    }
+}
+
+// Choice among the TLE history of a registered satellite for a time
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum ElementSelection {
+    // Epoch closest to the time
+    #[default]
+    #[serde(alias = "nearest")]
+    Nearest,
+    // Latest epoch at or before the time
+    #[serde(alias = "latest_before")]
+    LatestBefore,
+}
+
+impl From<ElementSelection> for catalog::ElementSelection {
+    fn from(selection: ElementSelection) -> Self {
+        match selection {
+            ElementSelection::Nearest => catalog::ElementSelection::Nearest,
+            ElementSelection::LatestBefore => catalog::ElementSelection::LatestBefore,
+        }
+    }
+}
+
+// Request time as Unix seconds (fractional allowed) or an ISO 8601 string,
+// read on the clock of the request's time scale
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(try_from = "RawTimestamp")]
+struct Timestamp(f64);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawTimestamp {
+    Seconds(f64),
+    Iso(String),
+}
+
+impl TryFrom<RawTimestamp> for Timestamp {
+    type Error = String;
+
+    fn try_from(raw: RawTimestamp) -> Result<Self, Self::Error> {
+        match raw {
+            RawTimestamp::Seconds(seconds) if seconds.is_finite() => Ok(Timestamp(seconds)),
+            RawTimestamp::Seconds(seconds) => Err(format!("invalid timestamp: {}", seconds)),
+            RawTimestamp::Iso(text) => {
+                // Without an offset the time is taken as written
+                let time = chrono::DateTime::parse_from_rfc3339(&text)
+                    .map(|time| time.with_timezone(&chrono::Utc))
+                    .or_else(|_| {
+                        chrono::NaiveDateTime::parse_from_str(&text, "%Y-%m-%dT%H:%M:%S%.f")
+                            .map(|time| time.and_utc())
+                    })
+                    .map_err(|e| format!("invalid ISO 8601 timestamp '{}': {}", text, e))?;
+                Ok(Timestamp(
+                    time.timestamp() as f64 + time.timestamp_subsec_nanos() as f64 * 1e-9,
+                ))
+            }
+        }
+    }
+}
+
+// CCSDS OMM as a KVN/XML string or an embedded JSON object
+#[derive(Debug, Deserialize)]
+#[serde(from = "serde_json::Value")]
+struct OmmDocument(String);
+
+impl From<serde_json::Value> for OmmDocument {
+    fn from(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::String(text) => OmmDocument(text),
+            other => OmmDocument(other.to_string()),
+        }
+    }
+}
+
+// NORAD ID given as a JSON string or number
+#[derive(Debug, Deserialize)]
+#[serde(from = "serde_json::Value")]
+struct NoradId(String);
+
+impl From<serde_json::Value> for NoradId {
+    fn from(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::String(text) => NoradId(text),
+            other => NoradId(other.to_string()),
+        }
+    }
+}
+
+// Orbit of a request: the OMM when given, else a validated pair of TLE lines,
+// checked against the caller's NORAD ID
+fn orbit_source<'a>(
+    tle_line1: &'a str,
+    tle_line2: &'a str,
+    omm: Option<&'a OmmDocument>,
+    norad_id: Option<&NoradId>,
+) -> Result<OrbitSource<'a>, PropagationError> {
+    let source = match omm {
+        Some(_) if !tle_line1.is_empty() || !tle_line2.is_empty() => Err(
+            PropagationError::InvalidParameter("Provide either TLE lines or an OMM, not both".to_string()),
+        ),
+        Some(omm) => Ok(OrbitSource::omm(&omm.0)),
+        None => tle::validate(tle_line1, tle_line2)
+            .map_err(|e| PropagationError::TleParseError(e.to_string()))
+            .map(|()| OrbitSource::Tle {
+                line1: tle_line1,
+                line2: tle_line2,
+            }),
+    }?;
+
+    if let Some(norad_id) = norad_id {
+        source.check_norad_id(&norad_id.0)?;
+    }
+    Ok(source)
+}
+
+// Requests without an orbit take a TLE of the satellite registered under their
+// satellite_id, chosen from its history for `time` (UTC); returns the epoch of
+// that element set
+fn use_registered_tle(
+    catalog: &Catalog,
+    satellite_id: &str,
+    time: f64,
+    selection: ElementSelection,
+    tle_line1: &mut String,
+    tle_line2: &mut String,
+    omm: Option<&OmmDocument>,
+) -> Result<Option<ElementEpoch>, PropagationError> {
+    if omm.is_some() || !tle_line1.is_empty() || !tle_line2.is_empty() || satellite_id.is_empty() {
+        return Ok(None);
+    }
+
+    let entry = catalog.elements_at(satellite_id, time, selection.into())?;
+    tle_line1.clone_from(&entry.tle_line1);
+    tle_line2.clone_from(&entry.tle_line2);
+    Ok(Some(ElementEpoch::from(&*entry)))
+}
+
+// Sub-second field when present, else the whole-second field
+fn request_time(timestamp: Option<Timestamp>, timestamp_unix: i64) -> f64 {
+    timestamp.map_or(timestamp_unix as f64, |t| t.0)
+}
+
+// ISO 8601 rendering of a response time; the "Z" suffix only applies to UTC
+fn iso_8601(timestamp_unix: f64, time_scale: TimeScale) -> String {
+    let seconds = timestamp_unix.floor();
+    let micros = ((timestamp_unix - seconds) * 1e6).round().min(999_999.0) as u32;
+    let Some(time) = chrono::DateTime::from_timestamp(seconds as i64, micros * 1000) else {
+        return String::new();
+    };
+    let text = time.format("%Y-%m-%dT%H:%M:%S%.6f").to_string();
+    match time_scale {
+        TimeScale::Utc => text + "Z",
+        _ => text,
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum VisibilityMode {
+    #[default]
+    Radio,
+    Optical,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Twilight {
+    Civil,
+    #[default]
+    Nautical,
+    Astronomical,
+}
+
+#[derive(Debug, Deserialize)]
+struct PassSampling {
+    #[serde(default = "default_pass_sample_step")]
+    step_seconds: f64,
+    downlink_frequency_hz: Option<f64>,
+    uplink_frequency_hz: Option<f64>,
+}
+
+fn default_pass_sample_step() -> f64 {
+    propagator::DEFAULT_PASS_SAMPLE_STEP_SECONDS
+}
+
+// Eclipse prediction request
+#[derive(Debug, Deserialize)]
+struct EclipseRequest {
+    satellite_id: String,
+    #[serde(default)]
+    tle_line1: String,
+    #[serde(default)]
+    tle_line2: String,
+    // CCSDS OMM (KVN/XML string or JSON object) in place of the TLE lines
+    omm: Option<OmmDocument>,
+    // Expected NORAD ID (digits or Alpha-5); rejected when it disagrees with the orbit
+    norad_id: Option<NoradId>,
+    #[serde(default, alias = "start_unix")]
     start_timestamp_unix: i64,
-    #[serde(alias = "end_unix")]
+    #[serde(default, alias = "end_unix")]
     end_timestamp_unix: i64,
+    start_time: Option<Timestamp>,
+    end_time: Option<Timestamp>,
+    // Scale of the window and the interval times (UTC unless requested)
+    #[serde(default)]
+    time_scale: TimeScale,
+    // Registered satellites: choice among the TLE history for the window start
+    #[serde(default)]
+    element_selection: ElementSelection,
 }
 
 #[derive(Debug, Deserialize)]
@@ -117,18 +550,50 @@ struct GroundStation {
     longitude_deg: f64,
     altitude_m: f64,
     min_elevation_deg: f64,
+    // Optional azimuth-dependent minimum elevation
+    #[serde(default)]
+    horizon_mask: Vec<HorizonMaskPoint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HorizonMaskPoint {
+    azimuth_deg: f64,
+    min_elevation_deg: f64,
 }
 
 #[derive(Debug, Serialize)]
 struct PropagateResponse {
     satellite_id: String,
+    // Decoded NORAD catalog number of the orbit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    norad_id: Option<u64>,
+    // Whole seconds (truncated) for existing clients
     timestamp_unix: i64,
+    // Fractional seconds and ISO 8601 form of the same time
+    time_unix: f64,
+    timestamp: String,
     position: Position,
     velocity: Velocity,
     geodetic: Geodetic,
+    // Frame of position and velocity
+    frame: ReferenceFrame,
+    // Scale of the timestamps
+    time_scale: TimeScale,
+    // True when the output frame needs EOP data that did not cover the
+    // request (UT1 = UTC, no polar motion)
+    eop_fallback: bool,
     success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<String>,
+    // Machine-readable code of a failed batch item (see Problem)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_code: Option<&'static str>,
+    // Set when the item failed because the orbit has decayed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decay: Option<Decay>,
+    // Epoch of the registered element set used; absent for inline orbits
+    #[serde(flatten)]
+    element_epoch: Option<ElementEpoch>,
 }
 
 // TASK-157: Batch response
@@ -144,29 +609,227 @@ struct BatchPropagateResponse {
 #[derive(Debug, Serialize)]
 struct TrajectoryResponse {
     satellite_id: String,
+    // Decoded NORAD catalog number of the orbit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    norad_id: Option<u64>,
     points: Vec<TrajectoryPoint>,
+    // Frame of point positions and velocities
+    frame: ReferenceFrame,
+    time_scale: TimeScale,
+    eop_fallback: bool,
+    // True when the orbit decayed inside the requested range; points stop at
+    // the last sample before the decay
+    decayed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decay: Option<Decay>,
     success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<String>,
+    // Epoch of the registered element set used; absent for inline orbits
+    #[serde(flatten)]
+    element_epoch: Option<ElementEpoch>,
+}
+
+// Last line of a streamed (NDJSON) trajectory
+#[derive(Debug, Serialize)]
+struct TrajectoryStreamEnd {
+    complete: bool,
+    satellite_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    norad_id: Option<u64>,
+    point_count: usize,
+    frame: ReferenceFrame,
+    time_scale: TimeScale,
+    eop_fallback: bool,
+    decayed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decay: Option<Decay>,
+    // Epoch of the registered element set used; absent for inline orbits
+    #[serde(flatten)]
+    element_epoch: Option<ElementEpoch>,
 }
 
 #[derive(Debug, Serialize)]
 struct TrajectoryPoint {
     timestamp_unix: i64,
+    time_unix: f64,
+    timestamp: String,
     position: Position,
     velocity: Velocity,
     geodetic: Geodetic,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    illumination_fraction: Option<f64>,
+}
+
+// First event of a live stream; subscription changes are posted to
+// /api/live/{session_id}
+#[derive(Debug, Serialize)]
+struct LiveSessionOpened {
+    session_id: String,
+    interval_ms: u64,
+    frame: ReferenceFrame,
+}
+
+// Positions of every subscribed satellite at one UTC instant, ordered by
+// satellite ID; satellites that cannot be propagated have success = false
+#[derive(Debug, Serialize)]
+struct LiveUpdate {
+    timestamp_unix: i64,
+    time_unix: f64,
+    timestamp: String,
+    positions: Vec<PropagateResponse>,
+}
+
+// Session settings after a subscription change
+#[derive(Debug, Serialize)]
+struct LiveSubscriptionResponse {
+    success: bool,
+    satellite_count: usize,
+    interval_ms: u64,
+    frame: ReferenceFrame,
+    // Satellites that were not added (invalid orbit, session full)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    rejected: Vec<LiveRejection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct LiveRejection {
+    satellite_id: String,
+    error: String,
+    error_code: &'static str,
+}
+
+// Registered satellite with one of its TLEs; the current one except in history
+#[derive(Debug, Serialize)]
+struct CatalogObject {
+    satellite_id: String,
+    name: String,
+    norad_id: u64,
+    tle_line1: String,
+    tle_line2: String,
+    // Epoch of the TLE
+    epoch_unix: f64,
+    epoch: String,
+    // When the entry was last written
+    updated_unix: f64,
+}
+
+impl From<&CatalogEntry> for CatalogObject {
+    fn from(entry: &CatalogEntry) -> Self {
+        CatalogObject {
+            satellite_id: entry.satellite_id.clone(),
+            name: entry.name.clone(),
+            norad_id: entry.norad_id,
+            tle_line1: entry.tle_line1.clone(),
+            tle_line2: entry.tle_line2.clone(),
+            epoch_unix: entry.epoch_unix,
+            epoch: iso_8601(entry.epoch_unix, TimeScale::Utc),
+            updated_unix: entry.updated_unix,
+        }
+    }
+}
+
+// UTC epoch of the element set chosen from a registered satellite's history
+#[derive(Debug, Clone, Serialize)]
+struct ElementEpoch {
+    element_epoch_unix: f64,
+    element_epoch: String,
+}
+
+impl From<&CatalogEntry> for ElementEpoch {
+    fn from(entry: &CatalogEntry) -> Self {
+        ElementEpoch {
+            element_epoch_unix: entry.epoch_unix,
+            element_epoch: iso_8601(entry.epoch_unix, TimeScale::Utc),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CatalogListResponse {
+    satellites: Vec<CatalogObject>,
+    count: usize,
+}
+
+// Every element set registered for a satellite, ordered by epoch
+#[derive(Debug, Serialize)]
+struct CatalogHistoryResponse {
+    satellite_id: String,
+    element_sets: Vec<CatalogObject>,
+    count: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct GroundStationListResponse {
+    ground_stations: Vec<propagator::GroundStation>,
+    count: usize,
+}
+
+// Eclipse prediction response
+#[derive(Debug, Serialize)]
+struct EclipseResponse {
+    satellite_id: String,
+    // Decoded NORAD catalog number of the orbit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    norad_id: Option<u64>,
+    eclipses: Vec<EclipseInterval>,
+    // Scale of the interval times
+    time_scale: TimeScale,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    // Epoch of the registered element set used; absent for inline orbits
+    #[serde(flatten)]
+    element_epoch: Option<ElementEpoch>,
+}
+
+#[derive(Debug, Serialize)]
+struct EclipseInterval {
+    kind: ShadowKind,
+    entry_time_unix: f64,
+    exit_time_unix: f64,
+    duration_seconds: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ShadowKind {
+    Penumbra,
+    Umbra,
+}
+
+// EOP reload response
+#[derive(Debug, Serialize)]
+struct EopReloadResponse {
+    success: bool,
+    entries: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coverage_start_unix: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coverage_end_unix: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
 }
 
 // TASK-159: Visibility response
 #[derive(Debug, Serialize)]
 struct VisibilityResponse {
     satellite_id: String,
+    // Decoded NORAD catalog number of the orbit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    norad_id: Option<u64>,
     ground_station_id: String,
     passes: Vec<VisibilityPass>,
+    time_scale: TimeScale,
+    eop_fallback: bool,
     success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<String>,
+    // Epoch of the registered element set used; absent for inline orbits
+    #[serde(flatten)]
+    element_epoch: Option<ElementEpoch>,
 }
 
 #[derive(Debug, Serialize)]
@@ -181,6 +844,32 @@ struct VisibilityPass {
     #[serde(skip_serializing_if = "Option::is_none")]
     los_azimuth_deg: Option<f64>,
     duration_seconds: i64,
+    // Refined event times with sub-second precision
+    aos_time_unix: f64,
+    los_time_unix: f64,
+    tca_time_unix: f64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    samples: Vec<PassSample>,
+    // Longest sub-interval with the satellite sunlit (and station dark in optical mode)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sunlit_start_time_unix: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sunlit_end_time_unix: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    visual_magnitude: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct PassSample {
+    timestamp_unix: f64,
+    elevation_deg: f64,
+    azimuth_deg: f64,
+    range_km: f64,
+    range_rate_km_s: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    downlink_doppler_hz: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uplink_doppler_hz: Option<f64>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -204,42 +893,120 @@ struct Geodetic {
     altitude_km: f64,
 }
 
+// Decay of an orbit, estimated by bisecting to the first failing SGP4 time.
+// Always UTC, whatever the time scale of the request.
+#[derive(Debug, Serialize)]
+struct Decay {
+    last_valid_time_unix: f64,
+    last_valid_timestamp: String,
+    decay_time_unix: f64,
+    decay_timestamp: String,
+}
+
+impl From<propagator::Decay> for Decay {
+    fn from(decay: propagator::Decay) -> Self {
+        Decay {
+            last_valid_time_unix: decay.last_valid_unix,
+            last_valid_timestamp: iso_8601(decay.last_valid_unix, TimeScale::Utc),
+            decay_time_unix: decay.decay_time_unix,
+            decay_timestamp: iso_8601(decay.decay_time_unix, TimeScale::Utc),
+        }
+    }
+}
+
+// RFC 7807 problem details, the body of every failed propagation request.
+// `code` is the machine-readable error code shared with the gRPC API.
+#[derive(Debug, Serialize)]
+struct Problem {
+    #[serde(rename = "type")]
+    problem_type: String,
+    title: &'static str,
+    status: u16,
+    detail: String,
+    code: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decay: Option<Decay>,
+}
+
+impl From<PropagationError> for Problem {
+    fn from(error: PropagationError) -> Self {
+        let code = error.code();
+        Problem {
+            problem_type: format!(
+                "https://docs.stellarops.io/errors/{}",
+                code.as_str().to_ascii_lowercase().replace('_', "-")
+            ),
+            title: code.title(),
+            status: http_status(code).as_u16(),
+            detail: error.to_string(),
+            code: code.as_str(),
+            decay: error.decay().map(Decay::from),
+        }
+    }
+}
+
+impl IntoResponse for Problem {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        (status, [(header::CONTENT_TYPE, "application/problem+json")], Json(self)).into_response()
+    }
+}
+
+// Malformed input is 400 and unregistered satellites or stations and unknown
+// live sessions 404; well-formed orbits that cannot be propagated to the
+// requested time are 422; only service faults and exhausted limits are 5xx
+fn http_status(code: ErrorCode) -> StatusCode {
+    match code {
+        ErrorCode::TleParseError | ErrorCode::InvalidArgument => StatusCode::BAD_REQUEST,
+        ErrorCode::SatelliteNotFound
+        | ErrorCode::GroundStationNotFound
+        | ErrorCode::SessionNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::SatelliteDecayed
+        | ErrorCode::EccentricityOutOfRange
+        | ErrorCode::TimeOutOfValidity => StatusCode::UNPROCESSABLE_ENTITY,
+        ErrorCode::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 // HTTP handler for propagation
 async fn propagate_handler(
     State(state): State<Arc<RwLock<AppState>>>,
-    Json(req): Json<PropagateRequest>,
-) -> Result<Json<PropagateResponse>, (StatusCode, Json<PropagateResponse>)> {
-    // TASK-163: Validate TLE format
-    if req.tle_line1.len() != 69 || req.tle_line2.len() != 69 {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(PropagateResponse {
-                satellite_id: req.satellite_id.clone(),
-                timestamp_unix: req.timestamp_unix,
-                position: Position { x_km: 0.0, y_km: 0.0, z_km: 0.0 },
-                velocity: Velocity { vx_km_s: 0.0, vy_km_s: 0.0, vz_km_s: 0.0 },
-                geodetic: Geodetic { latitude_deg: 0.0, longitude_deg: 0.0, altitude_km: 0.0 },
-                success: false,
-                error: Some("TLE lines must be exactly 69 characters".to_string()),
-            }),
-        ));
-    }
+    Json(mut req): Json<PropagateRequest>,
+) -> Result<Json<PropagateResponse>, Problem> {
+    let time = request_time(req.timestamp, req.timestamp_unix);
+    let utc = timescale::to_utc(time, req.time_scale.into());
 
-    // TASK-164: Validate timestamp range
-    let now = chrono::Utc::now().timestamp();
-    if req.timestamp_unix < now - (365 * 24 * 3600) {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(PropagateResponse {
-                satellite_id: req.satellite_id.clone(),
-                timestamp_unix: req.timestamp_unix,
-                position: Position { x_km: 0.0, y_km: 0.0, z_km: 0.0 },
-                velocity: Velocity { vx_km_s: 0.0, vy_km_s: 0.0, vz_km_s: 0.0 },
-                geodetic: Geodetic { latitude_deg: 0.0, longitude_deg: 0.0, altitude_km: 0.0 },
-                success: false,
-                error: Some("Timestamp is more than 1 year in the past".to_string()),
-            }),
-        ));
+    let element_epoch = use_registered_tle(
+        &state.read().await.catalog,
+        &req.satellite_id,
+        utc,
+        req.element_selection,
+        &mut req.tle_line1,
+        &mut req.tle_line2,
+        req.omm.as_ref(),
+    )?;
+    // TASK-163: Validate TLE format (or take the OMM)
+    let source = orbit_source(
+        &req.tle_line1,
+        &req.tle_line2,
+        req.omm.as_ref(),
+        req.norad_id.as_ref(),
+    )?;
+    let norad_id = source.norad_id();
+
+    // TASK-164: Validate timestamp range. Registered satellites are checked
+    // against the element set chosen from their history rather than today, so
+    // past times can be reconstructed.
+    let one_year = (365 * 24 * 3600) as f64;
+    let too_old = match &element_epoch {
+        Some(epoch) => (utc < epoch.element_epoch_unix - one_year)
+            .then_some("Timestamp is more than 1 year before the earliest registered element set"),
+        None => (utc < chrono::Utc::now().timestamp() as f64 - one_year)
+            .then_some("Timestamp is more than 1 year in the past"),
+    };
+    if let Some(message) = too_old {
+        return Err(PropagationError::TimeOutOfValidity(message.to_string()).into());
     }
 
     // Update metrics
@@ -248,28 +1015,32 @@ async fn propagate_handler(
         app_state.metrics.increment_propagation_count();
     }
 
-    match propagator::propagate(&req.tle_line1, &req.tle_line2, req.timestamp_unix) {
-        Ok(result) => Ok(Json(PropagateResponse {
-            satellite_id: req.satellite_id,
-            timestamp_unix: req.timestamp_unix,
-            position: Position {
-                x_km: result.position_km[0],
-                y_km: result.position_km[1],
-                z_km: result.position_km[2],
-            },
-            velocity: Velocity {
-                vx_km_s: result.velocity_km_s[0],
-                vy_km_s: result.velocity_km_s[1],
-                vz_km_s: result.velocity_km_s[2],
-            },
-            geodetic: Geodetic {
-                latitude_deg: result.geodetic.latitude_deg,
-                longitude_deg: result.geodetic.longitude_deg,
-                altitude_km: result.geodetic.altitude_km,
-            },
-            success: true,
-            error: None,
-        })),
+    let orbit = OwnedOrbitSource::from(source);
+
+    match compute::run(move || propagator::propagate_at(&orbit.as_source(), utc)).await {
+        Ok(result) => {
+            let (position, velocity, geodetic) = state_vectors(&result, req.output_frame, utc);
+            let eop_fallback = Frame::from(req.output_frame).uses_eop() && !eop::covers(utc, utc);
+
+            Ok(Json(PropagateResponse {
+                satellite_id: req.satellite_id,
+                norad_id,
+                timestamp_unix: time.floor() as i64,
+                time_unix: time,
+                timestamp: iso_8601(time, req.time_scale),
+                position,
+                velocity,
+                geodetic,
+                frame: req.output_frame,
+                time_scale: req.time_scale,
+                eop_fallback,
+                success: true,
+                error: None,
+                error_code: None,
+                decay: None,
+                element_epoch,
+            }))
+        }
         Err(e) => {
             // Update error metrics
             {
@@ -277,30 +1048,7 @@ async fn propagate_handler(
                 app_state.metrics.increment_error_count();
             }
 
-            Err((
-                StatusCode::BAD_REQUEST,
-                Json(PropagateResponse {
-                    satellite_id: req.satellite_id,
-                    timestamp_unix: req.timestamp_unix,
-                    position: Position {
-                        x_km: 0.0,
-                        y_km: 0.0,
-                        z_km: 0.0,
-                    },
-                    velocity: Velocity {
-                        vx_km_s: 0.0,
-                        vy_km_s: 0.0,
-                        vz_km_s: 0.0,
-                    },
-                    geodetic: Geodetic {
-                        latitude_deg: 0.0,
-                        longitude_deg: 0.0,
-                        altitude_km: 0.0,
-                    },
-                    success: false,
-                    error: Some(e.to_string()),
-                }),
-            ))
+            Err(e.into())
         }
     }
 }
@@ -310,66 +1058,15 @@ async fn batch_propagate_handler(
     State(state): State<Arc<RwLock<AppState>>>,
     Json(batch_req): Json<BatchPropagateRequest>,
 ) -> Json<BatchPropagateResponse> {
-    let mut results = Vec::new();
-    let mut success_count = 0;
-    let mut error_count = 0;
-
-    // TASK-162: Optimize for batch requests by reusing parsed elements where possible
-    for req in batch_req.requests {
-        // Validate TLE format
-        if req.tle_line1.len() != 69 || req.tle_line2.len() != 69 {
-            results.push(PropagateResponse {
-                satellite_id: req.satellite_id,
-                timestamp_unix: req.timestamp_unix,
-                position: Position { x_km: 0.0, y_km: 0.0, z_km: 0.0 },
-                velocity: Velocity { vx_km_s: 0.0, vy_km_s: 0.0, vz_km_s: 0.0 },
-                geodetic: Geodetic { latitude_deg: 0.0, longitude_deg: 0.0, altitude_km: 0.0 },
-                success: false,
-                error: Some("TLE lines must be exactly 69 characters".to_string()),
-            });
-            error_count += 1;
-            continue;
-        }
-
-        match propagator::propagate(&req.tle_line1, &req.tle_line2, req.timestamp_unix) {
-            Ok(result) => {
-                results.push(PropagateResponse {
-                    satellite_id: req.satellite_id,
-                    timestamp_unix: req.timestamp_unix,
-                    position: Position {
-                        x_km: result.position_km[0],
-                        y_km: result.position_km[1],
-                        z_km: result.position_km[2],
-                    },
-                    velocity: Velocity {
-                        vx_km_s: result.velocity_km_s[0],
-                        vy_km_s: result.velocity_km_s[1],
-                        vz_km_s: result.velocity_km_s[2],
-                    },
-                    geodetic: Geodetic {
-                        latitude_deg: result.geodetic.latitude_deg,
-                        longitude_deg: result.geodetic.longitude_deg,
-                        altitude_km: result.geodetic.altitude_km,
-                    },
-                    success: true,
-                    error: None,
-                });
-                success_count += 1;
-            }
-            Err(e) => {
-                results.push(PropagateResponse {
-                    satellite_id: req.satellite_id,
-                    timestamp_unix: req.timestamp_unix,
-                    position: Position { x_km: 0.0, y_km: 0.0, z_km: 0.0 },
-                    velocity: Velocity { vx_km_s: 0.0, vy_km_s: 0.0, vz_km_s: 0.0 },
-                    geodetic: Geodetic { latitude_deg: 0.0, longitude_deg: 0.0, altitude_km: 0.0 },
-                    success: false,
-                    error: Some(e.to_string()),
-                });
-                error_count += 1;
-            }
-        }
-    }
+    // TASK-162: Repeated orbits reuse their SGP4 constants through the shared
+    // cache; requests fan out across the compute pool in order
+    let catalog = Arc::clone(&state.read().await.catalog);
+    let results = compute::map(batch_req.requests, move |req| {
+        propagate_batch_item(req, &catalog)
+    })
+    .await;
+    let success_count = results.iter().filter(|result| result.success).count();
+    let error_count = results.len() - success_count;
 
     // Update metrics
     {
@@ -386,64 +1083,189 @@ async fn batch_propagate_handler(
     })
 }
 
-// TASK-158: Trajectory propagation handler
-async fn trajectory_handler(
-    State(state): State<Arc<RwLock<AppState>>>,
-    Json(req): Json<TrajectoryRequest>,
-) -> Result<Json<TrajectoryResponse>, (StatusCode, Json<TrajectoryResponse>)> {
-    // Validate TLE format
-    if req.tle_line1.len() != 69 || req.tle_line2.len() != 69 {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(TrajectoryResponse {
-                satellite_id: req.satellite_id,
-                points: vec![],
-                success: false,
-                error: Some("TLE lines must be exactly 69 characters".to_string()),
-            }),
-        ));
+// One batch entry; failures are reported in the entry rather than failing the batch
+fn propagate_batch_item(mut req: PropagateRequest, catalog: &Catalog) -> PropagateResponse {
+    let time = request_time(req.timestamp, req.timestamp_unix);
+    let utc = timescale::to_utc(time, req.time_scale.into());
+
+    // Validate TLE format (or take the OMM or a registered TLE)
+    let registered = use_registered_tle(
+        catalog,
+        &req.satellite_id,
+        utc,
+        req.element_selection,
+        &mut req.tle_line1,
+        &mut req.tle_line2,
+        req.omm.as_ref(),
+    );
+    let (element_epoch, source) = match registered {
+        Ok(element_epoch) => (
+            element_epoch,
+            orbit_source(
+                &req.tle_line1,
+                &req.tle_line2,
+                req.omm.as_ref(),
+                req.norad_id.as_ref(),
+            ),
+        ),
+        Err(e) => (None, Err(e)),
+    };
+    let (norad_id, outcome) = match source {
+        Ok(source) => (source.norad_id(), propagator::propagate_at(&source, utc)),
+        Err(e) => (None, Err(e)),
+    };
+
+    PropagateResponse {
+        element_epoch,
+        ..position_response(
+            req.satellite_id,
+            norad_id,
+            time,
+            req.time_scale,
+            req.output_frame,
+            outcome,
+        )
     }
+}
 
-    // Validate time range
-    if req.end_timestamp_unix <= req.start_timestamp_unix {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(TrajectoryResponse {
-                satellite_id: req.satellite_id,
-                points: vec![],
+// Batch or live feed entry for one propagation; failures keep zeroed state
+// vectors and carry the error code
+fn position_response(
+    satellite_id: String,
+    norad_id: Option<u64>,
+    time: f64,
+    time_scale: TimeScale,
+    frame: ReferenceFrame,
+    outcome: Result<propagator::PropagationResult, PropagationError>,
+) -> PropagateResponse {
+    let utc = timescale::to_utc(time, time_scale.into());
+
+    match outcome {
+        Ok(result) => {
+            let (position, velocity, geodetic) = state_vectors(&result, frame, utc);
+            let eop_fallback = Frame::from(frame).uses_eop() && !eop::covers(utc, utc);
+
+            PropagateResponse {
+                satellite_id,
+                norad_id,
+                timestamp_unix: time.floor() as i64,
+                time_unix: time,
+                timestamp: iso_8601(time, time_scale),
+                position,
+                velocity,
+                geodetic,
+                frame,
+                time_scale,
+                eop_fallback,
+                success: true,
+                error: None,
+                error_code: None,
+                decay: None,
+                element_epoch: None,
+            }
+        }
+        Err(e) => {
+            PropagateResponse {
+                satellite_id,
+                norad_id,
+                timestamp_unix: time.floor() as i64,
+                time_unix: time,
+                timestamp: iso_8601(time, time_scale),
+                position: Position { x_km: 0.0, y_km: 0.0, z_km: 0.0 },
+                velocity: Velocity { vx_km_s: 0.0, vy_km_s: 0.0, vz_km_s: 0.0 },
+                geodetic: Geodetic { latitude_deg: 0.0, longitude_deg: 0.0, altitude_km: 0.0 },
+                frame,
+                time_scale,
+                eop_fallback: false,
                 success: false,
-                error: Some("End time must be after start time".to_string()),
-            }),
-        ));
+                error: Some(e.to_string()),
+                error_code: Some(e.code().as_str()),
+                decay: e.decay().map(Decay::from),
+                element_epoch: None,
+            }
+        }
     }
+}
+
+// Maximum points in a buffered /api/trajectory response
+const MAX_TRAJECTORY_POINTS: i64 = 10_000;
 
-    match propagator::propagate_trajectory(
+// TASK-158: Trajectory propagation handler
+async fn trajectory_handler(
+    State(state): State<Arc<RwLock<AppState>>>,
+    Json(mut req): Json<TrajectoryRequest>,
+) -> Result<Json<TrajectoryResponse>, Problem> {
+    let start = request_time(req.start_time, req.start_timestamp_unix);
+    let end = request_time(req.end_time, req.end_timestamp_unix);
+
+    let element_epoch = use_registered_tle(
+        &state.read().await.catalog,
+        &req.satellite_id,
+        timescale::to_utc(start, req.time_scale.into()),
+        req.element_selection,
+        &mut req.tle_line1,
+        &mut req.tle_line2,
+        req.omm.as_ref(),
+    )?;
+    // Validate TLE format (or take the OMM)
+    let source = orbit_source(
         &req.tle_line1,
         &req.tle_line2,
-        req.start_timestamp_unix,
-        req.end_timestamp_unix,
-        req.step_seconds,
-    ) {
+        req.omm.as_ref(),
+        req.norad_id.as_ref(),
+    )?;
+    let norad_id = source.norad_id();
+
+    // Validate time range
+    if end <= start {
+        return Err(PropagationError::InvalidParameter(
+            "End time must be after start time".to_string(),
+        )
+        .into());
+    }
+
+    if !req.step_seconds.is_finite() || req.step_seconds <= 0.0 {
+        return Err(PropagationError::InvalidParameter(
+            "step_seconds must be positive".to_string(),
+        )
+        .into());
+    }
+
+    // Limit buffered responses; longer ranges go through the streaming endpoint.
+    // The step count is bounded as f64 so a tiny step cannot overflow the cast.
+    let intervals = ((end - start) / req.step_seconds).floor();
+    if intervals >= MAX_TRAJECTORY_POINTS as f64 {
+        return Err(PropagationError::InvalidParameter(format!(
+            "Trajectory would have {} points, max is {}; use /api/trajectory/stream for longer ranges",
+            (intervals as i64).saturating_add(1),
+            MAX_TRAJECTORY_POINTS
+        ))
+        .into());
+    }
+
+    let time_scale: timescale::TimeScale = req.time_scale.into();
+
+    let orbit = OwnedOrbitSource::from(source);
+    let step_seconds = req.step_seconds;
+    let outcome = compute::run(move || {
+        propagator::propagate_trajectory_in(&orbit.as_source(), start, end, step_seconds, time_scale)
+    })
+    .await;
+
+    match outcome {
         Ok(trajectory) => {
+            let decay = trajectory.decay;
             let points = trajectory
+                .points
                 .into_iter()
-                .map(|(timestamp, result)| TrajectoryPoint {
-                    timestamp_unix: timestamp,
-                    position: Position {
-                        x_km: result.position_km[0],
-                        y_km: result.position_km[1],
-                        z_km: result.position_km[2],
-                    },
-                    velocity: Velocity {
-                        vx_km_s: result.velocity_km_s[0],
-                        vy_km_s: result.velocity_km_s[1],
-                        vz_km_s: result.velocity_km_s[2],
-                    },
-                    geodetic: Geodetic {
-                        latitude_deg: result.geodetic.latitude_deg,
-                        longitude_deg: result.geodetic.longitude_deg,
-                        altitude_km: result.geodetic.altitude_km,
-                    },
+                .map(|(timestamp, result)| {
+                    trajectory_point(
+                        timestamp,
+                        &result,
+                        req.output_frame,
+                        req.time_scale,
+                        req.include_illumination,
+                    )
                 })
                 .collect();
 
@@ -453,11 +1275,24 @@ async fn trajectory_handler(
                 app_state.metrics.increment_propagation_count();
             }
 
+            let eop_fallback = Frame::from(req.output_frame).uses_eop()
+                && !eop::covers(
+                    timescale::to_utc(start, time_scale),
+                    timescale::to_utc(end, time_scale),
+                );
+
             Ok(Json(TrajectoryResponse {
                 satellite_id: req.satellite_id,
+                norad_id,
                 points,
+                frame: req.output_frame,
+                time_scale: req.time_scale,
+                eop_fallback,
+                decayed: decay.is_some(),
+                decay: decay.map(Decay::from),
                 success: true,
                 error: None,
+                element_epoch,
             }))
         }
         Err(e) => {
@@ -466,12 +1301,592 @@ async fn trajectory_handler(
                 app_state.metrics.increment_error_count();
             }
 
+            Err(e.into())
+        }
+    }
+}
+
+// Streaming trajectory as NDJSON, without the point limit of /api/trajectory.
+// Each line is one point, produced as the client reads them; the last line is
+// a summary with `complete: true` and any decay inside the range.
+async fn trajectory_stream_handler(
+    State(state): State<Arc<RwLock<AppState>>>,
+    Json(mut req): Json<TrajectoryRequest>,
+) -> Result<Response, Problem> {
+    let start = request_time(req.start_time, req.start_timestamp_unix);
+    let end = request_time(req.end_time, req.end_timestamp_unix);
+
+    let element_epoch = use_registered_tle(
+        &state.read().await.catalog,
+        &req.satellite_id,
+        timescale::to_utc(start, req.time_scale.into()),
+        req.element_selection,
+        &mut req.tle_line1,
+        &mut req.tle_line2,
+        req.omm.as_ref(),
+    )?;
+    // Validate TLE format (or take the OMM)
+    let source = orbit_source(
+        &req.tle_line1,
+        &req.tle_line2,
+        req.omm.as_ref(),
+        req.norad_id.as_ref(),
+    )?;
+    let norad_id = source.norad_id();
+
+    // Validate time range
+    if end <= start {
+        return Err(PropagationError::InvalidParameter(
+            "End time must be after start time".to_string(),
+        )
+        .into());
+    }
+
+    let time_scale: timescale::TimeScale = req.time_scale.into();
+
+    // Orbit errors are reported before the stream starts
+    let orbit = OwnedOrbitSource::from(source);
+    let step_seconds = req.step_seconds;
+    let outcome = compute::run(move || {
+        propagator::trajectory_samples(&orbit.as_source(), start, end, step_seconds, time_scale)
+    })
+    .await;
+
+    let samples = match outcome {
+        Ok(samples) => samples,
+        Err(e) => {
+            {
+                let mut app_state = state.write().await;
+                app_state.metrics.increment_error_count();
+            }
+
+            return Err(e.into());
+        }
+    };
+
+    {
+        let mut app_state = state.write().await;
+        app_state.metrics.increment_propagation_count();
+    }
+
+    let mut summary = TrajectoryStreamEnd {
+        complete: true,
+        satellite_id: req.satellite_id,
+        norad_id,
+        point_count: 0,
+        frame: req.output_frame,
+        time_scale: req.time_scale,
+        eop_fallback: Frame::from(req.output_frame).uses_eop()
+            && !eop::covers(
+                timescale::to_utc(start, time_scale),
+                timescale::to_utc(end, time_scale),
+            ),
+        decayed: false,
+        decay: None,
+        element_epoch,
+    };
+    let (frame, scale, include_illumination) =
+        (req.output_frame, req.time_scale, req.include_illumination);
+    let (sender, receiver) = mpsc::channel::<Result<String, Infallible>>(compute::STREAM_BUFFER_CHUNKS);
+
+    // Points are produced and serialised on the compute pool one chunk at a
+    // time; a slow client fills the channel and pauses production
+    tokio::spawn(async move {
+        let samples = compute::drain_chunks(
+            samples,
+            compute::STREAM_CHUNK_ITEMS,
+            move |(timestamp, result)| {
+                ndjson_line(&trajectory_point(
+                    timestamp,
+                    &result,
+                    frame,
+                    scale,
+                    include_illumination,
+                ))
+            },
+            |lines| {
+                summary.point_count += lines.len();
+                let sender = sender.clone();
+                async move { sender.send(Ok(lines.concat())).await.is_ok() }
+            },
+        )
+        .await;
+
+        let decay = samples.decay();
+        summary.decayed = decay.is_some();
+        summary.decay = decay.map(Decay::from);
+        let _ = sender.send(Ok(ndjson_line(&summary))).await;
+    });
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(ReceiverStream::new(receiver)),
+    )
+        .into_response())
+}
+
+// One JSON document per line
+fn ndjson_line<T: Serialize>(value: &T) -> String {
+    let mut line = serde_json::to_string(value).unwrap_or_default();
+    line.push('\n');
+    line
+}
+
+// Position, velocity and geodetic position of a propagated state; the single
+// conversion behind every HTTP response, as `state_to_proto` is for gRPC
+fn state_vectors(
+    result: &propagator::PropagationResult,
+    frame: ReferenceFrame,
+    utc: f64,
+) -> (Position, Velocity, Geodetic) {
+    let (position, velocity) = result.state_in(frame.into(), utc);
+    (
+        Position {
+            x_km: position[0],
+            y_km: position[1],
+            z_km: position[2],
+        },
+        Velocity {
+            vx_km_s: velocity[0],
+            vy_km_s: velocity[1],
+            vz_km_s: velocity[2],
+        },
+        Geodetic {
+            latitude_deg: result.geodetic.latitude_deg,
+            longitude_deg: result.geodetic.longitude_deg,
+            altitude_km: result.geodetic.altitude_km,
+        },
+    )
+}
+
+// Trajectory sample in the requested frame and time scale
+fn trajectory_point(
+    timestamp: f64,
+    result: &propagator::PropagationResult,
+    frame: ReferenceFrame,
+    time_scale: TimeScale,
+    include_illumination: bool,
+) -> TrajectoryPoint {
+    let utc = timescale::to_utc(timestamp, time_scale.into());
+    let (position, velocity, geodetic) = state_vectors(result, frame, utc);
+
+    TrajectoryPoint {
+        timestamp_unix: timestamp.floor() as i64,
+        time_unix: timestamp,
+        timestamp: iso_8601(timestamp, time_scale),
+        position,
+        velocity,
+        geodetic,
+        illumination_fraction: include_illumination
+            .then(|| propagator::illumination_fraction(&result.position_km, utc)),
+    }
+}
+
+fn horizon_mask(points: &[HorizonMaskPoint]) -> Vec<propagator::HorizonMaskPoint> {
+    points
+        .iter()
+        .map(|point| propagator::HorizonMaskPoint {
+            azimuth_deg: point.azimuth_deg,
+            min_elevation_deg: point.min_elevation_deg,
+        })
+        .collect()
+}
+
+// Register a satellite, or add a TLE to the history of a registered one and
+// make it current. Answers 201 for a new ID and 200 for an existing one.
+async fn catalog_upsert_handler(
+    State(state): State<Arc<RwLock<AppState>>>,
+    Path(satellite_id): Path<String>,
+    Json(req): Json<CatalogUpsertRequest>,
+) -> Result<(StatusCode, Json<CatalogObject>), Problem> {
+    let entry = compute::run(move || {
+        CatalogEntry::new(
+            &satellite_id,
+            &req.name,
+            &req.tle_line1,
+            &req.tle_line2,
+            req.norad_id.as_ref().map(|norad_id| norad_id.0.as_str()),
+        )
+    })
+    .await?;
+
+    let object = CatalogObject::from(&entry);
+    let catalog = Arc::clone(&state.read().await.catalog);
+    let replaced = catalog::blocking_write(move || catalog.upsert(entry)).await?;
+    info!(
+        satellite_id = %object.satellite_id,
+        norad_id = object.norad_id,
+        replaced = replaced.is_some(),
+        "Satellite registered"
+    );
+
+    let status = if replaced.is_some() {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+    Ok((status, Json(object)))
+}
+
+// Registered satellites, ordered by ID
+async fn catalog_list_handler(
+    State(state): State<Arc<RwLock<AppState>>>,
+) -> Json<CatalogListResponse> {
+    let satellites: Vec<CatalogObject> = state
+        .read()
+        .await
+        .catalog
+        .list()
+        .iter()
+        .map(|entry| CatalogObject::from(&**entry))
+        .collect();
+
+    Json(CatalogListResponse {
+        count: satellites.len(),
+        satellites,
+    })
+}
+
+async fn catalog_get_handler(
+    State(state): State<Arc<RwLock<AppState>>>,
+    Path(satellite_id): Path<String>,
+) -> Result<Json<CatalogObject>, Problem> {
+    let entry = state.read().await.catalog.lookup(&satellite_id)?;
+    Ok(Json(CatalogObject::from(&*entry)))
+}
+
+async fn catalog_history_handler(
+    State(state): State<Arc<RwLock<AppState>>>,
+    Path(satellite_id): Path<String>,
+) -> Result<Json<CatalogHistoryResponse>, Problem> {
+    let element_sets: Vec<CatalogObject> = state
+        .read()
+        .await
+        .catalog
+        .history(&satellite_id)?
+        .iter()
+        .map(|entry| CatalogObject::from(&**entry))
+        .collect();
+
+    Ok(Json(CatalogHistoryResponse {
+        satellite_id,
+        count: element_sets.len(),
+        element_sets,
+    }))
+}
+
+async fn catalog_delete_handler(
+    State(state): State<Arc<RwLock<AppState>>>,
+    Path(satellite_id): Path<String>,
+) -> Result<StatusCode, Problem> {
+    let catalog = Arc::clone(&state.read().await.catalog);
+    let removed = {
+        let satellite_id = satellite_id.clone();
+        catalog::blocking_write(move || catalog.remove(&satellite_id)).await?
+    };
+    match removed {
+        Some(_) => {
+            info!(satellite_id = %satellite_id, "Satellite removed from the catalog");
+            Ok(StatusCode::NO_CONTENT)
+        }
+        None => Err(PropagationError::SatelliteNotFound(satellite_id).into()),
+    }
+}
+
+// Register a ground station, or replace a registered one. Answers 201 for a
+// new ID and 200 for a replacement.
+async fn ground_station_upsert_handler(
+    State(state): State<Arc<RwLock<AppState>>>,
+    Path(station_id): Path<String>,
+    Json(req): Json<GroundStationUpsertRequest>,
+) -> Result<(StatusCode, Json<propagator::GroundStation>), Problem> {
+    let station = propagator::GroundStation {
+        id: station_id,
+        name: req.name,
+        latitude_deg: req.latitude_deg,
+        longitude_deg: req.longitude_deg,
+        altitude_m: req.altitude_m,
+        min_elevation_deg: req.min_elevation_deg,
+        horizon_mask: horizon_mask(&req.horizon_mask),
+    };
+
+    let catalog = Arc::clone(&state.read().await.catalog);
+    let replaced = {
+        let station = station.clone();
+        catalog::blocking_write(move || catalog.upsert_ground_station(station)).await?
+    };
+    info!(
+        station_id = %station.id,
+        replaced = replaced.is_some(),
+        "Ground station registered"
+    );
+
+    let status = if replaced.is_some() {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+    Ok((status, Json(station)))
+}
+
+// Registered ground stations, ordered by ID
+async fn ground_station_list_handler(
+    State(state): State<Arc<RwLock<AppState>>>,
+) -> Json<GroundStationListResponse> {
+    let ground_stations: Vec<propagator::GroundStation> = state
+        .read()
+        .await
+        .catalog
+        .ground_stations()
+        .iter()
+        .map(|station| (**station).clone())
+        .collect();
+
+    Json(GroundStationListResponse {
+        count: ground_stations.len(),
+        ground_stations,
+    })
+}
+
+async fn ground_station_get_handler(
+    State(state): State<Arc<RwLock<AppState>>>,
+    Path(station_id): Path<String>,
+) -> Result<Json<propagator::GroundStation>, Problem> {
+    let station = state.read().await.catalog.ground_station(&station_id)?;
+    Ok(Json((*station).clone()))
+}
+
+async fn ground_station_delete_handler(
+    State(state): State<Arc<RwLock<AppState>>>,
+    Path(station_id): Path<String>,
+) -> Result<StatusCode, Problem> {
+    let catalog = Arc::clone(&state.read().await.catalog);
+    let removed = {
+        let station_id = station_id.clone();
+        catalog::blocking_write(move || catalog.remove_ground_station(&station_id)).await?
+    };
+    match removed {
+        Some(_) => {
+            info!(station_id = %station_id, "Ground station removed");
+            Ok(StatusCode::NO_CONTENT)
+        }
+        None => Err(PropagationError::GroundStationNotFound(station_id).into()),
+    }
+}
+
+// Live positions as Server-Sent Events. The first event ("session") carries
+// the ID for changing subscriptions through POST /api/live/{session_id};
+// "positions" events follow at the session interval while any satellite is
+// subscribed. The session is dropped when the client disconnects.
+async fn live_handler(
+    State(state): State<Arc<RwLock<AppState>>>,
+) -> Result<Sse<ReceiverStream<Result<Event, Infallible>>>, Problem> {
+    let session_id = live_session_id();
+    let session = Arc::new(LiveSession::new());
+
+    {
+        let mut app_state = state.write().await;
+        if app_state.live_sessions.len() >= live::MAX_SESSIONS {
+            return Err(PropagationError::Unavailable(format!(
+                "At most {} live streams may be open",
+                live::MAX_SESSIONS
+            ))
+            .into());
+        }
+        app_state
+            .live_sessions
+            .insert(session_id.clone(), Arc::clone(&session));
+    }
+
+    let (sender, receiver) = mpsc::channel(compute::STREAM_BUFFER_CHUNKS);
+    let opened = LiveSessionOpened {
+        session_id: session_id.clone(),
+        interval_ms: session.interval().as_millis() as u64,
+        frame: session.frame().into(),
+    };
+    // The channel is empty, so the first event always fits
+    let _ = sender.try_send(Ok(sse_event("session", &opened)));
+
+    info!(session_id = %session_id, "Live stream opened");
+    tokio::spawn(run_live_session(session_id, session, sender, state));
+
+    Ok(Sse::new(ReceiverStream::new(receiver)).keep_alive(KeepAlive::default()))
+}
+
+// Produce the events of one live stream until the client disconnects
+async fn run_live_session(
+    session_id: String,
+    session: Arc<LiveSession>,
+    events: mpsc::Sender<Result<Event, Infallible>>,
+    state: Arc<RwLock<AppState>>,
+) {
+    let mut interval_changes = session.interval_changes();
+    let mut ticker = live::ticker(*interval_changes.borrow_and_update());
+
+    loop {
+        tokio::select! {
+            changed = interval_changes.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                ticker = live::ticker(*interval_changes.borrow_and_update());
+            }
+            _ = ticker.tick() => {
+                let start = Instant::now();
+                let update = session.positions(|position| {
+                    position_response(
+                        position.satellite_id,
+                        position.norad_id,
+                        position.time,
+                        TimeScale::Utc,
+                        position.frame.into(),
+                        position.outcome,
+                    )
+                });
+                let Some((time, positions)) = update.await else {
+                    continue;
+                };
+
+                {
+                    let app_state = state.read().await;
+                    let propagated = positions.iter().filter(|position| position.success).count();
+                    app_state.metrics.record_live_feed(start.elapsed(), propagated);
+                }
+
+                let update = LiveUpdate {
+                    timestamp_unix: time.floor() as i64,
+                    time_unix: time,
+                    timestamp: iso_8601(time, TimeScale::Utc),
+                    positions,
+                };
+                if events.send(Ok(sse_event("positions", &update))).await.is_err() {
+                    break;
+                }
+            }
+            _ = events.closed() => break,
+        }
+    }
+
+    {
+        let mut app_state = state.write().await;
+        app_state.live_sessions.remove(&session_id);
+    }
+
+    info!(session_id = %session_id, "Live stream closed");
+}
+
+// Add or remove satellites of an open live stream, or change its interval or
+// output frame. Orbits are checked here, so an invalid one is reported once in
+// `rejected` instead of failing in every update.
+async fn live_subscription_handler(
+    State(state): State<Arc<RwLock<AppState>>>,
+    Path(session_id): Path<String>,
+    Json(req): Json<LiveSubscriptionRequest>,
+) -> Result<Json<LiveSubscriptionResponse>, Problem> {
+    let session = state.read().await.live_sessions.get(&session_id).cloned();
+    let Some(session) = session else {
+        return Err(PropagationError::SessionNotFound(session_id).into());
+    };
+
+    // Refuse additions beyond the session capacity before parsing any orbit
+    session.check_capacity(
+        req.add.iter().map(|satellite| satellite.satellite_id.as_str()),
+        req.remove.iter().map(String::as_str),
+    )?;
+
+    let added = compute::map(req.add, |satellite| {
+        let orbit = live_orbit(&satellite);
+        (satellite.satellite_id, orbit)
+    })
+    .await;
+
+    for satellite_id in &req.remove {
+        session.remove(satellite_id);
+    }
+    let mut rejected = Vec::new();
+    for (satellite_id, orbit) in added {
+        let subscribed = orbit.and_then(|orbit| session.insert(satellite_id.clone(), orbit));
+        if let Err(e) = subscribed {
+            rejected.push(LiveRejection {
+                satellite_id,
+                error: e.to_string(),
+                error_code: e.code().as_str(),
+            });
+        }
+    }
+    if let Some(frame) = req.output_frame {
+        session.set_frame(frame.into());
+    }
+    if let Some(interval_ms) = req.interval_ms {
+        session.set_interval(Duration::from_millis(interval_ms));
+    }
+
+    Ok(Json(LiveSubscriptionResponse {
+        success: true,
+        satellite_count: session.len(),
+        interval_ms: session.interval().as_millis() as u64,
+        frame: session.frame().into(),
+        rejected,
+        error: None,
+    }))
+}
+
+// Validated orbit of a satellite added to a live session
+fn live_orbit(satellite: &LiveSatellite) -> Result<LiveOrbit, PropagationError> {
+    let source = orbit_source(
+        &satellite.tle_line1,
+        &satellite.tle_line2,
+        satellite.omm.as_ref(),
+        satellite.norad_id.as_ref(),
+    )?;
+    LiveOrbit::new(source)
+}
+
+// Random ID for a live session; only the client that opened the stream sees it
+fn live_session_id() -> String {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(NEXT.fetch_add(1, Ordering::Relaxed));
+    format!("{:016x}", hasher.finish())
+}
+
+// Named SSE event with a JSON payload
+fn sse_event<T: Serialize>(name: &str, value: &T) -> Event {
+    Event::default()
+        .event(name)
+        .data(serde_json::to_string(value).unwrap_or_default())
+}
+
+// Re-read the configured EOP file without restarting the service
+async fn eop_reload_handler() -> Result<Json<EopReloadResponse>, (StatusCode, Json<EopReloadResponse>)> {
+    // File I/O and parsing off the async runtime
+    let result = tokio::task::spawn_blocking(eop::reload)
+        .await
+        .unwrap_or_else(|e| Err(EopError::Io(e.to_string())));
+
+    match result {
+        Ok(entries) => {
+            let coverage = eop::current_table().map(|table| table.coverage_unix());
+            Ok(Json(EopReloadResponse {
+                success: true,
+                entries,
+                coverage_start_unix: coverage.map(|(start, _)| start),
+                coverage_end_unix: coverage.map(|(_, end)| end),
+                error: None,
+            }))
+        }
+        Err(e) => {
+            let status = match e {
+                EopError::NotConfigured => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
             Err((
-                StatusCode::BAD_REQUEST,
-                Json(TrajectoryResponse {
-                    satellite_id: req.satellite_id,
-                    points: vec![],
+                status,
+                Json(EopReloadResponse {
                     success: false,
+                    entries: 0,
+                    coverage_start_unix: None,
+                    coverage_end_unix: None,
                     error: Some(e.to_string()),
                 }),
             ))
@@ -482,38 +1897,85 @@ async fn trajectory_handler(
 // TASK-159: Visibility calculation handler
 async fn visibility_handler(
     State(state): State<Arc<RwLock<AppState>>>,
-    Json(req): Json<VisibilityRequest>,
-) -> Result<Json<VisibilityResponse>, (StatusCode, Json<VisibilityResponse>)> {
-    // Validate TLE format
-    if req.tle_line1.len() != 69 || req.tle_line2.len() != 69 {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(VisibilityResponse {
-                satellite_id: req.satellite_id,
-                ground_station_id: req.ground_station.id,
-                passes: vec![],
-                success: false,
-                error: Some("TLE lines must be exactly 69 characters".to_string()),
-            }),
-        ));
-    }
+    Json(mut req): Json<VisibilityRequest>,
+) -> Result<Json<VisibilityResponse>, Problem> {
+    let start = request_time(req.start_time, req.start_timestamp_unix);
+    let end = request_time(req.end_time, req.end_timestamp_unix);
 
-    let ground_station = propagator::GroundStation {
-        id: req.ground_station.id.clone(),
-        name: req.ground_station.name.clone(),
-        latitude_deg: req.ground_station.latitude_deg,
-        longitude_deg: req.ground_station.longitude_deg,
-        altitude_m: req.ground_station.altitude_m,
-        min_elevation_deg: req.ground_station.min_elevation_deg,
-    };
-
-    match propagator::calculate_visibility_passes(
+    let element_epoch = use_registered_tle(
+        &state.read().await.catalog,
+        &req.satellite_id,
+        timescale::to_utc(start, req.time_scale.into()),
+        req.element_selection,
+        &mut req.tle_line1,
+        &mut req.tle_line2,
+        req.omm.as_ref(),
+    )?;
+    // Validate TLE format (or take the OMM)
+    let source = orbit_source(
         &req.tle_line1,
         &req.tle_line2,
-        &ground_station,
-        req.start_timestamp_unix,
-        req.end_timestamp_unix,
-    ) {
+        req.omm.as_ref(),
+        req.norad_id.as_ref(),
+    )?;
+    let norad_id = source.norad_id();
+
+    let ground_station = match (&req.ground_station, &req.ground_station_id) {
+        (Some(station), _) => propagator::GroundStation {
+            id: station.id.clone(),
+            name: station.name.clone(),
+            latitude_deg: station.latitude_deg,
+            longitude_deg: station.longitude_deg,
+            altitude_m: station.altitude_m,
+            min_elevation_deg: station.min_elevation_deg,
+            horizon_mask: horizon_mask(&station.horizon_mask),
+        },
+        (None, Some(station_id)) => {
+            (*state.read().await.catalog.ground_station(station_id)?).clone()
+        }
+        (None, None) => {
+            return Err(PropagationError::InvalidParameter(
+                "ground_station or ground_station_id is required".to_string(),
+            )
+            .into())
+        }
+    };
+    let ground_station_id = ground_station.id.clone();
+
+    ground_station.validate()?;
+
+    let options = propagator::VisibilityOptions {
+        sampling: req.pass_sampling.as_ref().map(|sampling| propagator::PassSampling {
+            step_seconds: sampling.step_seconds,
+            downlink_frequency_hz: sampling.downlink_frequency_hz,
+            uplink_frequency_hz: sampling.uplink_frequency_hz,
+        }),
+        mode: match req.visibility_mode {
+            VisibilityMode::Radio => propagator::VisibilityMode::Radio,
+            VisibilityMode::Optical => propagator::VisibilityMode::Optical(match req.twilight {
+                Twilight::Civil => propagator::Twilight::Civil,
+                Twilight::Nautical => propagator::Twilight::Nautical,
+                Twilight::Astronomical => propagator::Twilight::Astronomical,
+            }),
+        },
+        standard_magnitude: req.standard_magnitude,
+        time_scale: req.time_scale.into(),
+    };
+    let time_scale = options.time_scale;
+
+    let orbit = OwnedOrbitSource::from(source);
+    let outcome = compute::run(move || {
+        propagator::calculate_visibility_passes_with_options(
+            &orbit.as_source(),
+            &ground_station,
+            start,
+            end,
+            &options,
+        )
+    })
+    .await;
+
+    match outcome {
         Ok(passes) => {
             let visibility_passes = passes
                 .into_iter()
@@ -525,6 +1987,25 @@ async fn visibility_handler(
                     aos_azimuth_deg: pass.aos_azimuth_deg,
                     los_azimuth_deg: pass.los_azimuth_deg,
                     duration_seconds: pass.duration_seconds.unwrap_or(0),
+                    aos_time_unix: pass.aos_time_unix,
+                    los_time_unix: pass.los_time_unix,
+                    tca_time_unix: pass.tca_time_unix,
+                    samples: pass
+                        .samples
+                        .into_iter()
+                        .map(|sample| PassSample {
+                            timestamp_unix: sample.timestamp_unix,
+                            elevation_deg: sample.elevation_deg,
+                            azimuth_deg: sample.azimuth_deg,
+                            range_km: sample.range_km,
+                            range_rate_km_s: sample.range_rate_km_s,
+                            downlink_doppler_hz: sample.downlink_doppler_hz,
+                            uplink_doppler_hz: sample.uplink_doppler_hz,
+                        })
+                        .collect(),
+                    sunlit_start_time_unix: pass.sunlit_start_time_unix,
+                    sunlit_end_time_unix: pass.sunlit_end_time_unix,
+                    visual_magnitude: pass.visual_magnitude,
                 })
                 .collect();
 
@@ -533,12 +2014,21 @@ async fn visibility_handler(
                 app_state.metrics.increment_propagation_count();
             }
 
+            let eop_fallback = !eop::covers(
+                timescale::to_utc(start, time_scale),
+                timescale::to_utc(end, time_scale),
+            );
+
             Ok(Json(VisibilityResponse {
                 satellite_id: req.satellite_id,
-                ground_station_id: req.ground_station.id,
+                norad_id,
+                ground_station_id,
                 passes: visibility_passes,
+                time_scale: req.time_scale,
+                eop_fallback,
                 success: true,
                 error: None,
+                element_epoch,
             }))
         }
         Err(e) => {
@@ -547,16 +2037,89 @@ async fn visibility_handler(
                 app_state.metrics.increment_error_count();
             }
 
-            Err((
-                StatusCode::BAD_REQUEST,
-                Json(VisibilityResponse {
-                    satellite_id: req.satellite_id,
-                    ground_station_id: req.ground_station.id,
-                    passes: vec![],
-                    success: false,
-                    error: Some(e.to_string()),
-                }),
-            ))
+            Err(e.into())
+        }
+    }
+}
+
+// Eclipse (umbra/penumbra) prediction handler
+async fn eclipse_handler(
+    State(state): State<Arc<RwLock<AppState>>>,
+    Json(mut req): Json<EclipseRequest>,
+) -> Result<Json<EclipseResponse>, Problem> {
+    let start = request_time(req.start_time, req.start_timestamp_unix);
+    let end = request_time(req.end_time, req.end_timestamp_unix);
+
+    let element_epoch = use_registered_tle(
+        &state.read().await.catalog,
+        &req.satellite_id,
+        timescale::to_utc(start, req.time_scale.into()),
+        req.element_selection,
+        &mut req.tle_line1,
+        &mut req.tle_line2,
+        req.omm.as_ref(),
+    )?;
+    // Validate TLE format (or take the OMM)
+    let source = orbit_source(
+        &req.tle_line1,
+        &req.tle_line2,
+        req.omm.as_ref(),
+        req.norad_id.as_ref(),
+    )?;
+    let norad_id = source.norad_id();
+
+    // Validate time range
+    if end <= start {
+        return Err(PropagationError::InvalidParameter(
+            "End time must be after start time".to_string(),
+        )
+        .into());
+    }
+
+    let orbit = OwnedOrbitSource::from(source);
+    let time_scale = req.time_scale.into();
+    let outcome = compute::run(move || {
+        propagator::calculate_eclipses(&orbit.as_source(), start, end, time_scale)
+    })
+    .await;
+
+    match outcome {
+        Ok(intervals) => {
+            let eclipses = intervals
+                .into_iter()
+                .map(|interval| EclipseInterval {
+                    kind: match interval.kind {
+                        propagator::ShadowKind::Penumbra => ShadowKind::Penumbra,
+                        propagator::ShadowKind::Umbra => ShadowKind::Umbra,
+                    },
+                    entry_time_unix: interval.entry_time_unix,
+                    exit_time_unix: interval.exit_time_unix,
+                    duration_seconds: interval.exit_time_unix - interval.entry_time_unix,
+                })
+                .collect();
+
+            {
+                let mut app_state = state.write().await;
+                app_state.metrics.increment_propagation_count();
+            }
+
+            Ok(Json(EclipseResponse {
+                satellite_id: req.satellite_id,
+                norad_id,
+                eclipses,
+                time_scale: req.time_scale,
+                success: true,
+                error: None,
+                element_epoch,
+            }))
+        }
+        Err(e) => {
+            {
+                let mut app_state = state.write().await;
+                app_state.metrics.increment_error_count();
+            }
+
+            Err(e.into())
         }
     }
 }
@@ -583,9 +2146,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .init();
     }
 
-    // Create shared application state
-    let state = Arc::new(RwLock::new(AppState::new()));
-
     // Get configuration from environment
     let grpc_port: u16 = std::env::var("GRPC_PORT")
         .unwrap_or_else(|_| "50051".to_string())
@@ -597,6 +2157,63 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .parse()
         .expect("METRICS_PORT must be a valid port number");
 
+    // Optional IERS finals2000A file for UT1-UTC and polar motion
+    if let Ok(eop_path) = std::env::var("EOP_FILE") {
+        if let Err(e) = eop::load_file(std::path::Path::new(&eop_path)) {
+            tracing::warn!("Failed to load EOP file, using UT1 = UTC fallback: {}", e);
+        }
+    }
+
+    // Optional leap-second list replacing the bundled table
+    if let Ok(leap_seconds_path) = std::env::var("LEAP_SECONDS_FILE") {
+        if let Err(e) = timescale::load_leap_second_file(std::path::Path::new(&leap_seconds_path)) {
+            tracing::warn!("Failed to load leap second file, using bundled table: {}", e);
+        }
+    }
+
+    // Worker threads for CPU-bound propagation, one per core by default
+    if let Ok(threads) = std::env::var("COMPUTE_THREADS") {
+        match threads.parse() {
+            Ok(threads) => {
+                compute::init(threads);
+            }
+            Err(e) => tracing::warn!("Invalid COMPUTE_THREADS, using one per core: {}", e),
+        }
+    }
+
+    // Number of orbits whose SGP4 constants are kept between requests
+    if let Ok(capacity) = std::env::var("SGP4_CACHE_CAPACITY") {
+        match capacity.parse() {
+            Ok(capacity) => cache::set_capacity(capacity),
+            Err(e) => tracing::warn!(
+                "Invalid SGP4_CACHE_CAPACITY, using {}: {}",
+                cache::DEFAULT_CAPACITY,
+                e
+            ),
+        }
+    }
+
+    // Registered satellites, TLE history and ground stations are kept in
+    // DATA_DIR when set, and in memory only otherwise
+    let catalog = match std::env::var("DATA_DIR") {
+        Ok(data_dir) => {
+            let catalog = Arc::new(Catalog::open(std::path::Path::new(&data_dir))?);
+            let warming = Arc::clone(&catalog);
+            tokio::spawn(async move {
+                let orbits = compute::run(move || warming.warm_cache()).await;
+                info!(orbits, "Warmed SGP4 cache from the catalog");
+            });
+            catalog
+        }
+        Err(_) => {
+            tracing::warn!("DATA_DIR not set, the catalog will not persist across restarts");
+            Arc::new(Catalog::new())
+        }
+    };
+
+    // Create shared application state
+    let state = Arc::new(RwLock::new(AppState::with_catalog(catalog)));
+
     let grpc_addr: SocketAddr = format!("0.0.0.0:{}", grpc_port).parse()?;
     let metrics_addr: SocketAddr = format!("0.0.0.0:{}", metrics_port).parse()?;
 
@@ -616,7 +2233,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .route("/api/propagate", post(propagate_handler))
             .route("/api/propagate/batch", post(batch_propagate_handler))  // TASK-157
             .route("/api/trajectory", post(trajectory_handler))  // TASK-158
+            .route("/api/trajectory/stream", post(trajectory_stream_handler))
+            .route("/api/live", get(live_handler))
+            .route("/api/live/:session_id", post(live_subscription_handler))
             .route("/api/visibility", post(visibility_handler))  // TASK-159
+            .route("/api/eclipses", post(eclipse_handler))
+            .route("/api/eop/reload", post(eop_reload_handler))
+            .route("/api/catalog", get(catalog_list_handler))
+            .route(
+                "/api/catalog/:satellite_id",
+                get(catalog_get_handler)
+                    .put(catalog_upsert_handler)
+                    .delete(catalog_delete_handler),
+            )
+            .route("/api/catalog/:satellite_id/history", get(catalog_history_handler))
+            .route("/api/ground-stations", get(ground_station_list_handler))
+            .route(
+                "/api/ground-stations/:station_id",
+                get(ground_station_get_handler)
+                    .put(ground_station_upsert_handler)
+                    .delete(ground_station_delete_handler),
+            )
             .with_state(metrics_state);
 
         let listener = tokio::net::TcpListener::bind(metrics_addr).await.unwrap();
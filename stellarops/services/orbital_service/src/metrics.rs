@@ -6,7 +6,8 @@ use std::time::Duration;
 use axum::{extract::State, http::StatusCode, response::IntoResponse};
 use lazy_static::lazy_static;
 use prometheus::{
-    register_counter_vec, register_histogram_vec, CounterVec, Encoder, HistogramVec, TextEncoder,
+    register_counter_vec, register_histogram_vec, register_int_gauge, CounterVec, Encoder,
+    HistogramVec, IntGauge, TextEncoder,
 };
 use tokio::sync::RwLock;
 
@@ -71,6 +72,29 @@ lazy_static! {
         "Total number of errors by type",
         &["error_type"]
     ).unwrap();
+
+    /// Lookups in the SGP4 constants cache by outcome (hit, miss, eviction)
+    pub static ref SGP4_CACHE_EVENTS: CounterVec = register_counter_vec!(
+        "orbital_sgp4_cache_events_total",
+        "SGP4 constants cache hits, misses and evictions",
+        &["event"]
+    ).unwrap();
+
+    /// Orbits currently held in the SGP4 constants cache
+    pub static ref SGP4_CACHE_ENTRIES: IntGauge = register_int_gauge!(
+        "orbital_sgp4_cache_entries",
+        "Number of orbits in the SGP4 constants cache"
+    ).unwrap();
+}
+
+/// Record an SGP4 cache hit, miss or eviction
+pub fn record_sgp4_cache_event(event: &str) {
+    SGP4_CACHE_EVENTS.with_label_values(&[event]).inc();
+}
+
+/// Adjust the SGP4 cache size gauge
+pub fn add_sgp4_cache_entries(delta: i64) {
+    SGP4_CACHE_ENTRIES.add(delta);
 }
 
 /// Metrics state for recording from service handlers
@@ -140,6 +164,46 @@ impl MetricsState {
             .observe(duration.as_secs_f64());
     }
 
+    pub fn record_eclipses(&self, duration: Duration, success: bool) {
+        let status = if success { "success" } else { "error" };
+
+        GRPC_REQUESTS
+            .with_label_values(&["CalculateEclipses", status])
+            .inc();
+
+        PROPAGATION_LATENCY
+            .with_label_values(&["eclipses"])
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn record_batch(&self, duration: Duration, success_count: usize, error_count: usize) {
+        GRPC_REQUESTS
+            .with_label_values(&["PropagateBatch", "success"])
+            .inc();
+
+        PROPAGATION_LATENCY
+            .with_label_values(&["batch"])
+            .observe(duration.as_secs_f64());
+
+        PROPAGATION_COUNT
+            .with_label_values(&["success"])
+            .inc_by(success_count as f64);
+
+        ERROR_COUNT
+            .with_label_values(&["propagation_error"])
+            .inc_by(error_count as f64);
+    }
+
+    pub fn record_live_feed(&self, duration: Duration, positions: usize) {
+        PROPAGATION_LATENCY
+            .with_label_values(&["live_feed"])
+            .observe(duration.as_secs_f64());
+
+        PROPAGATION_COUNT
+            .with_label_values(&["success"])
+            .inc_by(positions as f64);
+    }
+
     pub fn record_trajectory(&self, duration: Duration, points: usize, success: bool) {
         let status = if success { "success" } else { "error" };
         
@@ -0,0 +1,320 @@
+//! CCSDS Orbit Mean-Elements Messages (OMM)
+//!
+//! Accepts the KVN, XML and JSON encodings published by Space-Track and
+//! CelesTrak and maps the SGP4 mean elements onto the same element set a TLE
+//! produces. The encoding is detected from the first non-blank character.
+
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use sgp4::{Classification, Elements};
+
+/// Parse a single OMM in any supported encoding
+pub fn parse(text: &str) -> Result<Elements, OmmError> {
+    let text = text.trim_start();
+    let fields = match text.chars().next() {
+        Some('{') | Some('[') => json_fields(text)?,
+        Some('<') => xml_fields(text)?,
+        Some(_) => kvn_fields(text)?,
+        None => return Err(OmmError::Empty),
+    };
+    elements_from_fields(&fields)
+}
+
+/// `KEY = value` lines; units in square brackets are dropped
+fn kvn_fields(text: &str) -> Result<HashMap<String, String>, OmmError> {
+    let mut fields = HashMap::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("COMMENT") {
+            continue;
+        }
+
+        let (key, value) = line.split_once('=').ok_or_else(|| {
+            OmmError::Syntax(format!("line {}: expected KEY = value", index + 1))
+        })?;
+        let value = value.split('[').next().unwrap_or_default().trim();
+        insert_field(&mut fields, key.trim(), value)?;
+    }
+
+    Ok(fields)
+}
+
+/// Leaf elements of an OMM/NDM XML document (namespace prefixes ignored)
+fn xml_fields(text: &str) -> Result<HashMap<String, String>, OmmError> {
+    let mut fields = HashMap::new();
+    let mut rest = text;
+
+    while let Some(open) = rest.find('<') {
+        rest = &rest[open + 1..];
+        let close = rest
+            .find('>')
+            .ok_or_else(|| OmmError::Syntax("unterminated XML tag".to_string()))?;
+        let tag = &rest[..close];
+        rest = &rest[close + 1..];
+
+        if tag.starts_with(['/', '?', '!']) || tag.ends_with('/') {
+            continue;
+        }
+
+        let name = tag.split_whitespace().next().unwrap_or_default();
+        let Some(end) = rest.find(&format!("</{}>", name)) else {
+            return Err(OmmError::Syntax(format!("missing closing tag for <{}>", name)));
+        };
+        let value = &rest[..end];
+        if !value.contains('<') {
+            let key = name.rsplit(':').next().unwrap_or(name);
+            insert_field(&mut fields, key, &xml_unescape(value.trim()))?;
+        }
+    }
+
+    Ok(fields)
+}
+
+/// A CelesTrak/Space-Track style object, or an array holding exactly one
+fn json_fields(text: &str) -> Result<HashMap<String, String>, OmmError> {
+    let value: serde_json::Value =
+        serde_json::from_str(text).map_err(|e| OmmError::Syntax(e.to_string()))?;
+
+    let object = match &value {
+        serde_json::Value::Array(items) if items.len() == 1 => &items[0],
+        serde_json::Value::Array(items) => return Err(OmmError::MessageCount(items.len())),
+        _ => &value,
+    };
+    let object = object
+        .as_object()
+        .ok_or_else(|| OmmError::Syntax("expected a JSON object".to_string()))?;
+
+    let mut fields = HashMap::new();
+    for (key, value) in object {
+        match value {
+            serde_json::Value::String(text) => insert_field(&mut fields, key, text)?,
+            serde_json::Value::Number(number) => insert_field(&mut fields, key, &number.to_string())?,
+            _ => {}
+        }
+    }
+
+    Ok(fields)
+}
+
+fn insert_field(fields: &mut HashMap<String, String>, key: &str, value: &str) -> Result<(), OmmError> {
+    let key = key.to_ascii_uppercase();
+    // A second epoch means a second message; one orbit per request
+    if key == "EPOCH" && fields.contains_key(&key) {
+        return Err(OmmError::MessageCount(2));
+    }
+    fields.entry(key).or_insert_with(|| value.to_string());
+    Ok(())
+}
+
+fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn elements_from_fields(fields: &HashMap<String, String>) -> Result<Elements, OmmError> {
+    let text = |name: &'static str| {
+        fields
+            .get(name)
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    };
+    let invalid = |name: &'static str, message: String| OmmError::InvalidField { name, message };
+    let number = |name: &'static str| -> Result<f64, OmmError> {
+        text(name)
+            .ok_or(OmmError::MissingField(name))?
+            .parse::<f64>()
+            .map_err(|e| invalid(name, e.to_string()))
+    };
+    let optional_number = |name: &'static str| -> Result<f64, OmmError> {
+        text(name).map_or(Ok(0.0), |_| number(name))
+    };
+    let optional_integer = |name: &'static str| -> Result<u64, OmmError> {
+        text(name).map_or(Ok(0), |value| value.parse::<u64>().map_err(|e| invalid(name, e.to_string())))
+    };
+
+    if let Some(theory) = text("MEAN_ELEMENT_THEORY") {
+        // SGP4-XP elements are not compatible with SGP4
+        if !theory.eq_ignore_ascii_case("SGP4") && !theory.eq_ignore_ascii_case("SGP/SGP4") {
+            return Err(OmmError::UnsupportedTheory(theory.to_string()));
+        }
+    }
+
+    let epoch = text("EPOCH").ok_or(OmmError::MissingField("EPOCH"))?;
+    let datetime = parse_epoch(epoch)
+        .ok_or_else(|| invalid("EPOCH", format!("'{}' is not an ISO 8601 time", epoch)))?;
+
+    let classification = match text("CLASSIFICATION_TYPE").unwrap_or("U") {
+        "U" => Classification::Unclassified,
+        "C" => Classification::Classified,
+        "S" => Classification::Secret,
+        other => return Err(invalid("CLASSIFICATION_TYPE", format!("unknown value '{}'", other))),
+    };
+
+    let eccentricity = number("ECCENTRICITY")?;
+    if !(0.0..1.0).contains(&eccentricity) {
+        return Err(invalid("ECCENTRICITY", format!("{} outside [0, 1)", eccentricity)));
+    }
+    let mean_motion = number("MEAN_MOTION")?;
+    if mean_motion <= 0.0 {
+        return Err(invalid("MEAN_MOTION", format!("{} is not positive", mean_motion)));
+    }
+
+    Ok(Elements {
+        object_name: text("OBJECT_NAME").map(str::to_string),
+        international_designator: text("OBJECT_ID").map(str::to_string),
+        norad_id: optional_integer("NORAD_CAT_ID")?,
+        classification,
+        datetime,
+        mean_motion_dot: optional_number("MEAN_MOTION_DOT")?,
+        mean_motion_ddot: optional_number("MEAN_MOTION_DDOT")?,
+        drag_term: optional_number("BSTAR")?,
+        element_set_number: optional_integer("ELEMENT_SET_NO")?,
+        inclination: number("INCLINATION")?,
+        right_ascension: number("RA_OF_ASC_NODE")?,
+        eccentricity,
+        argument_of_perigee: number("ARG_OF_PERICENTER")?,
+        mean_anomaly: number("MEAN_ANOMALY")?,
+        mean_motion,
+        revolution_number: optional_integer("REV_AT_EPOCH")?,
+        ephemeris_type: optional_integer("EPHEMERIS_TYPE")? as u8,
+    })
+}
+
+/// Calendar (`2024-01-01T12:00:00.000`) or day-of-year (`2024-001T12:00:00`) UTC epoch
+fn parse_epoch(epoch: &str) -> Option<NaiveDateTime> {
+    let epoch = epoch.trim_end_matches('Z');
+    NaiveDateTime::parse_from_str(epoch, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(epoch, "%Y-%jT%H:%M:%S%.f"))
+        .ok()
+}
+
+/// OMM parsing errors
+#[derive(Debug, Clone)]
+pub enum OmmError {
+    Empty,
+    Syntax(String),
+    MissingField(&'static str),
+    InvalidField { name: &'static str, message: String },
+    UnsupportedTheory(String),
+    MessageCount(usize),
+}
+
+impl std::fmt::Display for OmmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OmmError::Empty => write!(f, "OMM is empty"),
+            OmmError::Syntax(msg) => write!(f, "malformed OMM: {}", msg),
+            OmmError::MissingField(name) => write!(f, "missing required field {}", name),
+            OmmError::InvalidField { name, message } => write!(f, "invalid {}: {}", name, message),
+            OmmError::UnsupportedTheory(theory) => {
+                write!(f, "mean element theory {} is not supported (expected SGP4)", theory)
+            }
+            OmmError::MessageCount(count) => write!(f, "expected a single OMM, found {}", count),
+        }
+    }
+}
+
+impl std::error::Error for OmmError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ISS elements matching the TLE used across the test suite
+    const ISS_KVN: &str = "\
+CCSDS_OMM_VERS = 2.0
+COMMENT Example OMM
+CREATION_DATE = 2024-01-01T12:00:00
+ORIGINATOR = TEST
+OBJECT_NAME = ISS (ZARYA)
+OBJECT_ID = 1998-067A
+CENTER_NAME = EARTH
+REF_FRAME = TEME
+TIME_SYSTEM = UTC
+MEAN_ELEMENT_THEORY = SGP4
+EPOCH = 2024-01-01T12:00:00.000000
+MEAN_MOTION = 15.50377579 [rev/day]
+ECCENTRICITY = 0.0006703
+INCLINATION = 51.6400 [deg]
+RA_OF_ASC_NODE = 208.9163 [deg]
+ARG_OF_PERICENTER = 130.5360 [deg]
+MEAN_ANOMALY = 325.0288 [deg]
+EPHEMERIS_TYPE = 0
+CLASSIFICATION_TYPE = U
+NORAD_CAT_ID = 25544
+ELEMENT_SET_NO = 900
+REV_AT_EPOCH = 42309
+BSTAR = 0.10270E-3
+MEAN_MOTION_DOT = 0.00016717
+MEAN_MOTION_DDOT = 0.0
+";
+
+    const ISS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ndm><omm id="CCSDS_OMM_VERS" version="2.0">
+<header><CREATION_DATE/><ORIGINATOR/></header>
+<body><segment>
+<metadata><OBJECT_NAME>ISS (ZARYA)</OBJECT_NAME><OBJECT_ID>1998-067A</OBJECT_ID>
+<CENTER_NAME>EARTH</CENTER_NAME><REF_FRAME>TEME</REF_FRAME><TIME_SYSTEM>UTC</TIME_SYSTEM>
+<MEAN_ELEMENT_THEORY>SGP4</MEAN_ELEMENT_THEORY></metadata>
+<data><meanElements><EPOCH>2024-01-01T12:00:00.000000</EPOCH>
+<MEAN_MOTION>15.50377579</MEAN_MOTION><ECCENTRICITY>.0006703</ECCENTRICITY>
+<INCLINATION>51.6400</INCLINATION><RA_OF_ASC_NODE>208.9163</RA_OF_ASC_NODE>
+<ARG_OF_PERICENTER>130.5360</ARG_OF_PERICENTER><MEAN_ANOMALY>325.0288</MEAN_ANOMALY></meanElements>
+<tleParameters><EPHEMERIS_TYPE>0</EPHEMERIS_TYPE><CLASSIFICATION_TYPE>U</CLASSIFICATION_TYPE>
+<NORAD_CAT_ID>25544</NORAD_CAT_ID><ELEMENT_SET_NO>900</ELEMENT_SET_NO><REV_AT_EPOCH>42309</REV_AT_EPOCH>
+<BSTAR>.1027E-3</BSTAR><MEAN_MOTION_DOT>.00016717</MEAN_MOTION_DOT><MEAN_MOTION_DDOT>0</MEAN_MOTION_DDOT></tleParameters>
+</data></segment></body></omm></ndm>"#;
+
+    const ISS_JSON: &str = r#"[{"OBJECT_NAME":"ISS (ZARYA)","OBJECT_ID":"1998-067A",
+"EPOCH":"2024-01-01T12:00:00.000000","MEAN_MOTION":15.50377579,"ECCENTRICITY":0.0006703,
+"INCLINATION":51.64,"RA_OF_ASC_NODE":208.9163,"ARG_OF_PERICENTER":130.536,"MEAN_ANOMALY":325.0288,
+"EPHEMERIS_TYPE":0,"CLASSIFICATION_TYPE":"U","NORAD_CAT_ID":25544,"ELEMENT_SET_NO":900,
+"REV_AT_EPOCH":42309,"BSTAR":0.0001027,"MEAN_MOTION_DOT":0.00016717,"MEAN_MOTION_DDOT":0}]"#;
+
+    #[test]
+    fn test_encodings_match_tle() {
+        let tle = Elements::from_tle(
+            None,
+            b"1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008",
+            b"2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096",
+        )
+        .unwrap();
+
+        for text in [ISS_KVN, ISS_XML, ISS_JSON] {
+            let omm = parse(text).unwrap();
+            assert_eq!(omm.norad_id, tle.norad_id);
+            assert_eq!(omm.datetime, tle.datetime);
+            assert_eq!(omm.object_name.as_deref(), Some("ISS (ZARYA)"));
+            assert!((omm.mean_motion - tle.mean_motion).abs() < 1e-9);
+            assert!((omm.eccentricity - tle.eccentricity).abs() < 1e-12);
+            assert!((omm.inclination - tle.inclination).abs() < 1e-9);
+            assert!((omm.drag_term - tle.drag_term).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_day_of_year_epoch() {
+        let text = ISS_KVN.replace("2024-01-01T12:00:00.000000", "2024-001T12:00:00");
+        assert_eq!(parse(&text).unwrap().datetime, parse(ISS_KVN).unwrap().datetime);
+    }
+
+    #[test]
+    fn test_rejects_incomplete_or_unsupported() {
+        let missing = ISS_KVN.replace("MEAN_MOTION = 15.50377579 [rev/day]\n", "");
+        assert!(matches!(parse(&missing), Err(OmmError::MissingField("MEAN_MOTION"))));
+
+        let xp = ISS_KVN.replace("= SGP4", "= SGP4-XP");
+        assert!(matches!(parse(&xp), Err(OmmError::UnsupportedTheory(_))));
+
+        let two = format!("[{0},{0}]", ISS_JSON.trim_start_matches('[').trim_end_matches(']'));
+        assert!(matches!(parse(&two), Err(OmmError::MessageCount(2))));
+
+        assert!(matches!(parse("  \n"), Err(OmmError::Empty)));
+    }
+}
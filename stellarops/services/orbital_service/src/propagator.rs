@@ -1,17 +1,162 @@
 //! SGP4 orbital propagation implementation
 
+use std::sync::Arc;
+
 use chrono::{Datelike, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
 use sgp4::{Constants, Elements};
 use tracing::{debug, warn};
 
+use crate::cache;
+use crate::frames::{self, Frame};
+use crate::omm;
+use crate::solar;
+use crate::timescale::{self, TimeScale};
+use crate::tle;
+
+/// Orbit definition accepted by the propagation functions
+#[derive(Debug, Clone, Copy)]
+pub enum OrbitSource<'a> {
+    /// Two-line element set
+    Tle { line1: &'a str, line2: &'a str },
+    /// CCSDS OMM in KVN, XML or JSON encoding, with the catalog number decoded
+    /// when the source was built
+    Omm { text: &'a str, norad_id: Option<u64> },
+}
+
+impl<'a> OrbitSource<'a> {
+    /// OMM source; the document is parsed once here for its catalog number
+    pub fn omm(text: &'a str) -> Self {
+        let norad_id = omm::parse(text)
+            .ok()
+            .map(|elements| elements.norad_id)
+            .filter(|&norad_id| norad_id != 0);
+        OrbitSource::Omm { text, norad_id }
+    }
+
+    /// Parse into the SGP4 element set
+    pub fn elements(&self) -> Result<Elements, PropagationError> {
+        match *self {
+            OrbitSource::Tle { line1, line2 } => {
+                tle::parse(line1, line2).map_err(|e| PropagationError::TleParseError(e.to_string()))
+            }
+            OrbitSource::Omm { text, .. } => {
+                omm::parse(text).map_err(|e| PropagationError::OmmParseError(e.to_string()))
+            }
+        }
+    }
+
+    /// Parsed elements and initialised SGP4 constants, shared through the
+    /// process-wide cache
+    pub fn sgp4(&self) -> Result<Arc<Sgp4Orbit>, PropagationError> {
+        cache::get_or_try_insert(&self.cache_key(), || Sgp4Orbit::from_source(self))
+    }
+
+    /// Cache key; the orbit text itself, so re-issued elements get a new entry
+    fn cache_key(&self) -> String {
+        match *self {
+            OrbitSource::Tle { line1, line2 } => format!("TLE\n{}\n{}", line1, line2),
+            OrbitSource::Omm { text, .. } => format!("OMM\n{}", text),
+        }
+    }
+
+    /// Decoded NORAD catalog number, when the orbit carries one
+    pub fn norad_id(&self) -> Option<u64> {
+        match *self {
+            OrbitSource::Tle { line1, .. } => tle::catalog_number(line1, 1).ok(),
+            OrbitSource::Omm { norad_id, .. } => norad_id,
+        }
+    }
+
+    /// Reject a caller-supplied NORAD ID (digits or Alpha-5) that disagrees
+    /// with the orbit's catalog number
+    pub fn check_norad_id(&self, supplied: &str) -> Result<(), PropagationError> {
+        let expected = tle::parse_catalog_number(supplied.trim()).ok_or_else(|| {
+            PropagationError::InvalidParameter(format!("norad_id '{}' is not a catalog number", supplied))
+        })?;
+
+        match self.norad_id() {
+            Some(actual) if actual != expected => Err(PropagationError::InvalidParameter(format!(
+                "norad_id {} does not match catalog number {} of the orbit",
+                expected, actual
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Owned copy of an `OrbitSource`, for work handed to the compute pool
+#[derive(Debug, Clone)]
+pub enum OwnedOrbitSource {
+    Tle { line1: String, line2: String },
+    Omm { text: String, norad_id: Option<u64> },
+}
+
+impl OwnedOrbitSource {
+    pub fn as_source(&self) -> OrbitSource<'_> {
+        match self {
+            OwnedOrbitSource::Tle { line1, line2 } => OrbitSource::Tle { line1, line2 },
+            OwnedOrbitSource::Omm { text, norad_id } => OrbitSource::Omm {
+                text,
+                norad_id: *norad_id,
+            },
+        }
+    }
+}
+
+impl From<OrbitSource<'_>> for OwnedOrbitSource {
+    fn from(source: OrbitSource<'_>) -> Self {
+        match source {
+            OrbitSource::Tle { line1, line2 } => OwnedOrbitSource::Tle {
+                line1: line1.to_string(),
+                line2: line2.to_string(),
+            },
+            OrbitSource::Omm { text, norad_id } => OwnedOrbitSource::Omm {
+                text: text.to_string(),
+                norad_id,
+            },
+        }
+    }
+}
+
+/// Elements together with the SGP4 state initialised from them
+pub struct Sgp4Orbit {
+    pub elements: Elements,
+    pub constants: Constants,
+    /// Epoch of the elements as a UTC Unix time
+    pub epoch_unix: f64,
+}
+
+impl Sgp4Orbit {
+    /// Parse the orbit and initialise SGP4, bypassing the cache
+    pub fn from_source(source: &OrbitSource) -> Result<Self, PropagationError> {
+        let elements = source.elements()?;
+        // Create propagator with WGS84 constants
+        let constants = Constants::from_elements(&elements).map_err(|e| sgp4_error(e, 0.0))?;
+        let epoch_unix = tle_epoch_to_unix(&elements);
+        Ok(Self {
+            elements,
+            constants,
+            epoch_unix,
+        })
+    }
+}
+
 /// Result of orbital propagation
 #[derive(Debug, Clone)]
 pub struct PropagationResult {
-    pub position_km: [f64; 3],   // ECI position [x, y, z] in km
-    pub velocity_km_s: [f64; 3], // ECI velocity [vx, vy, vz] in km/s
+    pub position_km: [f64; 3],   // TEME position [x, y, z] in km
+    pub velocity_km_s: [f64; 3], // TEME velocity [vx, vy, vz] in km/s
     pub geodetic: GeodeticCoords,
 }
 
+impl PropagationResult {
+    /// Position and velocity expressed in the requested output frame
+    pub fn state_in(&self, frame: Frame, timestamp_unix: f64) -> ([f64; 3], [f64; 3]) {
+        frames::teme_to_frame(frame, &self.position_km, &self.velocity_km_s, timestamp_unix)
+    }
+}
+
 /// Geodetic coordinates
 #[derive(Debug, Clone)]
 pub struct GeodeticCoords {
@@ -20,31 +165,51 @@ pub struct GeodeticCoords {
     pub altitude_km: f64,
 }
 
+/// Decay of an orbit, bracketed by bisection between the element epoch and
+/// the first failing time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Decay {
+    /// Latest UTC Unix time with a valid SGP4 state
+    pub last_valid_unix: f64,
+    /// Estimated decay time: the earliest failing time found
+    pub decay_time_unix: f64,
+}
+
+/// Trajectory samples, cut short when the orbit decays
+#[derive(Debug, Clone)]
+pub struct Trajectory {
+    pub points: Vec<(f64, PropagationResult)>,
+    pub decay: Option<Decay>,
+}
+
 /// Parse TLE and propagate to given timestamp
+#[cfg(test)]
 pub fn propagate(
     tle_line1: &str,
     tle_line2: &str,
     timestamp_unix: i64,
 ) -> Result<PropagationResult, PropagationError> {
-    // Parse TLE
-    let elements = Elements::from_tle(
-        None,
-        tle_line1.as_bytes(),
-        tle_line2.as_bytes(),
-    ).map_err(|e| PropagationError::TleParseError(format!("{:?}", e)))?;
+    let source = OrbitSource::Tle { line1: tle_line1, line2: tle_line2 };
+    propagate_at(&source, timestamp_unix as f64)
+}
+
+/// Parse the orbit and propagate to a (fractional) UTC Unix time
+pub fn propagate_at(
+    source: &OrbitSource,
+    timestamp_unix: f64,
+) -> Result<PropagationResult, PropagationError> {
+    let orbit = source.sgp4()?;
 
     debug!(
-        "Parsed TLE for NORAD ID: {}, epoch: {:?}",
-        elements.norad_id, elements.datetime
+        "Using elements for NORAD ID: {}, epoch: {:?}",
+        orbit.elements.norad_id, orbit.elements.datetime
     );
 
-    // Create propagator with WGS84 constants
-    let constants = Constants::from_elements(&elements)
-        .map_err(|e| PropagationError::PropagatorError(format!("{:?}", e)))?;
+    let constants = &orbit.constants;
 
     // Calculate time since TLE epoch in minutes
-    let tle_epoch_unix = tle_epoch_to_unix(&elements);
-    let minutes_since_epoch = (timestamp_unix as f64 - tle_epoch_unix) / 60.0;
+    let tle_epoch_unix = orbit.epoch_unix;
+    let minutes_since_epoch = (timestamp_unix - tle_epoch_unix) / 60.0;
 
     debug!(
         "Propagating {} minutes from epoch",
@@ -52,9 +217,7 @@ pub fn propagate(
     );
 
     // Propagate
-    let prediction = constants
-        .propagate(minutes_since_epoch)
-        .map_err(|e| PropagationError::PropagatorError(format!("{:?}", e)))?;
+    let prediction = propagate_sgp4(constants, tle_epoch_unix, timestamp_unix)?;
 
     // Extract position and velocity
     let position_km = prediction.position;
@@ -71,6 +234,7 @@ pub fn propagate(
 }
 
 /// Propagate trajectory over a time range
+#[cfg(test)]
 pub fn propagate_trajectory(
     tle_line1: &str,
     tle_line2: &str,
@@ -78,47 +242,120 @@ pub fn propagate_trajectory(
     end_unix: i64,
     step_seconds: i64,
 ) -> Result<Vec<(i64, PropagationResult)>, PropagationError> {
-    let elements = Elements::from_tle(
-        None,
-        tle_line1.as_bytes(),
-        tle_line2.as_bytes(),
-    ).map_err(|e| PropagationError::TleParseError(format!("{:?}", e)))?;
-
-    let constants = Constants::from_elements(&elements)
-        .map_err(|e| PropagationError::PropagatorError(format!("{:?}", e)))?;
-
-    let tle_epoch_unix = tle_epoch_to_unix(&elements);
-    let mut results = Vec::new();
-
-    let mut timestamp = start_unix;
-    while timestamp <= end_unix {
-        let minutes_since_epoch = (timestamp as f64 - tle_epoch_unix) / 60.0;
-
-        match constants.propagate(minutes_since_epoch) {
-            Ok(prediction) => {
-                let geodetic = eci_to_geodetic(&prediction.position, timestamp);
-                results.push((
-                    timestamp,
-                    PropagationResult {
-                        position_km: prediction.position,
-                        velocity_km_s: prediction.velocity,
-                        geodetic,
-                    },
-                ));
-            }
-            Err(e) => {
-                warn!("Propagation failed at timestamp {}: {:?}", timestamp, e);
-            }
-        }
+    let trajectory = propagate_trajectory_in(
+        &OrbitSource::Tle { line1: tle_line1, line2: tle_line2 },
+        start_unix as f64,
+        end_unix as f64,
+        step_seconds as f64,
+        TimeScale::Utc,
+    )?;
+    Ok(trajectory
+        .points
+        .into_iter()
+        .map(|(timestamp, result)| (timestamp as i64, result))
+        .collect())
+}
+
+/// Propagate trajectory over a time range whose (fractional) timestamps are in `time_scale`.
+/// Sampling stops at the first decayed sample.
+pub fn propagate_trajectory_in(
+    source: &OrbitSource,
+    start_unix: f64,
+    end_unix: f64,
+    step_seconds: f64,
+    time_scale: TimeScale,
+) -> Result<Trajectory, PropagationError> {
+    let mut samples = trajectory_samples(source, start_unix, end_unix, step_seconds, time_scale)?;
+    let points = samples.by_ref().collect();
+
+    Ok(Trajectory {
+        points,
+        decay: samples.decay(),
+    })
+}
 
-        timestamp += step_seconds;
+/// Lazily sampled trajectory, for ranges too long to hold in memory. Yields
+/// points in time order and ends at the first decayed sample.
+pub fn trajectory_samples(
+    source: &OrbitSource,
+    start_unix: f64,
+    end_unix: f64,
+    step_seconds: f64,
+    time_scale: TimeScale,
+) -> Result<TrajectorySamples, PropagationError> {
+    if !step_seconds.is_finite() || step_seconds <= 0.0 {
+        return Err(PropagationError::InvalidParameter(
+            "step_seconds must be positive".to_string(),
+        ));
     }
 
-    Ok(results)
+    Ok(TrajectorySamples {
+        orbit: source.sgp4()?,
+        start_unix,
+        step_seconds,
+        // Offsets from the start rather than repeated addition, so fractional
+        // steps do not accumulate rounding error
+        steps: ((end_unix - start_unix) / step_seconds + 1e-9).floor().max(-1.0) as i64,
+        index: 0,
+        time_scale,
+        decay: None,
+    })
+}
+
+/// Iterator over `(timestamp, state)` samples of a trajectory
+pub struct TrajectorySamples {
+    orbit: Arc<Sgp4Orbit>,
+    start_unix: f64,
+    step_seconds: f64,
+    steps: i64,
+    index: i64,
+    time_scale: TimeScale,
+    decay: Option<Decay>,
+}
+
+impl TrajectorySamples {
+    /// Decay that ended the trajectory early, once reached
+    pub fn decay(&self) -> Option<Decay> {
+        self.decay
+    }
+}
+
+impl Iterator for TrajectorySamples {
+    type Item = (f64, PropagationResult);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.decay.is_none() && self.index <= self.steps {
+            let timestamp = self.start_unix + self.index as f64 * self.step_seconds;
+            let utc = timescale::to_utc(timestamp, self.time_scale);
+            self.index += 1;
+
+            match propagate_sgp4(&self.orbit.constants, self.orbit.epoch_unix, utc) {
+                Ok(prediction) => {
+                    let geodetic = eci_to_geodetic(&prediction.position, utc);
+                    return Some((
+                        timestamp,
+                        PropagationResult {
+                            position_km: prediction.position,
+                            velocity_km_s: prediction.velocity,
+                            geodetic,
+                        },
+                    ));
+                }
+                Err(PropagationError::Decayed(found)) => {
+                    warn!("Orbit decayed before timestamp {}: {:?}", timestamp, found);
+                    self.decay = Some(found);
+                }
+                Err(e) => {
+                    warn!("Propagation failed at timestamp {}: {}", timestamp, e);
+                }
+            }
+        }
+        None
+    }
 }
 
 /// Ground station location
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GroundStation {
     pub id: String,
     pub name: String,
@@ -126,6 +363,97 @@ pub struct GroundStation {
     pub longitude_deg: f64,
     pub altitude_m: f64,
     pub min_elevation_deg: f64,
+    /// Optional terrain/obstruction mask; the effective threshold is the
+    /// higher of this and `min_elevation_deg` at each azimuth
+    pub horizon_mask: Vec<HorizonMaskPoint>,
+}
+
+/// Minimum visible elevation at a given azimuth
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HorizonMaskPoint {
+    pub azimuth_deg: f64,       // Degrees from North, clockwise
+    pub min_elevation_deg: f64,
+}
+
+impl GroundStation {
+    /// Check that the coordinates and horizon mask entries are within range
+    pub fn validate(&self) -> Result<(), PropagationError> {
+        let fields = [
+            ("latitude", self.latitude_deg),
+            ("longitude", self.longitude_deg),
+            ("altitude", self.altitude_m),
+            ("min_elevation", self.min_elevation_deg),
+        ];
+        for (field, value) in fields {
+            if !value.is_finite() {
+                return Err(PropagationError::InvalidGroundStation(format!(
+                    "{} must be finite",
+                    field
+                )));
+            }
+        }
+        if !(-90.0..=90.0).contains(&self.latitude_deg) {
+            return Err(PropagationError::InvalidGroundStation(format!(
+                "latitude {} outside [-90, 90]",
+                self.latitude_deg
+            )));
+        }
+        if !(-180.0..=180.0).contains(&self.longitude_deg) {
+            return Err(PropagationError::InvalidGroundStation(format!(
+                "longitude {} outside [-180, 180]",
+                self.longitude_deg
+            )));
+        }
+        for (index, point) in self.horizon_mask.iter().enumerate() {
+            if !(0.0..=360.0).contains(&point.azimuth_deg) {
+                return Err(PropagationError::InvalidGroundStation(format!(
+                    "horizon_mask[{}]: azimuth {} outside [0, 360]",
+                    index, point.azimuth_deg
+                )));
+            }
+            if !(-90.0..=90.0).contains(&point.min_elevation_deg) {
+                return Err(PropagationError::InvalidGroundStation(format!(
+                    "horizon_mask[{}]: elevation {} outside [-90, 90]",
+                    index, point.min_elevation_deg
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Minimum elevation at `azimuth_deg` from a horizon mask sorted by azimuth.
+///
+/// Values are linearly interpolated between neighbouring entries, wrapping
+/// through North, and never drop below `floor_deg`.
+fn horizon_min_elevation(mask: &[HorizonMaskPoint], floor_deg: f64, azimuth_deg: f64) -> f64 {
+    let masked = match mask.len() {
+        0 => return floor_deg,
+        1 => mask[0].min_elevation_deg,
+        len => {
+            let azimuth = azimuth_deg.rem_euclid(360.0);
+            let index = mask.partition_point(|p| p.azimuth_deg < azimuth);
+            let (lower, upper) = if index == 0 || index == len {
+                (mask[len - 1], mask[0])
+            } else {
+                (mask[index - 1], mask[index])
+            };
+
+            let mut span = upper.azimuth_deg - lower.azimuth_deg;
+            let mut offset = azimuth - lower.azimuth_deg;
+            if span <= 0.0 {
+                span += 360.0;
+            }
+            if offset < 0.0 {
+                offset += 360.0;
+            }
+
+            let fraction = if span > 0.0 { offset / span } else { 0.0 };
+            lower.min_elevation_deg + fraction * (upper.min_elevation_deg - lower.min_elevation_deg)
+        }
+    };
+
+    masked.max(floor_deg)
 }
 
 /// Visibility pass information
@@ -138,9 +466,166 @@ pub struct VisibilityPass {
     pub aos_azimuth_deg: Option<f64>,
     pub los_azimuth_deg: Option<f64>,
     pub duration_seconds: Option<i64>,
+    pub aos_time_unix: f64,      // Refined AOS (fractional seconds)
+    pub los_time_unix: f64,      // Refined LOS (fractional seconds)
+    pub tca_time_unix: f64,      // Refined TCA (fractional seconds)
+    pub samples: Vec<PassSample>, // Range/Doppler curve, when requested
+    /// Longest sub-interval with the satellite sunlit (and, in optical mode,
+    /// the ground station dark)
+    pub sunlit_start_time_unix: Option<f64>,
+    pub sunlit_end_time_unix: Option<f64>,
+    pub visual_magnitude: Option<f64>, // Optical mode only
+}
+
+impl VisibilityPass {
+    /// Re-express the UTC times of a pass in another time scale
+    fn in_time_scale(mut self, time_scale: TimeScale) -> Self {
+        if time_scale == TimeScale::Utc {
+            return self;
+        }
+        let convert = |utc: f64| timescale::from_utc(utc, time_scale);
+
+        self.aos_time_unix = convert(self.aos_time_unix);
+        self.los_time_unix = convert(self.los_time_unix);
+        self.tca_time_unix = convert(self.tca_time_unix);
+        self.aos_timestamp = self.aos_time_unix.round() as i64;
+        self.los_timestamp = self.los_time_unix.round() as i64;
+        self.tca_timestamp = self.tca_timestamp.map(|_| self.tca_time_unix.round() as i64);
+        self.sunlit_start_time_unix = self.sunlit_start_time_unix.map(convert);
+        self.sunlit_end_time_unix = self.sunlit_end_time_unix.map(convert);
+        for sample in &mut self.samples {
+            sample.timestamp_unix = convert(sample.timestamp_unix);
+        }
+        self
+    }
+}
+
+/// Topocentric geometry from a ground station to a satellite
+#[derive(Debug, Clone, Copy)]
+pub struct LookAngles {
+    pub elevation_deg: f64,
+    pub azimuth_deg: f64,      // From North, clockwise
+    pub range_km: f64,         // Slant range
+    pub range_rate_km_s: f64,  // Positive when receding
+}
+
+/// Parameters for sampling range, range-rate and Doppler across each pass
+#[derive(Debug, Clone)]
+pub struct PassSampling {
+    pub step_seconds: f64,
+    pub downlink_frequency_hz: Option<f64>,
+    pub uplink_frequency_hz: Option<f64>,
+}
+
+impl PassSampling {
+    /// Check that the step and carrier frequencies are usable
+    pub fn validate(&self) -> Result<(), PropagationError> {
+        if !self.step_seconds.is_finite() || self.step_seconds < MIN_PASS_SAMPLE_STEP_SECONDS {
+            return Err(PropagationError::InvalidParameter(format!(
+                "pass sampling step must be at least {} s",
+                MIN_PASS_SAMPLE_STEP_SECONDS
+            )));
+        }
+        for frequency in [self.downlink_frequency_hz, self.uplink_frequency_hz].into_iter().flatten() {
+            if !frequency.is_finite() || frequency <= 0.0 {
+                return Err(PropagationError::InvalidParameter(
+                    "carrier frequencies must be positive".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Range, range-rate and Doppler at one instant of a pass
+#[derive(Debug, Clone)]
+pub struct PassSample {
+    pub timestamp_unix: f64,
+    pub elevation_deg: f64,
+    pub azimuth_deg: f64,
+    pub range_km: f64,
+    pub range_rate_km_s: f64,
+    pub downlink_doppler_hz: Option<f64>, // Shift received at the ground station
+    pub uplink_doppler_hz: Option<f64>,   // Shift received at the spacecraft
+}
+
+/// Sun elevation limit below which the ground station counts as dark
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Twilight {
+    Civil,
+    #[default]
+    Nautical,
+    Astronomical,
+}
+
+impl Twilight {
+    pub fn sun_elevation_deg(self) -> f64 {
+        match self {
+            Twilight::Civil => -6.0,
+            Twilight::Nautical => -12.0,
+            Twilight::Astronomical => -18.0,
+        }
+    }
+}
+
+/// Which passes count as visible
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VisibilityMode {
+    /// Any pass above the horizon mask
+    #[default]
+    Radio,
+    /// Only passes with the satellite sunlit while the station is dark
+    Optical(Twilight),
+}
+
+/// Optional outputs for visibility calculations
+#[derive(Debug, Clone, Default)]
+pub struct VisibilityOptions {
+    pub sampling: Option<PassSampling>,
+    pub mode: VisibilityMode,
+    /// Magnitude at 1000 km and 90 deg phase, for optical brightness estimates
+    pub standard_magnitude: Option<f64>,
+    /// Time scale of the search window and of all returned times
+    pub time_scale: TimeScale,
+}
+
+/// Speed of light in km/s
+const SPEED_OF_LIGHT_KM_S: f64 = 299_792.458;
+
+/// WGS84 equatorial radius (km)
+const EARTH_EQUATORIAL_RADIUS_KM: f64 = 6378.137;
+
+/// Smallest accepted step for pass sampling
+const MIN_PASS_SAMPLE_STEP_SECONDS: f64 = 0.1;
+
+/// Pass sampling step when the caller does not set one
+pub const DEFAULT_PASS_SAMPLE_STEP_SECONDS: f64 = 10.0;
+
+/// First-order Doppler shift of a carrier over a line of sight with the given range rate
+pub fn doppler_shift_hz(frequency_hz: f64, range_rate_km_s: f64) -> f64 {
+    -frequency_hz * range_rate_km_s / SPEED_OF_LIGHT_KM_S
 }
 
+/// Sampling interval used to bracket pass events before refinement
+const PASS_SEARCH_STEP_SECONDS: f64 = 30.0;
+
+/// Convergence tolerance for refined AOS/LOS/TCA times
+const PASS_TIME_TOLERANCE_SECONDS: f64 = 1e-3;
+
+/// Elevation assumed for sample times where SGP4 fails
+const FAILED_SAMPLE_ELEVATION_DEG: f64 = -90.0;
+
+/// Longest visibility window, to bound the pass search
+const MAX_VISIBILITY_WINDOW_SECONDS: f64 = 31.0 * 86400.0;
+
+/// Sampling interval used to bracket lighting changes within a pass
+const LIGHTING_SEARCH_STEP_SECONDS: f64 = 10.0;
+
+/// Standard magnitude assumed when the caller does not supply one
+const DEFAULT_STANDARD_MAGNITUDE: f64 = 4.0;
+
 /// Calculate visibility passes for a satellite over a ground station (full detail)
+#[cfg(test)]
 pub fn calculate_visibility_passes(
     tle_line1: &str,
     tle_line2: &str,
@@ -148,17 +633,33 @@ pub fn calculate_visibility_passes(
     start_unix: i64,
     end_unix: i64,
 ) -> Result<Vec<VisibilityPass>, PropagationError> {
-    // Parse TLE once
-    let elements = Elements::from_tle(
-        None,
-        tle_line1.as_bytes(),
-        tle_line2.as_bytes(),
-    ).map_err(|e| PropagationError::TleParseError(format!("{:?}", e)))?;
+    calculate_visibility_passes_with_options(
+        &OrbitSource::Tle { line1: tle_line1, line2: tle_line2 },
+        ground_station,
+        start_unix as f64,
+        end_unix as f64,
+        &VisibilityOptions::default(),
+    )
+}
 
-    let constants = Constants::from_elements(&elements)
-        .map_err(|e| PropagationError::PropagatorError(format!("{:?}", e)))?;
+/// Calculate visibility passes with optional per-pass outputs
+pub fn calculate_visibility_passes_with_options(
+    source: &OrbitSource,
+    ground_station: &GroundStation,
+    start_unix: f64,
+    end_unix: f64,
+    options: &VisibilityOptions,
+) -> Result<Vec<VisibilityPass>, PropagationError> {
+    check_window(start_unix, end_unix, MAX_VISIBILITY_WINDOW_SECONDS)?;
+    ground_station.validate()?;
+    if let Some(sampling) = &options.sampling {
+        sampling.validate()?;
+    }
 
-    let tle_epoch_unix = tle_epoch_to_unix(&elements);
+    // Shared with other requests for the same orbit
+    let orbit = source.sgp4()?;
+    let constants = &orbit.constants;
+    let tle_epoch_unix = orbit.epoch_unix;
 
     // Convert ground station position to ECEF
     let gs_ecef = geodetic_to_ecef(
@@ -167,158 +668,536 @@ pub fn calculate_visibility_passes(
         ground_station.altitude_m / 1000.0, // Convert to km
     );
 
-    let mut passes = Vec::new();
-    let step_seconds: i64 = 30; // Check every 30 seconds for passes
-    
-    let mut in_pass = false;
-    let mut current_pass_start: i64 = 0;
-    let mut current_pass_start_azimuth: f64 = 0.0;
-    let mut max_elevation: f64 = 0.0;
-    let mut tca_timestamp: i64 = 0;
-
-    let mut timestamp = start_unix;
-    while timestamp <= end_unix {
-        let minutes_since_epoch = (timestamp as f64 - tle_epoch_unix) / 60.0;
-
-        if let Ok(prediction) = constants.propagate(minutes_since_epoch) {
-            // Calculate elevation and azimuth from ground station
-            let (elevation, azimuth) = calculate_look_angles(
+    let topocentric = |timestamp: f64| -> Option<LookAngles> {
+        let minutes_since_epoch = (timestamp - tle_epoch_unix) / 60.0;
+
+        constants.propagate(minutes_since_epoch).ok().map(|prediction| {
+            calculate_look_angles(
                 &prediction.position,
+                &prediction.velocity,
                 &gs_ecef,
                 ground_station.latitude_deg,
                 ground_station.longitude_deg,
                 timestamp,
-            );
+            )
+        })
+    };
+    let look_angles = |timestamp: f64| {
+        topocentric(timestamp).map_or((FAILED_SAMPLE_ELEVATION_DEG, 0.0), |look| {
+            (look.elevation_deg, look.azimuth_deg)
+        })
+    };
 
-            let above_horizon = elevation >= ground_station.min_elevation_deg;
-
-            if above_horizon && !in_pass {
-                // Start of new pass
-                in_pass = true;
-                current_pass_start = timestamp;
-                current_pass_start_azimuth = azimuth;
-                max_elevation = elevation;
-                tca_timestamp = timestamp;
-            } else if above_horizon && in_pass {
-                // Update max elevation
-                if elevation > max_elevation {
-                    max_elevation = elevation;
-                    tca_timestamp = timestamp;
-                }
-            } else if !above_horizon && in_pass {
-                // End of pass
-                in_pass = false;
-                
-                // Re-calculate end azimuth
-                let (_, end_azimuth) = calculate_look_angles(
+    let mut horizon_mask = ground_station.horizon_mask.clone();
+    horizon_mask.sort_by(|a, b| a.azimuth_deg.total_cmp(&b.azimuth_deg));
+    let min_elevation = |azimuth_deg: f64| {
+        horizon_min_elevation(&horizon_mask, ground_station.min_elevation_deg, azimuth_deg)
+    };
+
+    let mut passes = find_passes(
+        look_angles,
+        min_elevation,
+        timescale::to_utc(start_unix, options.time_scale),
+        timescale::to_utc(end_unix, options.time_scale),
+    );
+
+    if let Some(sampling) = &options.sampling {
+        for pass in &mut passes {
+            pass.samples = sample_pass(topocentric, pass, sampling);
+        }
+    }
+
+    // Lighting: satellite outside the umbra and, for optical passes, the
+    // Sun below the twilight limit at the station
+    let sun_elevation = |timestamp: f64| {
+        calculate_look_angles(
+            &solar::sun_position_eci(timestamp),
+            &[0.0; 3],
+            &gs_ecef,
+            ground_station.latitude_deg,
+            ground_station.longitude_deg,
+            timestamp,
+        )
+        .elevation_deg
+    };
+    let lighting_margin = |timestamp: f64| {
+        let minutes_since_epoch = (timestamp - tle_epoch_unix) / 60.0;
+        let sunlit = constants.propagate(minutes_since_epoch).map_or(-1.0, |prediction| {
+            solar::shadow_margins(&prediction.position, &solar::sun_position_eci(timestamp)).1
+        });
+
+        match options.mode {
+            VisibilityMode::Radio => sunlit,
+            VisibilityMode::Optical(twilight) => {
+                sunlit.min((twilight.sun_elevation_deg() - sun_elevation(timestamp)).to_radians())
+            }
+        }
+    };
+
+    for pass in &mut passes {
+        let lit = find_negative_intervals(
+            |t| -lighting_margin(t),
+            pass.aos_time_unix,
+            pass.los_time_unix,
+            LIGHTING_SEARCH_STEP_SECONDS,
+        )
+        .into_iter()
+        .max_by(|a, b| (a.1 - a.0).total_cmp(&(b.1 - b.0)));
+
+        let Some((lit_start, lit_end)) = lit else {
+            continue;
+        };
+        pass.sunlit_start_time_unix = Some(lit_start);
+        pass.sunlit_end_time_unix = Some(lit_end);
+
+        if let VisibilityMode::Optical(_) = options.mode {
+            // Brightness nearest the highest point of the visible part
+            let t = pass.tca_time_unix.clamp(lit_start, lit_end);
+            if let Ok(prediction) = constants.propagate((t - tle_epoch_unix) / 60.0) {
+                pass.visual_magnitude = Some(solar::visual_magnitude(
+                    options.standard_magnitude.unwrap_or(DEFAULT_STANDARD_MAGNITUDE),
                     &prediction.position,
-                    &gs_ecef,
-                    ground_station.latitude_deg,
-                    ground_station.longitude_deg,
-                    timestamp - step_seconds,
-                );
-
-                passes.push(VisibilityPass {
-                    aos_timestamp: current_pass_start,
-                    los_timestamp: timestamp,
-                    max_elevation_deg: max_elevation,
-                    tca_timestamp: Some(tca_timestamp),
-                    aos_azimuth_deg: Some(current_pass_start_azimuth),
-                    los_azimuth_deg: Some(end_azimuth),
-                    duration_seconds: Some(timestamp - current_pass_start),
-                });
+                    &solar::sun_position_eci(t),
+                    &frames::itrf_to_teme(&gs_ecef, t),
+                ));
             }
         }
+    }
 
-        timestamp += step_seconds;
+    if let VisibilityMode::Optical(_) = options.mode {
+        passes.retain(|pass| pass.sunlit_start_time_unix.is_some());
     }
 
-    // Handle pass that extends beyond time window
-    if in_pass {
+    debug!(
+        "Found {} visibility passes over {} ({})",
+        passes.len(),
+        ground_station.name,
+        ground_station.id
+    );
+    Ok(passes
+        .into_iter()
+        .map(|pass| pass.in_time_scale(options.time_scale))
+        .collect())
+}
+
+/// Reject a time window that is not finite, not increasing or longer than
+/// `max_seconds`
+fn check_window(start: f64, end: f64, max_seconds: f64) -> Result<(), PropagationError> {
+    if !start.is_finite() || !end.is_finite() {
+        return Err(PropagationError::InvalidParameter(
+            "window start and end must be finite".to_string(),
+        ));
+    }
+    if end <= start {
+        return Err(PropagationError::InvalidParameter(
+            "end time must be after start time".to_string(),
+        ));
+    }
+    if end - start > max_seconds {
+        return Err(PropagationError::InvalidParameter(format!(
+            "window exceeds {} days",
+            max_seconds / 86400.0
+        )));
+    }
+    Ok(())
+}
+
+/// Find passes above the azimuth-dependent `min_elevation` within `[start, end]`.
+///
+/// The window is sampled coarsely to bracket every local maximum of the
+/// elevation margin above the mask, including short passes that peak between
+/// two samples. Each maximum is refined by golden-section search, the AOS/LOS
+/// mask crossings around it are refined with Brent's method, and TCA is the
+/// refined elevation maximum between AOS and LOS. Only three samples are kept
+/// while walking the window; the crossings are found by stepping out from the
+/// peak on the same sample grid.
+fn find_passes<F, M>(look_angles: F, min_elevation: M, start: f64, end: f64) -> Vec<VisibilityPass>
+where
+    F: Fn(f64) -> (f64, f64),
+    M: Fn(f64) -> f64,
+{
+    let elevation = |t: f64| look_angles(t).0;
+    let margin = |t: f64| {
+        let (elevation, azimuth) = look_angles(t);
+        elevation - min_elevation(azimuth)
+    };
+
+    // Sample grid over the window, always including its end point
+    let last = ((end - start) / PASS_SEARCH_STEP_SECONDS).ceil().max(0.0) as usize;
+    let time_at = |index: usize| (start + index as f64 * PASS_SEARCH_STEP_SECONDS).min(end);
+    // Samples before (or at) `t`, searched from `from` down
+    let count_before = |from: usize, t: f64, inclusive: bool| {
+        (0..=from.min(last))
+            .rev()
+            .find(|&j| time_at(j) < t || (inclusive && time_at(j) == t))
+            .map_or(0, |j| j + 1)
+    };
+
+    let mut passes: Vec<VisibilityPass> = Vec::new();
+
+    // Margins at the previous, current and next sample
+    let mut current = margin(time_at(0));
+    let mut previous = current;
+    for i in 0..=last {
+        let next = if i < last {
+            margin(time_at(i + 1))
+        } else {
+            f64::NEG_INFINITY
+        };
+        let rising = i == 0 || current >= previous;
+        let falling = i == last || current > next;
+        (previous, current) = (current, next);
+        if !(rising && falling) {
+            continue;
+        }
+
+        // Refine the maximum between the neighbouring samples
+        let lower = time_at(i.saturating_sub(1));
+        let upper = time_at((i + 1).min(last));
+        let peak = find_maximum(margin, lower, upper, PASS_TIME_TOLERANCE_SECONDS);
+
+        if margin(peak) < 0.0 {
+            continue;
+        }
+
+        // A second maximum inside an already recorded pass
+        if passes.last().is_some_and(|last| peak <= last.los_time_unix) {
+            continue;
+        }
+
+        // AOS: last sample below the mask before the peak, refined
+        let before = count_before(i + 1, peak, false);
+        let aos = match (0..before).rev().find(|&j| margin(time_at(j)) < 0.0) {
+            Some(j) => {
+                let bracket_end = if j + 1 < before { time_at(j + 1) } else { peak };
+                find_root(margin, time_at(j), bracket_end, PASS_TIME_TOLERANCE_SECONDS)
+            }
+            None => start, // Pass already in progress at window start
+        };
+
+        // LOS: first sample below the mask after the peak, refined
+        let after = count_before(i + 1, peak, true);
+        let los = match (after..=last).find(|&j| margin(time_at(j)) < 0.0) {
+            Some(j) => {
+                let bracket_start = if j > after { time_at(j - 1) } else { peak };
+                find_root(
+                    margin,
+                    bracket_start,
+                    time_at(j),
+                    PASS_TIME_TOLERANCE_SECONDS,
+                )
+            }
+            None => end, // Pass extends beyond window end
+        };
+
+        let tca = find_maximum(elevation, aos, los, PASS_TIME_TOLERANCE_SECONDS);
+
+        let aos_timestamp = aos.round() as i64;
+        let los_timestamp = los.round() as i64;
+
         passes.push(VisibilityPass {
-            aos_timestamp: current_pass_start,
-            los_timestamp: end_unix,
-            max_elevation_deg: max_elevation,
-            tca_timestamp: Some(tca_timestamp),
-            aos_azimuth_deg: Some(current_pass_start_azimuth),
-            los_azimuth_deg: None, // Unknown
-            duration_seconds: Some(end_unix - current_pass_start),
+            aos_timestamp,
+            los_timestamp,
+            max_elevation_deg: elevation(tca),
+            tca_timestamp: Some(tca.round() as i64),
+            aos_azimuth_deg: Some(look_angles(aos).1),
+            los_azimuth_deg: Some(look_angles(los).1),
+            duration_seconds: Some(los_timestamp - aos_timestamp),
+            aos_time_unix: aos,
+            los_time_unix: los,
+            tca_time_unix: tca,
+            samples: Vec::new(),
+            sunlit_start_time_unix: None,
+            sunlit_end_time_unix: None,
+            visual_magnitude: None,
         });
     }
 
-    debug!("Found {} visibility passes", passes.len());
-    Ok(passes)
+    passes
 }
 
-/// TASK-159: Calculate visibility passes for a ground station (simplified API)
-pub fn calculate_visibility(
-    tle_line1: &str,
-    tle_line2: &str,
-    gs_lat_deg: &f64,
-    gs_lon_deg: &f64,
-    gs_alt_km: f64,
-    min_elevation_deg: f64,
-    start_unix: i64,
-    end_unix: i64,
-) -> Result<Vec<VisibilityPass>, PropagationError> {
-    // Generate trajectory with 10-second steps for visibility calculation
-    let trajectory = propagate_trajectory(tle_line1, tle_line2, start_unix, end_unix, 10)?;
-
-    let mut passes = Vec::new();
-    let mut in_pass = false;
-    let mut pass_start: i64 = 0;
-    let mut max_elevation = 0.0;
-
-    for (timestamp, result) in trajectory {
-        let elevation = calculate_elevation(
-            &result.position_km,
-            *gs_lat_deg,
-            *gs_lon_deg,
-            gs_alt_km,
-            timestamp,
-        );
+/// Sample range, range-rate and Doppler from AOS to LOS inclusive
+fn sample_pass<F>(topocentric: F, pass: &VisibilityPass, sampling: &PassSampling) -> Vec<PassSample>
+where
+    F: Fn(f64) -> Option<LookAngles>,
+{
+    let mut times = Vec::new();
+    let mut index = 0;
+    loop {
+        let t = pass.aos_time_unix + index as f64 * sampling.step_seconds;
+        if t >= pass.los_time_unix {
+            break;
+        }
+        times.push(t);
+        index += 1;
+    }
+    times.push(pass.los_time_unix);
+
+    times
+        .into_iter()
+        .filter_map(|t| {
+            topocentric(t).map(|look| PassSample {
+                timestamp_unix: t,
+                elevation_deg: look.elevation_deg,
+                azimuth_deg: look.azimuth_deg,
+                range_km: look.range_km,
+                range_rate_km_s: look.range_rate_km_s,
+                downlink_doppler_hz: sampling
+                    .downlink_frequency_hz
+                    .map(|f| doppler_shift_hz(f, look.range_rate_km_s)),
+                uplink_doppler_hz: sampling
+                    .uplink_frequency_hz
+                    .map(|f| doppler_shift_hz(f, look.range_rate_km_s)),
+            })
+        })
+        .collect()
+}
 
-        if elevation >= min_elevation_deg {
-            if !in_pass {
-                // Start of pass
-                in_pass = true;
-                pass_start = timestamp;
-                max_elevation = elevation;
-            } else {
-                // Update max elevation
-                if elevation > max_elevation {
-                    max_elevation = elevation;
-                }
+/// Part of the Earth shadow a satellite passes through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadowKind {
+    Penumbra,
+    Umbra,
+}
+
+/// A contiguous interval spent in one part of the Earth shadow
+#[derive(Debug, Clone)]
+pub struct EclipseInterval {
+    pub kind: ShadowKind,
+    pub entry_time_unix: f64,
+    pub exit_time_unix: f64,
+}
+
+/// Sampling interval used to bracket shadow boundary crossings
+const ECLIPSE_SEARCH_STEP_SECONDS: f64 = 30.0;
+
+/// Longest eclipse window, to bound the shadow scans
+const MAX_ECLIPSE_WINDOW_SECONDS: f64 = 31.0 * 86400.0;
+
+/// Calculate umbra and penumbra intervals over a time window, in time order.
+///
+/// A typical eclipse yields penumbra entry, umbra and penumbra exit intervals.
+/// The window and the interval times are read on the clock of `time_scale`.
+pub fn calculate_eclipses(
+    source: &OrbitSource,
+    start_unix: f64,
+    end_unix: f64,
+    time_scale: TimeScale,
+) -> Result<Vec<EclipseInterval>, PropagationError> {
+    check_window(start_unix, end_unix, MAX_ECLIPSE_WINDOW_SECONDS)?;
+    let start = timescale::to_utc(start_unix, time_scale);
+    let end = timescale::to_utc(end_unix, time_scale);
+
+    let orbit = source.sgp4()?;
+    let constants = &orbit.constants;
+    let tle_epoch_unix = orbit.epoch_unix;
+
+    // Failed samples are treated as sunlit so they never open an interval
+    let margins = |timestamp: f64| -> (f64, f64) {
+        let minutes_since_epoch = (timestamp - tle_epoch_unix) / 60.0;
+
+        constants.propagate(minutes_since_epoch).map_or((1.0, 1.0), |prediction| {
+            solar::shadow_margins(&prediction.position, &solar::sun_position_eci(timestamp))
+        })
+    };
+
+    let shadow = find_negative_intervals(|t| margins(t).0, start, end, ECLIPSE_SEARCH_STEP_SECONDS);
+    let umbra = find_negative_intervals(|t| margins(t).1, start, end, ECLIPSE_SEARCH_STEP_SECONDS);
+
+    // Split each shadow interval into penumbra around any umbra it contains
+    let mut eclipses = Vec::new();
+    for (entry, exit) in shadow {
+        let mut cursor = entry;
+        for &(umbra_entry, umbra_exit) in umbra.iter().filter(|(u_entry, u_exit)| *u_entry < exit && *u_exit > entry) {
+            if umbra_entry > cursor {
+                eclipses.push(EclipseInterval {
+                    kind: ShadowKind::Penumbra,
+                    entry_time_unix: cursor,
+                    exit_time_unix: umbra_entry,
+                });
             }
-        } else if in_pass {
-            // End of pass
-            passes.push(VisibilityPass {
-                aos_timestamp: pass_start,
-                los_timestamp: timestamp,
-                max_elevation_deg: max_elevation,
-                tca_timestamp: None,
-                aos_azimuth_deg: None,
-                los_azimuth_deg: None,
-                duration_seconds: None,
+            eclipses.push(EclipseInterval {
+                kind: ShadowKind::Umbra,
+                entry_time_unix: umbra_entry.max(entry),
+                exit_time_unix: umbra_exit.min(exit),
+            });
+            cursor = umbra_exit;
+        }
+        if cursor < exit {
+            eclipses.push(EclipseInterval {
+                kind: ShadowKind::Penumbra,
+                entry_time_unix: cursor,
+                exit_time_unix: exit,
             });
-            in_pass = false;
         }
     }
 
-    // Handle case where pass is still active at end of time range
-    if in_pass {
-        passes.push(VisibilityPass {
-            aos_timestamp: pass_start,
-            los_timestamp: end_unix,
-            max_elevation_deg: max_elevation,
-            tca_timestamp: None,
-            aos_azimuth_deg: None,
-            los_azimuth_deg: None,
-            duration_seconds: None,
-        });
+    debug!("Found {} eclipse intervals", eclipses.len());
+    Ok(eclipses
+        .into_iter()
+        .map(|interval| EclipseInterval {
+            entry_time_unix: timescale::from_utc(interval.entry_time_unix, time_scale),
+            exit_time_unix: timescale::from_utc(interval.exit_time_unix, time_scale),
+            ..interval
+        })
+        .collect())
+}
+
+/// Fraction of the solar disc visible from a propagated position
+pub fn illumination_fraction(position_km: &[f64; 3], timestamp_unix: f64) -> f64 {
+    solar::illumination_fraction(position_km, &solar::sun_position_eci(timestamp_unix))
+}
+
+/// Intervals within `[start, end]` where `f` is negative.
+///
+/// Sign changes are bracketed by sampling every `step` seconds and refined
+/// with Brent's method; intervals are clipped to the window.
+fn find_negative_intervals<F>(f: F, start: f64, end: f64, step: f64) -> Vec<(f64, f64)>
+where
+    F: Fn(f64) -> f64,
+{
+    let mut intervals = Vec::new();
+    let mut previous_time = start;
+    let mut previous_inside = f(start) < 0.0;
+    let mut entry = if previous_inside { Some(start) } else { None };
+
+    let mut index = 1;
+    while previous_time < end {
+        let t = (start + index as f64 * step).min(end);
+        let inside = f(t) < 0.0;
+
+        if inside != previous_inside {
+            let crossing = find_root(&f, previous_time, t, PASS_TIME_TOLERANCE_SECONDS);
+            match entry.take() {
+                Some(entry_time) => intervals.push((entry_time, crossing)),
+                None => entry = Some(crossing),
+            }
+        }
+
+        previous_time = t;
+        previous_inside = inside;
+        index += 1;
+    }
+
+    if let Some(entry_time) = entry {
+        intervals.push((entry_time, end));
+    }
+
+    intervals
+}
+
+/// Locate the maximum of a unimodal function on `[a, b]` by golden-section search
+fn find_maximum<F>(f: F, mut a: f64, mut b: f64, tolerance: f64) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    let inv_phi = (5.0_f64.sqrt() - 1.0) / 2.0;
+
+    let mut c = b - inv_phi * (b - a);
+    let mut d = a + inv_phi * (b - a);
+    let mut fc = f(c);
+    let mut fd = f(d);
+
+    while b - a > tolerance {
+        if fc > fd {
+            b = d;
+            d = c;
+            fd = fc;
+            c = b - inv_phi * (b - a);
+            fc = f(c);
+        } else {
+            a = c;
+            c = d;
+            fc = fd;
+            d = a + inv_phi * (b - a);
+            fd = f(d);
+        }
+    }
+
+    (a + b) / 2.0
+}
+
+/// Find a root of `f` bracketed by `[a, b]` using Brent's method.
+///
+/// If the bracket does not straddle a sign change, the endpoint closest to
+/// zero is returned.
+fn find_root<F>(f: F, a: f64, b: f64, tolerance: f64) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    const MAX_ITERATIONS: usize = 100;
+
+    let (mut a, mut b) = (a, b);
+    let (mut fa, mut fb) = (f(a), f(b));
+
+    if fa == 0.0 {
+        return a;
+    }
+    if fb == 0.0 {
+        return b;
+    }
+    if fa * fb > 0.0 {
+        return if fa.abs() < fb.abs() { a } else { b };
+    }
+
+    if fa.abs() < fb.abs() {
+        std::mem::swap(&mut a, &mut b);
+        std::mem::swap(&mut fa, &mut fb);
+    }
+
+    let mut c = a;
+    let mut fc = fa;
+    let mut d = c;
+    let mut bisected = true;
+
+    for _ in 0..MAX_ITERATIONS {
+        if fb == 0.0 || (b - a).abs() < tolerance {
+            break;
+        }
+
+        let mut s = if fa != fc && fb != fc {
+            // Inverse quadratic interpolation
+            a * fb * fc / ((fa - fb) * (fa - fc))
+                + b * fa * fc / ((fb - fa) * (fb - fc))
+                + c * fa * fb / ((fc - fa) * (fc - fb))
+        } else {
+            // Secant step
+            b - fb * (b - a) / (fb - fa)
+        };
+
+        let quarter = (3.0 * a + b) / 4.0;
+        let outside = !(s > quarter.min(b) && s < quarter.max(b));
+        let slow = if bisected {
+            (s - b).abs() >= (b - c).abs() / 2.0 || (b - c).abs() < tolerance
+        } else {
+            (s - b).abs() >= (c - d).abs() / 2.0 || (c - d).abs() < tolerance
+        };
+
+        bisected = outside || slow;
+        if bisected {
+            s = (a + b) / 2.0;
+        }
+
+        let fs = f(s);
+        d = c;
+        c = b;
+        fc = fb;
+
+        if fa * fs < 0.0 {
+            b = s;
+            fb = fs;
+        } else {
+            a = s;
+            fa = fs;
+        }
+
+        if fa.abs() < fb.abs() {
+            std::mem::swap(&mut a, &mut b);
+            std::mem::swap(&mut fa, &mut fb);
+        }
     }
 
-    Ok(passes)
+    b
 }
 
 /// Convert geodetic coordinates to ECEF
@@ -345,24 +1224,18 @@ fn geodetic_to_ecef(lat_deg: f64, lon_deg: f64, alt_km: f64) -> [f64; 3] {
     ]
 }
 
-/// Calculate look angles (elevation, azimuth) from ground station to satellite
+/// Calculate look angles, slant range and range-rate from ground station to satellite
 fn calculate_look_angles(
     sat_eci: &[f64; 3],
+    sat_velocity_eci: &[f64; 3],
     gs_ecef: &[f64; 3],
     gs_lat_deg: f64,
     gs_lon_deg: f64,
-    timestamp_unix: i64,
-) -> (f64, f64) {
-    // Convert satellite ECI to ECEF
-    let gmst = calculate_gmst(timestamp_unix);
-    let cos_gmst = gmst.cos();
-    let sin_gmst = gmst.sin();
-
-    let sat_ecef = [
-        sat_eci[0] * cos_gmst + sat_eci[1] * sin_gmst,
-        -sat_eci[0] * sin_gmst + sat_eci[1] * cos_gmst,
-        sat_eci[2],
-    ];
+    timestamp_unix: f64,
+) -> LookAngles {
+    // Earth-fixed state of the satellite (velocity relative to the rotating Earth)
+    let (sat_ecef, sat_velocity_ecef) =
+        frames::teme_to_frame(Frame::Itrf, sat_eci, sat_velocity_eci, timestamp_unix);
 
     // Vector from ground station to satellite in ECEF
     let range_ecef = [
@@ -387,66 +1260,29 @@ fn calculate_look_angles(
 
     let range = (s * s + e * e + z * z).sqrt();
 
+    // Range-rate is the velocity component along the line of sight
+    let range_rate = (range_ecef[0] * sat_velocity_ecef[0]
+        + range_ecef[1] * sat_velocity_ecef[1]
+        + range_ecef[2] * sat_velocity_ecef[2])
+        / range;
+
     // Elevation angle
     let elevation_rad = (z / range).asin();
     let elevation_deg = elevation_rad.to_degrees();
 
-    // Azimuth angle (from North, clockwise)
-    let azimuth_rad = (-s).atan2(e);
+    // Azimuth angle (from North, clockwise); North is -S in the SEZ frame
+    let azimuth_rad = e.atan2(-s);
     let mut azimuth_deg = azimuth_rad.to_degrees();
     if azimuth_deg < 0.0 {
         azimuth_deg += 360.0;
     }
 
-    (elevation_deg, azimuth_deg)
-}
-
-/// Calculate elevation angle from ground station to satellite (simplified)
-fn calculate_elevation(
-    sat_position_eci: &[f64; 3],
-    gs_lat_deg: f64,
-    gs_lon_deg: f64,
-    gs_alt_km: f64,
-    timestamp: i64,
-) -> f64 {
-    // Convert ground station to ECEF
-    let gs_ecef = geodetic_to_ecef(gs_lat_deg, gs_lon_deg, gs_alt_km);
-
-    // Convert satellite ECI to ECEF
-    let gmst = calculate_gmst(timestamp);
-    let cos_gmst = gmst.cos();
-    let sin_gmst = gmst.sin();
-    let sat_ecef = [
-        sat_position_eci[0] * cos_gmst + sat_position_eci[1] * sin_gmst,
-        -sat_position_eci[0] * sin_gmst + sat_position_eci[1] * cos_gmst,
-        sat_position_eci[2],
-    ];
-
-    // Range vector from ground station to satellite
-    let range = [
-        sat_ecef[0] - gs_ecef[0],
-        sat_ecef[1] - gs_ecef[1],
-        sat_ecef[2] - gs_ecef[2],
-    ];
-
-    // Convert to SEZ (South-East-Zenith) coordinates
-    let lat_rad = gs_lat_deg.to_radians();
-    let lon_rad = gs_lon_deg.to_radians();
-
-    let sin_lat = lat_rad.sin();
-    let cos_lat = lat_rad.cos();
-    let sin_lon = lon_rad.sin();
-    let cos_lon = lon_rad.cos();
-
-    // Rotation matrix to SEZ
-    let s = sin_lat * cos_lon * range[0] + sin_lat * sin_lon * range[1] - cos_lat * range[2];
-    let e = -sin_lon * range[0] + cos_lon * range[1];
-    let z = cos_lat * cos_lon * range[0] + cos_lat * sin_lon * range[1] + sin_lat * range[2];
-
-    // Calculate elevation
-    let range_magnitude = (s * s + e * e + z * z).sqrt();
-    let elevation_rad = (z / range_magnitude).asin();
-    elevation_rad.to_degrees()
+    LookAngles {
+        elevation_deg,
+        azimuth_deg,
+        range_km: range,
+        range_rate_km_s: range_rate,
+    }
 }
 
 /// Convert TLE epoch to Unix timestamp
@@ -456,12 +1292,12 @@ fn tle_epoch_to_unix(elements: &Elements) -> f64 {
     
     let datetime = Utc
         .with_ymd_and_hms(
-            dt.year() as i32,
-            dt.month() as u32,
-            dt.day() as u32,
-            dt.hour() as u32,
-            dt.minute() as u32,
-            dt.second() as u32,
+            dt.year(),
+            dt.month(),
+            dt.day(),
+            dt.hour(),
+            dt.minute(),
+            dt.second(),
         )
         .single()
         .expect("Invalid datetime");
@@ -471,27 +1307,15 @@ fn tle_epoch_to_unix(elements: &Elements) -> f64 {
     datetime.timestamp() as f64 + (nanos as f64 / 1_000_000_000.0)
 }
 
-/// Convert ECI position to geodetic coordinates
-/// Simplified implementation - for production, use a proper geodetic library
-fn eci_to_geodetic(position_km: &[f64; 3], timestamp_unix: i64) -> GeodeticCoords {
-    let x = position_km[0];
-    let y = position_km[1];
-    let z = position_km[2];
-
+/// Convert TEME position to geodetic coordinates
+fn eci_to_geodetic(position_km: &[f64; 3], timestamp_unix: f64) -> GeodeticCoords {
     // WGS84 parameters
     let a = 6378.137; // Equatorial radius in km
     let f = 1.0 / 298.257223563; // Flattening
     let e2 = 2.0 * f - f * f; // First eccentricity squared
 
-    // Calculate GMST (Greenwich Mean Sidereal Time) for longitude
-    let gmst = calculate_gmst(timestamp_unix);
-
-    // ECI to ECEF rotation (simplified)
-    let cos_gmst = gmst.cos();
-    let sin_gmst = gmst.sin();
-    let x_ecef = x * cos_gmst + y * sin_gmst;
-    let y_ecef = -x * sin_gmst + y * cos_gmst;
-    let z_ecef = z;
+    // TEME to Earth-fixed
+    let [x_ecef, y_ecef, z_ecef] = frames::teme_to_itrf(position_km, timestamp_unix);
 
     // Longitude
     let longitude_rad = y_ecef.atan2(x_ecef);
@@ -526,57 +1350,235 @@ fn eci_to_geodetic(position_km: &[f64; 3], timestamp_unix: i64) -> GeodeticCoord
     }
 }
 
-/// Calculate Greenwich Mean Sidereal Time in radians
-fn calculate_gmst(timestamp_unix: i64) -> f64 {
-    // Julian date at Unix epoch (1970-01-01 00:00:00 UTC)
-    const JD_UNIX_EPOCH: f64 = 2440587.5;
-    
-    // Convert Unix timestamp to Julian date
-    let jd = JD_UNIX_EPOCH + (timestamp_unix as f64 / 86400.0);
-    
-    // Julian centuries from J2000.0
-    let t = (jd - 2451545.0) / 36525.0;
-    
-    // GMST in degrees
-    let gmst_deg = 280.46061837 
-        + 360.98564736629 * (jd - 2451545.0)
-        + 0.000387933 * t * t 
-        - t * t * t / 38710000.0;
-    
-    // Normalize to [0, 360)
-    let gmst_normalized = ((gmst_deg % 360.0) + 360.0) % 360.0;
-    
-    gmst_normalized.to_radians()
-}
-
 /// Propagation errors
 #[derive(Debug, Clone)]
 pub enum PropagationError {
     TleParseError(String),
-    PropagatorError(String),
-    InvalidTimestamp(String),
+    OmmParseError(String),
+    /// The orbit decayed before the requested time
+    Decayed(Decay),
+    /// Mean or perturbed eccentricity left [0, 1) during propagation
+    EccentricityOutOfRange { eccentricity: f64, minutes_since_epoch: f64 },
+    TimeOutOfValidity(String),
+    InvalidGroundStation(String),
+    InvalidParameter(String),
+    /// No orbit was given and the satellite_id is not registered
+    SatelliteNotFound(String),
+    /// The station_id is not registered
+    GroundStationNotFound(String),
+    /// No open live stream has the session ID
+    SessionNotFound(String),
+    /// A service-wide limit is reached; the request may succeed later
+    Unavailable(String),
+    Internal(String),
+}
+
+impl PropagationError {
+    /// Machine-readable classification shared by the HTTP and gRPC APIs
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            PropagationError::TleParseError(_) | PropagationError::OmmParseError(_) => {
+                ErrorCode::TleParseError
+            }
+            PropagationError::Decayed(_) => ErrorCode::SatelliteDecayed,
+            PropagationError::EccentricityOutOfRange { .. } => ErrorCode::EccentricityOutOfRange,
+            PropagationError::TimeOutOfValidity(_) => ErrorCode::TimeOutOfValidity,
+            PropagationError::InvalidGroundStation(_) | PropagationError::InvalidParameter(_) => {
+                ErrorCode::InvalidArgument
+            }
+            PropagationError::SatelliteNotFound(_) => ErrorCode::SatelliteNotFound,
+            PropagationError::GroundStationNotFound(_) => ErrorCode::GroundStationNotFound,
+            PropagationError::SessionNotFound(_) => ErrorCode::SessionNotFound,
+            PropagationError::Unavailable(_) => ErrorCode::Unavailable,
+            PropagationError::Internal(_) => ErrorCode::Internal,
+        }
+    }
+
+    /// Decay details of a `Decayed` error
+    pub fn decay(&self) -> Option<Decay> {
+        match self {
+            PropagationError::Decayed(decay) => Some(*decay),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for PropagationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             PropagationError::TleParseError(msg) => write!(f, "TLE parse error: {}", msg),
-            PropagationError::PropagatorError(msg) => write!(f, "Propagation error: {}", msg),
-            PropagationError::InvalidTimestamp(msg) => write!(f, "Invalid timestamp: {}", msg),
+            PropagationError::OmmParseError(msg) => write!(f, "OMM parse error: {}", msg),
+            PropagationError::Decayed(decay) => write!(
+                f,
+                "Satellite decayed: last valid state at Unix time {:.0}, estimated decay at {:.0}",
+                decay.last_valid_unix, decay.decay_time_unix
+            ),
+            PropagationError::EccentricityOutOfRange {
+                eccentricity,
+                minutes_since_epoch,
+            } => write!(
+                f,
+                "Eccentricity out of range: {} at {:.1} minutes from the element epoch",
+                eccentricity, minutes_since_epoch
+            ),
+            PropagationError::TimeOutOfValidity(msg) => write!(f, "Time out of validity: {}", msg),
+            PropagationError::InvalidGroundStation(msg) => write!(f, "Invalid ground station: {}", msg),
+            PropagationError::InvalidParameter(msg) => write!(f, "Invalid parameter: {}", msg),
+            PropagationError::SatelliteNotFound(id) => {
+                write!(f, "Satellite '{}' is not in the catalog", id)
+            }
+            PropagationError::GroundStationNotFound(id) => {
+                write!(f, "Ground station '{}' is not registered", id)
+            }
+            PropagationError::SessionNotFound(id) => {
+                write!(f, "No open live stream with session ID {}", id)
+            }
+            PropagationError::Unavailable(msg) => write!(f, "Unavailable: {}", msg),
+            PropagationError::Internal(msg) => write!(f, "Internal error: {}", msg),
         }
     }
 }
 
 impl std::error::Error for PropagationError {}
 
+/// Error codes reported to API clients. Everything except `Unavailable` and
+/// `Internal` is the caller's fault: bad input, or an orbit that cannot be
+/// propagated to the requested time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// TLE or OMM could not be parsed or failed validation
+    TleParseError,
+    SatelliteDecayed,
+    EccentricityOutOfRange,
+    /// Requested time outside the range the elements are valid for
+    TimeOutOfValidity,
+    InvalidArgument,
+    /// Unknown satellite_id in the catalog
+    SatelliteNotFound,
+    /// Unknown station_id among the registered ground stations
+    GroundStationNotFound,
+    /// Unknown live stream session ID
+    SessionNotFound,
+    /// Service at capacity
+    Unavailable,
+    Internal,
+}
+
+impl ErrorCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::TleParseError => "TLE_PARSE_ERROR",
+            ErrorCode::SatelliteDecayed => "SATELLITE_DECAYED",
+            ErrorCode::EccentricityOutOfRange => "ECCENTRICITY_OUT_OF_RANGE",
+            ErrorCode::TimeOutOfValidity => "TIME_OUT_OF_VALIDITY",
+            ErrorCode::InvalidArgument => "INVALID_ARGUMENT",
+            ErrorCode::SatelliteNotFound => "SATELLITE_NOT_FOUND",
+            ErrorCode::GroundStationNotFound => "GROUND_STATION_NOT_FOUND",
+            ErrorCode::SessionNotFound => "SESSION_NOT_FOUND",
+            ErrorCode::Unavailable => "UNAVAILABLE",
+            ErrorCode::Internal => "INTERNAL",
+        }
+    }
+
+    /// Short human-readable summary of the code
+    pub fn title(self) -> &'static str {
+        match self {
+            ErrorCode::TleParseError => "Orbital elements could not be parsed",
+            ErrorCode::SatelliteDecayed => "Satellite has decayed",
+            ErrorCode::EccentricityOutOfRange => "Eccentricity out of range",
+            ErrorCode::TimeOutOfValidity => "Time outside the validity of the elements",
+            ErrorCode::InvalidArgument => "Invalid argument",
+            ErrorCode::SatelliteNotFound => "Satellite not found",
+            ErrorCode::GroundStationNotFound => "Ground station not found",
+            ErrorCode::SessionNotFound => "Live session not found",
+            ErrorCode::Unavailable => "Service unavailable",
+            ErrorCode::Internal => "Internal error",
+        }
+    }
+}
+
+/// Bisection tolerance of the estimated decay time
+const DECAY_TIME_TOLERANCE_SECONDS: f64 = 1.0;
+
+/// SGP4 state at a UTC Unix time. The orbit has decayed when the position is
+/// below the Earth's surface (SGP4 error 6 in the reference implementation),
+/// or when drag has driven the mean eccentricity negative or the semi-latus
+/// rectum below zero.
+fn propagate_sgp4(
+    constants: &Constants,
+    epoch_unix: f64,
+    timestamp_unix: f64,
+) -> Result<sgp4::Prediction, PropagationError> {
+    let minutes_since_epoch = (timestamp_unix - epoch_unix) / 60.0;
+    match constants.propagate(minutes_since_epoch) {
+        Ok(prediction) if !below_surface(&prediction) => Ok(prediction),
+        Ok(_)
+        | Err(sgp4::Error::NegativeSemiLatusRectum { .. })
+        | Err(sgp4::Error::OutOfRangeEccentricity { eccentricity: ..=0.0, .. }) => Err(
+            PropagationError::Decayed(find_decay(constants, epoch_unix, timestamp_unix)),
+        ),
+        Err(e) => Err(sgp4_error(e, minutes_since_epoch)),
+    }
+}
+
+fn below_surface(prediction: &sgp4::Prediction) -> bool {
+    let [x, y, z] = prediction.position;
+    (x * x + y * y + z * z).sqrt() < EARTH_EQUATORIAL_RADIUS_KM
+}
+
+/// Bisect between the element epoch, assumed valid, and a failing time
+fn find_decay(constants: &Constants, epoch_unix: f64, failed_unix: f64) -> Decay {
+    let valid_at = |timestamp: f64| {
+        constants
+            .propagate((timestamp - epoch_unix) / 60.0)
+            .is_ok_and(|prediction| !below_surface(&prediction))
+    };
+
+    let (mut valid, mut failed) = (epoch_unix, failed_unix);
+    while (failed - valid).abs() > DECAY_TIME_TOLERANCE_SECONDS {
+        let middle = (valid + failed) / 2.0;
+        if valid_at(middle) {
+            valid = middle;
+        } else {
+            failed = middle;
+        }
+    }
+
+    Decay {
+        last_valid_unix: valid,
+        decay_time_unix: failed,
+    }
+}
+
+/// Classify an SGP4 failure at `minutes_since_epoch`
+fn sgp4_error(error: sgp4::Error, minutes_since_epoch: f64) -> PropagationError {
+    match error {
+        sgp4::Error::OutOfRangeEccentricity { eccentricity, .. }
+        | sgp4::Error::OutOfRangePerturbedEccentricity { eccentricity, .. } => {
+            PropagationError::EccentricityOutOfRange {
+                eccentricity,
+                minutes_since_epoch,
+            }
+        }
+        sgp4::Error::NegativeBrouwerMeanMotion | sgp4::Error::NegativeKozaiMeanMotion => {
+            PropagationError::TleParseError(format!("{:?}", error))
+        }
+        other => PropagationError::Internal(format!("{:?}", other)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     // ISS TLE (example - will be outdated)
     // Valid ISS TLE from January 2024
-    const ISS_TLE_LINE1: &str = "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9006";
-    const ISS_TLE_LINE2: &str = "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423094";
+    const ISS_TLE_LINE1: &str = "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008";
+    const ISS_TLE_LINE2: &str = "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096";
+    const ISS_TLE: OrbitSource<'static> = OrbitSource::Tle {
+        line1: ISS_TLE_LINE1,
+        line2: ISS_TLE_LINE2,
+    };
 
     #[test]
     fn test_propagate_iss() {
@@ -625,4 +1627,476 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_find_root_refines_crossing() {
+        let root = find_root(|t: f64| t * t - 2.0, 0.0, 2.0, 1e-9);
+        assert!((root - 2.0_f64.sqrt()).abs() < 1e-8, "got {}", root);
+    }
+
+    #[test]
+    fn test_find_maximum_refines_peak() {
+        let peak = find_maximum(|t: f64| -(t - 12.345).powi(2), 0.0, 30.0, 1e-6);
+        assert!((peak - 12.345).abs() < 1e-5, "got {}", peak);
+    }
+
+    #[test]
+    fn test_short_pass_between_samples_is_detected() {
+        // A 20 s pass peaking at 12 deg halfway between two 30 s samples
+        let peak_time = 1_000_045.0;
+        let look_angles = |t: f64| (12.0 - 0.1 * (t - peak_time).powi(2), 180.0);
+
+        let passes = find_passes(look_angles, |_| 2.0, 1_000_000.0, 1_000_120.0);
+
+        assert_eq!(passes.len(), 1);
+        let pass = &passes[0];
+        assert!((pass.tca_time_unix - peak_time).abs() < 0.01);
+        assert!((pass.aos_time_unix - (peak_time - 10.0)).abs() < 0.01);
+        assert!((pass.los_time_unix - (peak_time + 10.0)).abs() < 0.01);
+        assert!((pass.max_elevation_deg - 12.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_visibility_window_is_checked() {
+        let ground_station = GroundStation {
+            id: "GS1".to_string(),
+            name: "Test Station".to_string(),
+            latitude_deg: 40.7128,
+            longitude_deg: -74.0060,
+            altitude_m: 10.0,
+            min_elevation_deg: 10.0,
+            horizon_mask: Vec::new(),
+        };
+        let start = 1704067200;
+
+        for window_end in [start, start - 60, start + 32 * 86400] {
+            let result = calculate_visibility_passes(
+                ISS_TLE_LINE1,
+                ISS_TLE_LINE2,
+                &ground_station,
+                start,
+                window_end,
+            );
+            assert!(
+                matches!(result, Err(PropagationError::InvalidParameter(_))),
+                "window {} to {} was accepted",
+                start,
+                window_end
+            );
+        }
+        assert!(check_window(0.0, f64::NAN, MAX_VISIBILITY_WINDOW_SECONDS).is_err());
+        assert!(check_window(f64::NEG_INFINITY, 0.0, MAX_VISIBILITY_WINDOW_SECONDS).is_err());
+    }
+
+    #[test]
+    fn test_refined_aos_los_lie_on_threshold() {
+        let start = 1704067200;
+        let ground_station = GroundStation {
+            id: "GS1".to_string(),
+            name: "Test Station".to_string(),
+            latitude_deg: 40.7128,
+            longitude_deg: -74.0060,
+            altitude_m: 10.0,
+            min_elevation_deg: 10.0,
+            horizon_mask: Vec::new(),
+        };
+
+        let passes = calculate_visibility_passes(
+            ISS_TLE_LINE1,
+            ISS_TLE_LINE2,
+            &ground_station,
+            start,
+            start + 86400,
+        )
+        .expect("Visibility calculation should succeed");
+
+        let elements = Elements::from_tle(None, ISS_TLE_LINE1.as_bytes(), ISS_TLE_LINE2.as_bytes()).unwrap();
+        let constants = Constants::from_elements(&elements).unwrap();
+        let epoch = tle_epoch_to_unix(&elements);
+        let gs_ecef = geodetic_to_ecef(40.7128, -74.0060, 0.01);
+        let elevation_at = |t: f64| {
+            let prediction = constants.propagate((t - epoch) / 60.0).unwrap();
+            calculate_look_angles(&prediction.position, &prediction.velocity, &gs_ecef, 40.7128, -74.0060, t)
+                .elevation_deg
+        };
+
+        let interior: Vec<_> = passes
+            .iter()
+            .filter(|p| p.aos_time_unix > start as f64 && p.los_time_unix < (start + 86400) as f64)
+            .collect();
+        assert!(!interior.is_empty());
+
+        for pass in interior {
+            assert!((elevation_at(pass.aos_time_unix) - 10.0).abs() < 0.01);
+            assert!((elevation_at(pass.los_time_unix) - 10.0).abs() < 0.01);
+            assert!(elevation_at(pass.tca_time_unix) >= elevation_at(pass.tca_time_unix - 1.0));
+            assert!(elevation_at(pass.tca_time_unix) >= elevation_at(pass.tca_time_unix + 1.0));
+        }
+    }
+
+    #[test]
+    fn test_horizon_mask_interpolation() {
+        let mask = [
+            HorizonMaskPoint { azimuth_deg: 0.0, min_elevation_deg: 10.0 },
+            HorizonMaskPoint { azimuth_deg: 90.0, min_elevation_deg: 20.0 },
+            HorizonMaskPoint { azimuth_deg: 270.0, min_elevation_deg: 0.0 },
+        ];
+
+        assert!((horizon_min_elevation(&mask, 0.0, 45.0) - 15.0).abs() < 1e-9);
+        assert!((horizon_min_elevation(&mask, 0.0, 180.0) - 10.0).abs() < 1e-9);
+        // Wraps through North between 270 and 360/0
+        assert!((horizon_min_elevation(&mask, 0.0, 315.0) - 5.0).abs() < 1e-9);
+        assert!((horizon_min_elevation(&mask, 0.0, -45.0) - 5.0).abs() < 1e-9);
+        // The scalar minimum still acts as a floor
+        assert!((horizon_min_elevation(&mask, 8.0, 300.0) - 8.0).abs() < 1e-9);
+        assert!((horizon_min_elevation(&[], 5.0, 123.0) - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_horizon_mask_blocks_pass() {
+        // Pass rising in the East (0-180 s) and setting in the West (180-360 s)
+        let look_angles = |t: f64| {
+            let elevation = 30.0 - (t - 180.0).abs() / 6.0;
+            let azimuth = if t < 180.0 { 90.0 } else { 270.0 };
+            (elevation, azimuth)
+        };
+        let mask = [
+            HorizonMaskPoint { azimuth_deg: 90.0, min_elevation_deg: 20.0 },
+            HorizonMaskPoint { azimuth_deg: 270.0, min_elevation_deg: 0.0 },
+        ];
+        let min_elevation = |azimuth: f64| horizon_min_elevation(&mask, 0.0, azimuth);
+
+        let passes = find_passes(look_angles, min_elevation, 0.0, 360.0);
+
+        assert_eq!(passes.len(), 1);
+        // Rises above the 20 deg eastern mask at t = 120 s, sets at the window end
+        assert!((passes[0].aos_time_unix - 120.0).abs() < 0.01);
+        assert!((passes[0].los_time_unix - 360.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_invalid_horizon_mask_rejected() {
+        let ground_station = GroundStation {
+            id: "GS1".to_string(),
+            name: "Test Station".to_string(),
+            latitude_deg: 0.0,
+            longitude_deg: 0.0,
+            altitude_m: 0.0,
+            min_elevation_deg: 0.0,
+            horizon_mask: vec![HorizonMaskPoint { azimuth_deg: 400.0, min_elevation_deg: 5.0 }],
+        };
+
+        assert!(matches!(
+            ground_station.validate(),
+            Err(PropagationError::InvalidGroundStation(_))
+        ));
+    }
+
+    #[test]
+    fn test_look_angle_azimuth_from_north() {
+        // Station on the equator at the prime meridian; targets 500 km up,
+        // 5 deg away towards each cardinal direction
+        let timestamp = 1704067200.0;
+        let gs_ecef = geodetic_to_ecef(0.0, 0.0, 0.0);
+        for (lat_deg, lon_deg, expected_deg) in [
+            (5.0, 0.0, 0.0),
+            (0.0, 5.0, 90.0),
+            (-5.0, 0.0, 180.0),
+            (0.0, -5.0, 270.0),
+        ] {
+            let target =
+                frames::itrf_to_teme(&geodetic_to_ecef(lat_deg, lon_deg, 500.0), timestamp);
+            let look = calculate_look_angles(&target, &[0.0; 3], &gs_ecef, 0.0, 0.0, timestamp);
+            let error_deg = (look.azimuth_deg - expected_deg + 180.0).rem_euclid(360.0) - 180.0;
+            assert!(
+                error_deg.abs() < 0.5,
+                "azimuth {} towards ({}, {})",
+                look.azimuth_deg,
+                lat_deg,
+                lon_deg
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_ground_station_coordinates_rejected() {
+        let station = |latitude_deg: f64, longitude_deg: f64, altitude_m: f64| GroundStation {
+            id: "GS1".to_string(),
+            name: "Test Station".to_string(),
+            latitude_deg,
+            longitude_deg,
+            altitude_m,
+            min_elevation_deg: 0.0,
+            horizon_mask: Vec::new(),
+        };
+
+        assert!(station(90.0, -180.0, 0.0).validate().is_ok());
+        for rejected in [
+            station(90.5, 0.0, 0.0),
+            station(0.0, 180.5, 0.0),
+            station(f64::NAN, 0.0, 0.0),
+            station(0.0, 0.0, f64::INFINITY),
+        ] {
+            assert!(matches!(
+                rejected.validate(),
+                Err(PropagationError::InvalidGroundStation(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_doppler_shift_sign() {
+        // Approaching satellite (negative range-rate) shifts the carrier up
+        let shift = doppler_shift_hz(437.0e6, -7.0);
+        assert!(shift > 0.0);
+        assert!((shift - 437.0e6 * 7.0 / SPEED_OF_LIGHT_KM_S).abs() < 1e-6);
+        assert!((doppler_shift_hz(437.0e6, 7.0) + shift).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pass_sampling_range_rate_and_doppler() {
+        let start = 1704067200;
+        let ground_station = GroundStation {
+            id: "GS1".to_string(),
+            name: "Test Station".to_string(),
+            latitude_deg: 40.7128,
+            longitude_deg: -74.0060,
+            altitude_m: 10.0,
+            min_elevation_deg: 10.0,
+            horizon_mask: Vec::new(),
+        };
+        let options = VisibilityOptions {
+            sampling: Some(PassSampling {
+                step_seconds: 10.0,
+                downlink_frequency_hz: Some(437.0e6),
+                uplink_frequency_hz: None,
+            }),
+            ..Default::default()
+        };
+
+        let passes = calculate_visibility_passes_with_options(
+            &ISS_TLE,
+            &ground_station,
+            start as f64,
+            (start + 86400) as f64,
+            &options,
+        )
+        .expect("Visibility calculation should succeed");
+
+        let pass = passes
+            .iter()
+            .find(|p| p.aos_time_unix > start as f64 && p.los_time_unix < (start + 86400) as f64)
+            .expect("Should have a complete pass");
+        let first = pass.samples.first().unwrap();
+        let last = pass.samples.last().unwrap();
+
+        assert!((first.timestamp_unix - pass.aos_time_unix).abs() < 1e-9);
+        assert!((last.timestamp_unix - pass.los_time_unix).abs() < 1e-9);
+
+        // Approaching at AOS, receding at LOS
+        assert!(first.range_rate_km_s < 0.0 && last.range_rate_km_s > 0.0);
+        assert!(first.downlink_doppler_hz.unwrap() > 0.0);
+        assert!(last.downlink_doppler_hz.unwrap() < 0.0);
+        assert!(first.uplink_doppler_hz.is_none());
+
+        // Range-rate should match the finite difference of slant range
+        for pair in pass.samples.windows(2) {
+            let dt = pair[1].timestamp_unix - pair[0].timestamp_unix;
+            if dt < 5.0 {
+                continue;
+            }
+            let finite_difference = (pair[1].range_km - pair[0].range_km) / dt;
+            let mean_rate = (pair[0].range_rate_km_s + pair[1].range_rate_km_s) / 2.0;
+            assert!((finite_difference - mean_rate).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_find_negative_intervals() {
+        // Negative on (10, 20) and (95, end)
+        let f = |t: f64| if t < 50.0 { (t - 10.0) * (t - 20.0) } else { 95.0 - t };
+
+        let intervals = find_negative_intervals(f, 0.0, 120.0, 7.0);
+
+        assert_eq!(intervals.len(), 2);
+        assert!((intervals[0].0 - 10.0).abs() < 0.01 && (intervals[0].1 - 20.0).abs() < 0.01);
+        assert!((intervals[1].0 - 95.0).abs() < 0.01);
+        assert_eq!(intervals[1].1, 120.0);
+    }
+
+    #[test]
+    fn test_iss_eclipses() {
+        let start = 1704067200.0;
+        let eclipses = calculate_eclipses(&ISS_TLE, start, start + 86400.0, TimeScale::Utc)
+            .expect("Eclipse calculation should succeed");
+
+        let umbra: Vec<_> = eclipses.iter().filter(|e| e.kind == ShadowKind::Umbra).collect();
+
+        // ISS completes ~15.5 orbits per day with at most one eclipse each,
+        // lasting up to ~37 minutes
+        assert!(!umbra.is_empty() && umbra.len() <= 17, "got {}", umbra.len());
+        for interval in &umbra {
+            let duration = interval.exit_time_unix - interval.entry_time_unix;
+            assert!(duration > 0.0 && duration < 2400.0, "duration {}", duration);
+        }
+
+        // Intervals are ordered and contiguous within each eclipse
+        for pair in eclipses.windows(2) {
+            assert!(pair[1].entry_time_unix >= pair[0].exit_time_unix - 1e-6);
+        }
+
+        // Penumbra phases either side of an umbra are shorter than it; late in
+        // the day the orbit drifts into grazing, penumbra-only eclipses
+        for (index, interval) in eclipses.iter().enumerate() {
+            if interval.kind != ShadowKind::Umbra {
+                continue;
+            }
+            let duration = interval.exit_time_unix - interval.entry_time_unix;
+            let before = index.checked_sub(1).and_then(|i| eclipses.get(i));
+            for neighbour in [before, eclipses.get(index + 1)].into_iter().flatten() {
+                assert_eq!(neighbour.kind, ShadowKind::Penumbra);
+                assert!(neighbour.exit_time_unix - neighbour.entry_time_unix < duration);
+            }
+        }
+
+        // Illumination is zero mid-umbra
+        let middle = (umbra[0].entry_time_unix + umbra[0].exit_time_unix) / 2.0;
+        let position = propagate(ISS_TLE_LINE1, ISS_TLE_LINE2, middle.round() as i64)
+            .unwrap()
+            .position_km;
+        assert_eq!(illumination_fraction(&position, middle.round()), 0.0);
+    }
+
+    #[test]
+    fn test_eclipses_in_time_scale() {
+        let start = 1704067200.0;
+        let end = start + 6.0 * 3600.0;
+        let utc = calculate_eclipses(&ISS_TLE, start, end, TimeScale::Utc).unwrap();
+
+        // The same instants on the TAI clock, 37 s ahead in 2024
+        let offset = 37.0;
+        let tai = calculate_eclipses(&ISS_TLE, start + offset, end + offset, TimeScale::Tai).unwrap();
+
+        assert!(!utc.is_empty());
+        assert_eq!(tai.len(), utc.len());
+        for (tai, utc) in tai.iter().zip(&utc) {
+            assert_eq!(tai.kind, utc.kind);
+            assert!((tai.entry_time_unix - utc.entry_time_unix - offset).abs() < 1e-3);
+            assert!((tai.exit_time_unix - utc.exit_time_unix - offset).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_optical_passes_are_sunlit_and_dark() {
+        let start = 1704067200;
+        let station = |latitude_deg: f64, longitude_deg: f64| GroundStation {
+            id: "GS1".to_string(),
+            name: "Test Station".to_string(),
+            latitude_deg,
+            longitude_deg,
+            altitude_m: 10.0,
+            min_elevation_deg: 10.0,
+            horizon_mask: Vec::new(),
+        };
+        let calculate = |ground_station: &GroundStation, mode| {
+            calculate_visibility_passes_with_options(
+                &ISS_TLE,
+                ground_station,
+                start as f64,
+                (start + 3 * 86400) as f64,
+                &VisibilityOptions {
+                    mode,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+        let sun_elevation = |ground_station: &GroundStation, t: f64| {
+            let (latitude, longitude) = (ground_station.latitude_deg, ground_station.longitude_deg);
+            let gs_ecef = geodetic_to_ecef(latitude, longitude, 0.01);
+            calculate_look_angles(
+                &solar::sun_position_eci(t),
+                &[0.0; 3],
+                &gs_ecef,
+                latitude,
+                longitude,
+                t,
+            )
+            .elevation_deg
+        };
+
+        // In early January the ISS crosses 50 deg South around local
+        // midnight in summer sunlight, so passes there are visible
+        let south = station(-50.0, -70.0);
+        let optical = calculate(&south, VisibilityMode::Optical(Twilight::Civil));
+        assert!(!optical.is_empty());
+        for pass in &optical {
+            let lit_start = pass.sunlit_start_time_unix.unwrap();
+            let lit_end = pass.sunlit_end_time_unix.unwrap();
+            assert!(lit_start >= pass.aos_time_unix && lit_end <= pass.los_time_unix);
+            assert!(pass.visual_magnitude.is_some());
+            assert!(sun_elevation(&south, (lit_start + lit_end) / 2.0) < -6.0);
+        }
+
+        // Over New York the morning passes come in daylight and are dropped
+        let north = station(40.7128, -74.0060);
+        let radio = calculate(&north, VisibilityMode::Radio);
+        let optical = calculate(&north, VisibilityMode::Optical(Twilight::Civil));
+        let dropped_in_daylight = radio.iter().filter(|pass| {
+            sun_elevation(&north, pass.tca_time_unix) > 0.0
+                && optical
+                    .iter()
+                    .all(|kept| kept.aos_time_unix != pass.aos_time_unix)
+        });
+        assert!(dropped_in_daylight.count() > 0);
+
+        // Radio passes report no brightness
+        assert!(radio.iter().all(|pass| pass.visual_magnitude.is_none()));
+    }
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(
+            PropagationError::OmmParseError("empty".to_string()).code(),
+            ErrorCode::TleParseError
+        );
+        assert_eq!(
+            PropagationError::InvalidGroundStation("latitude".to_string()).code(),
+            ErrorCode::InvalidArgument
+        );
+        assert_eq!(
+            sgp4_error(sgp4::Error::NegativeKozaiMeanMotion, 0.0).code(),
+            ErrorCode::TleParseError
+        );
+        assert_eq!(ErrorCode::EccentricityOutOfRange.as_str(), "ECCENTRICITY_OUT_OF_RANGE");
+    }
+
+    #[test]
+    fn test_decay_detection() {
+        // ISS elements with an extreme drag term (B* = 0.5) decay within days
+        let line1 = "1 25544U 98067A   24001.50000000  .00016717  00000+0  50000-0 0  9000";
+        let source = OrbitSource::Tle { line1, line2: ISS_TLE_LINE2 };
+        let epoch = 1704110400.0; // 2024-01-01 12:00:00 UTC
+        let year_later = epoch + 365.0 * 86400.0;
+
+        let trajectory =
+            propagate_trajectory_in(&source, epoch, year_later, 86400.0, TimeScale::Utc).unwrap();
+        let decay = trajectory.decay.expect("orbit should decay");
+        assert!(decay.last_valid_unix < decay.decay_time_unix);
+        assert!(decay.decay_time_unix - decay.last_valid_unix <= DECAY_TIME_TOLERANCE_SECONDS);
+        assert!(decay.decay_time_unix > epoch && decay.decay_time_unix < year_later);
+        let (last_time, _) = trajectory.points.last().unwrap();
+        assert!(*last_time <= decay.last_valid_unix);
+
+        // Single propagations past the decay report the same estimate
+        match propagate_at(&source, year_later) {
+            Err(PropagationError::Decayed(found)) => {
+                let difference = (found.decay_time_unix - decay.decay_time_unix).abs();
+                assert!(difference <= 2.0 * DECAY_TIME_TOLERANCE_SECONDS);
+            }
+            other => panic!("expected a decay, got {:?}", other.map(|_| ())),
+        }
+        assert!(propagate_at(&source, decay.last_valid_unix).is_ok());
+    }
+
 }
@@ -1,21 +1,35 @@
 //! gRPC service implementation
 
+// Helpers here share the handlers' tonic::Status error type
+#![allow(clippy::result_large_err)]
+
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use tokio::sync::RwLock;
-use tonic::{Request, Response, Status};
+use tokio::sync::{mpsc, RwLock};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{metadata::MetadataValue, Code, Request, Response, Status, Streaming};
 use tracing::{debug, info, instrument, warn};
 
 use crate::generated::orbital::{
-    orbital_service_server::OrbitalService,
-    EciPosition, EciVelocity, GeodeticPosition,
-    HealthCheckRequest, HealthCheckResponse,
-    Pass, PropagateRequest, PropagateResponse,
-    TrajectoryPoint, TrajectoryRequest, TrajectoryResponse,
-    VisibilityRequest, VisibilityResponse,
+    eclipse_request, live_feed_request, live_feed_satellite, orbital_service_server::OrbitalService,
+    propagate_request, trajectory_request, visibility_request, BatchPropagateRequest, BatchPropagateResponse,
+    CatalogSatellite, Decay, DeleteSatelliteRequest, EciPosition, EciVelocity, EclipseInterval,
+    EclipseRequest, EclipseResponse, ElementSelection, GeodeticPosition, GetSatelliteRequest,
+    HealthCheckRequest, HealthCheckResponse, ListSatellitesRequest, ListSatellitesResponse, LiveFeedRequest,
+    LiveFeedSatellite, LiveFeedUpdate, Omm, ShadowKind, UpsertSatelliteRequest,
+    Pass, PassSample, PropagateRequest, PropagateResponse, ReferenceFrame, SatelliteHistoryResponse,
+    TimeScale, Tle, TrajectoryChunk, TrajectoryPoint, TrajectoryRequest, TrajectoryResponse, Twilight,
+    VisibilityMode, VisibilityRequest, VisibilityResponse,
 };
-use crate::propagator;
+use crate::catalog::{self, Catalog, CatalogEntry};
+use crate::compute;
+use crate::eop;
+use crate::frames::Frame;
+use crate::live::{self, LiveOrbit, LiveSession};
+use crate::propagator::{self, ErrorCode, OrbitSource, OwnedOrbitSource, PropagationError};
+use crate::timescale::{self, TimeScale as Scale};
 use crate::AppState;
 
 /// Implementation of the OrbitalService gRPC service
@@ -27,6 +41,10 @@ impl OrbitalServiceImpl {
     pub fn new(state: Arc<RwLock<AppState>>) -> Self {
         Self { state }
     }
+
+    async fn catalog(&self) -> Arc<Catalog> {
+        Arc::clone(&self.state.read().await.catalog)
+    }
 }
 
 #[tonic::async_trait]
@@ -37,22 +55,36 @@ impl OrbitalService for OrbitalServiceImpl {
         request: Request<PropagateRequest>,
     ) -> Result<Response<PropagateResponse>, Status> {
         let start = Instant::now();
-        let req = request.into_inner();
+        let mut req = request.into_inner();
 
         let satellite_id = req.satellite_id.clone();
         tracing::Span::current().record("satellite_id", &satellite_id);
 
         debug!("PropagatePosition request for satellite {}", satellite_id);
 
-        // Validate request
-        let tle = req.tle.ok_or_else(|| Status::invalid_argument("TLE is required"))?;
+        let frame = frame_from_proto(req.output_frame());
+        let time_scale = time_scale_from_proto(req.time_scale());
+        let time = seconds_from_proto(req.timestamp.as_ref(), req.timestamp_unix);
+        let utc = timescale::to_utc(time, time_scale);
 
-        if tle.line1.is_empty() || tle.line2.is_empty() {
-            return Err(Status::invalid_argument("TLE lines cannot be empty"));
-        }
+        // Validate request
+        let orbit = match req.orbit.take() {
+            Some(propagate_request::Orbit::Tle(tle)) => Orbit::Tle(tle),
+            Some(propagate_request::Orbit::Omm(omm)) => Orbit::Omm(omm),
+            None => Orbit::registered(
+                &*self.catalog().await,
+                &satellite_id,
+                utc,
+                req.element_selection(),
+            )?,
+        };
+        let source = orbit.source()?;
+        let norad_id = source.norad_id().unwrap_or_default();
+        let element_epoch = orbit.element_epoch();
 
         // Propagate
-        match propagator::propagate(&tle.line1, &tle.line2, req.timestamp_unix) {
+        let orbit = OwnedOrbitSource::from(source);
+        match compute::run(move || propagator::propagate_at(&orbit.as_source(), utc)).await {
             Ok(result) => {
                 let elapsed = start.elapsed();
                 
@@ -69,25 +101,8 @@ impl OrbitalService for OrbitalServiceImpl {
                 );
 
                 Ok(Response::new(PropagateResponse {
-                    satellite_id,
-                    timestamp_unix: req.timestamp_unix,
-                    position: Some(EciPosition {
-                        x_km: result.position_km[0],
-                        y_km: result.position_km[1],
-                        z_km: result.position_km[2],
-                    }),
-                    velocity: Some(EciVelocity {
-                        vx_km_s: result.velocity_km_s[0],
-                        vy_km_s: result.velocity_km_s[1],
-                        vz_km_s: result.velocity_km_s[2],
-                    }),
-                    geodetic: Some(GeodeticPosition {
-                        latitude_deg: result.geodetic.latitude_deg,
-                        longitude_deg: result.geodetic.longitude_deg,
-                        altitude_km: result.geodetic.altitude_km,
-                    }),
-                    success: true,
-                    error_message: String::new(),
+                    element_epoch,
+                    ..propagate_response(satellite_id, norad_id, time, time_scale, frame, &result)
                 }))
             }
             Err(e) => {
@@ -105,32 +120,96 @@ impl OrbitalService for OrbitalServiceImpl {
                     "Propagation failed"
                 );
 
-                Ok(Response::new(PropagateResponse {
-                    satellite_id,
-                    timestamp_unix: req.timestamp_unix,
-                    position: None,
-                    velocity: None,
-                    geodetic: None,
-                    success: false,
-                    error_message: e.to_string(),
-                }))
+                Err(e.into())
             }
         }
     }
 
+    #[instrument(skip(self, request), fields(items))]
+    async fn propagate_batch(
+        &self,
+        request: Request<BatchPropagateRequest>,
+    ) -> Result<Response<BatchPropagateResponse>, Status> {
+        let start = Instant::now();
+        let req = request.into_inner();
+
+        tracing::Span::current().record("items", req.requests.len());
+
+        // Limit batch size to prevent DoS
+        let max_items = 10000;
+        if req.requests.len() > max_items {
+            return Err(Status::invalid_argument(format!(
+                "Batch has {} items, max is {}",
+                req.requests.len(),
+                max_items
+            )));
+        }
+
+        // Items fan out across the compute pool; results keep request order
+        let epoch = req.epoch;
+        let catalog = self.catalog().await;
+        let results = compute::map(req.requests, move |item| {
+            propagate_batch_item(item, epoch.as_ref(), &catalog)
+        })
+        .await;
+
+        let elapsed = start.elapsed();
+        let success_count = results.iter().filter(|result| result.success).count();
+        let error_count = results.len() - success_count;
+
+        {
+            let state = self.state.read().await;
+            state.metrics.record_batch(elapsed, success_count, error_count);
+        }
+
+        info!(
+            items = %results.len(),
+            errors = %error_count,
+            elapsed_ms = %elapsed.as_millis(),
+            "Batch propagation finished"
+        );
+
+        Ok(Response::new(BatchPropagateResponse {
+            total_count: results.len() as u32,
+            success_count: success_count as u32,
+            error_count: error_count as u32,
+            results,
+        }))
+    }
+
     #[instrument(skip(self, request), fields(satellite_id, ground_station_id))]
     async fn calculate_visibility(
         &self,
         request: Request<VisibilityRequest>,
     ) -> Result<Response<VisibilityResponse>, Status> {
         let start = Instant::now();
-        let req = request.into_inner();
+        let mut req = request.into_inner();
 
         let satellite_id = req.satellite_id.clone();
-        let ground_station = req.ground_station.ok_or_else(|| {
-            Status::invalid_argument("Ground station is required")
-        })?;
-        let ground_station_id = ground_station.id.clone();
+        let station = match req.ground_station.take() {
+            Some(ground_station) => propagator::GroundStation {
+                id: ground_station.id,
+                name: ground_station.name,
+                latitude_deg: ground_station.latitude_deg,
+                longitude_deg: ground_station.longitude_deg,
+                altitude_m: ground_station.altitude_m,
+                min_elevation_deg: ground_station.min_elevation_deg,
+                horizon_mask: ground_station
+                    .horizon_mask
+                    .iter()
+                    .map(|point| propagator::HorizonMaskPoint {
+                        azimuth_deg: point.azimuth_deg,
+                        min_elevation_deg: point.min_elevation_deg,
+                    })
+                    .collect(),
+            },
+            None if !req.ground_station_id.is_empty() => {
+                let registered = self.catalog().await.ground_station(&req.ground_station_id)?;
+                (*registered).clone()
+            }
+            None => return Err(Status::invalid_argument("Ground station is required")),
+        };
+        let ground_station_id = station.id.clone();
 
         tracing::Span::current().record("satellite_id", &satellite_id);
         tracing::Span::current().record("ground_station_id", &ground_station_id);
@@ -140,49 +219,158 @@ impl OrbitalService for OrbitalServiceImpl {
             satellite_id, ground_station_id
         );
 
+        let time_scale = time_scale_from_proto(req.time_scale());
+        let window_start = seconds_from_proto(req.start_time.as_ref(), req.start_timestamp_unix);
+        let window_end = seconds_from_proto(req.end_time.as_ref(), req.end_timestamp_unix);
+
         // Validate request
-        let _tle = req.tle.ok_or_else(|| Status::invalid_argument("TLE is required"))?;
+        let orbit = match req.orbit.take() {
+            Some(visibility_request::Orbit::Tle(tle)) => Orbit::Tle(tle),
+            Some(visibility_request::Orbit::Omm(omm)) => Orbit::Omm(omm),
+            None => Orbit::registered(
+                &*self.catalog().await,
+                &satellite_id,
+                timescale::to_utc(window_start, time_scale),
+                req.element_selection(),
+            )?,
+        };
+        let source = orbit.source()?;
+        let norad_id = source.norad_id().unwrap_or_default();
+        let element_epoch = orbit.element_epoch();
 
-        // For now, return a stub response
-        // Full visibility calculation would require:
-        // 1. Propagate satellite at intervals
-        // 2. Calculate elevation from ground station
-        // 3. Find AOS/LOS crossings of min elevation
-        // 4. Calculate max elevation and azimuths
+        if window_end <= window_start {
+            return Err(Status::invalid_argument(
+                "end_timestamp must be after start_timestamp",
+            ));
+        }
 
-        let elapsed = start.elapsed();
-        
-        {
-            let state = self.state.read().await;
-            state.metrics.record_visibility(elapsed, true);
+        station.validate()?;
+
+        let twilight = match req.twilight() {
+            Twilight::Civil => propagator::Twilight::Civil,
+            Twilight::Unspecified | Twilight::Nautical => propagator::Twilight::Nautical,
+            Twilight::Astronomical => propagator::Twilight::Astronomical,
+        };
+        let mode = match req.visibility_mode() {
+            VisibilityMode::Unspecified | VisibilityMode::Radio => propagator::VisibilityMode::Radio,
+            VisibilityMode::Optical => propagator::VisibilityMode::Optical(twilight),
+        };
+
+        let options = propagator::VisibilityOptions {
+            sampling: req.pass_sampling.map(|sampling| propagator::PassSampling {
+                step_seconds: Some(sampling.step_seconds)
+                    .filter(|step| *step != 0.0)
+                    .unwrap_or(propagator::DEFAULT_PASS_SAMPLE_STEP_SECONDS),
+                downlink_frequency_hz: Some(sampling.downlink_frequency_hz).filter(|f| *f != 0.0),
+                uplink_frequency_hz: Some(sampling.uplink_frequency_hz).filter(|f| *f != 0.0),
+            }),
+            mode,
+            standard_magnitude: req.standard_magnitude,
+            time_scale,
+        };
+
+        if let Some(sampling) = &options.sampling {
+            sampling.validate()?;
         }
 
-        info!(
-            satellite_id = %satellite_id,
-            ground_station_id = %ground_station_id,
-            elapsed_ms = %elapsed.as_millis(),
-            "Visibility calculation complete (stub)"
-        );
+        let orbit = OwnedOrbitSource::from(source);
+        let outcome = compute::run(move || {
+            propagator::calculate_visibility_passes_with_options(
+                &orbit.as_source(),
+                &station,
+                window_start,
+                window_end,
+                &options,
+            )
+        })
+        .await;
 
-        // Return empty passes for now - full implementation would compute actual passes
-        Ok(Response::new(VisibilityResponse {
-            satellite_id,
-            ground_station_id,
-            passes: vec![
-                // Example stub pass
-                Pass {
-                    aos_timestamp: req.start_timestamp_unix + 3600,
-                    los_timestamp: req.start_timestamp_unix + 4200,
-                    max_elevation_timestamp: req.start_timestamp_unix + 3900,
-                    max_elevation_deg: 45.0,
-                    aos_azimuth_deg: 270.0,
-                    los_azimuth_deg: 90.0,
-                    duration_seconds: 600,
-                },
-            ],
-            success: true,
-            error_message: String::new(),
-        }))
+        match outcome {
+            Ok(results) => {
+                let elapsed = start.elapsed();
+
+                {
+                    let state = self.state.read().await;
+                    state.metrics.record_visibility(elapsed, true);
+                }
+
+                info!(
+                    satellite_id = %satellite_id,
+                    ground_station_id = %ground_station_id,
+                    passes = %results.len(),
+                    elapsed_ms = %elapsed.as_millis(),
+                    "Visibility calculation successful"
+                );
+
+                let passes: Vec<Pass> = results
+                    .into_iter()
+                    .map(|pass| Pass {
+                        aos_timestamp: pass.aos_timestamp,
+                        los_timestamp: pass.los_timestamp,
+                        max_elevation_timestamp: pass.tca_timestamp.unwrap_or(pass.aos_timestamp),
+                        max_elevation_deg: pass.max_elevation_deg,
+                        aos_azimuth_deg: pass.aos_azimuth_deg.unwrap_or_default(),
+                        los_azimuth_deg: pass.los_azimuth_deg.unwrap_or_default(),
+                        duration_seconds: pass
+                            .duration_seconds
+                            .unwrap_or(pass.los_timestamp - pass.aos_timestamp),
+                        aos_time_unix: pass.aos_time_unix,
+                        los_time_unix: pass.los_time_unix,
+                        max_elevation_time_unix: pass.tca_time_unix,
+                        samples: pass
+                            .samples
+                            .into_iter()
+                            .map(|sample| PassSample {
+                                timestamp_unix: sample.timestamp_unix,
+                                elevation_deg: sample.elevation_deg,
+                                azimuth_deg: sample.azimuth_deg,
+                                range_km: sample.range_km,
+                                range_rate_km_s: sample.range_rate_km_s,
+                                downlink_doppler_hz: sample.downlink_doppler_hz.unwrap_or_default(),
+                                uplink_doppler_hz: sample.uplink_doppler_hz.unwrap_or_default(),
+                            })
+                            .collect(),
+                        sunlit_start_time_unix: pass.sunlit_start_time_unix,
+                        sunlit_end_time_unix: pass.sunlit_end_time_unix,
+                        visual_magnitude: pass.visual_magnitude,
+                    })
+                    .collect();
+
+                let eop_fallback = !eop::covers(
+                    timescale::to_utc(window_start, time_scale),
+                    timescale::to_utc(window_end, time_scale),
+                );
+
+                Ok(Response::new(VisibilityResponse {
+                    satellite_id,
+                    norad_id,
+                    ground_station_id,
+                    passes,
+                    time_scale: time_scale_to_proto(time_scale).into(),
+                    eop_fallback,
+                    success: true,
+                    error_message: String::new(),
+                    element_epoch,
+                }))
+            }
+            Err(e) => {
+                let elapsed = start.elapsed();
+
+                {
+                    let state = self.state.read().await;
+                    state.metrics.record_visibility(elapsed, false);
+                }
+
+                warn!(
+                    satellite_id = %satellite_id,
+                    ground_station_id = %ground_station_id,
+                    error = %e,
+                    "Visibility calculation failed"
+                );
+
+                Err(e.into())
+            }
+        }
     }
 
     #[instrument(skip(self, request), fields(satellite_id))]
@@ -191,7 +379,7 @@ impl OrbitalService for OrbitalServiceImpl {
         request: Request<TrajectoryRequest>,
     ) -> Result<Response<TrajectoryResponse>, Status> {
         let start = Instant::now();
-        let req = request.into_inner();
+        let mut req = request.into_inner();
 
         let satellite_id = req.satellite_id.clone();
         tracing::Span::current().record("satellite_id", &satellite_id);
@@ -201,39 +389,37 @@ impl OrbitalService for OrbitalServiceImpl {
             satellite_id, req.start_timestamp_unix, req.end_timestamp_unix
         );
 
-        // Validate request
-        let tle = req.tle.ok_or_else(|| Status::invalid_argument("TLE is required"))?;
-
-        if req.step_seconds <= 0 {
-            return Err(Status::invalid_argument("step_seconds must be positive"));
-        }
-
-        if req.end_timestamp_unix <= req.start_timestamp_unix {
-            return Err(Status::invalid_argument(
-                "end_timestamp must be after start_timestamp",
-            ));
-        }
+        let window = TrajectoryWindow::from_request(&mut req, &*self.catalog().await)?;
 
         // Limit trajectory length to prevent DoS
         let max_points = 10000;
-        let num_points = (req.end_timestamp_unix - req.start_timestamp_unix) / req.step_seconds + 1;
-        if num_points > max_points {
+        if window.intervals() >= max_points as f64 {
             return Err(Status::invalid_argument(format!(
-                "Trajectory would have {} points, max is {}",
-                num_points, max_points
+                "Trajectory would have {} points, max is {}; use StreamTrajectory for longer ranges",
+                window.point_count(),
+                max_points
             )));
         }
 
-        match propagator::propagate_trajectory(
-            &tle.line1,
-            &tle.line2,
-            req.start_timestamp_unix,
-            req.end_timestamp_unix,
-            req.step_seconds,
-        ) {
-            Ok(results) => {
+        let orbit = window.orbit.clone();
+        let (window_start, window_end) = (window.start, window.end);
+        let (step_seconds, time_scale) = (window.step_seconds, window.format.time_scale);
+        let outcome = compute::run(move || {
+            propagator::propagate_trajectory_in(
+                &orbit.as_source(),
+                window_start,
+                window_end,
+                step_seconds,
+                time_scale,
+            )
+        })
+        .await;
+
+        match outcome {
+            Ok(trajectory) => {
                 let elapsed = start.elapsed();
-                
+                let (results, decay) = (trajectory.points, trajectory.decay);
+
                 {
                     let state = self.state.read().await;
                     state.metrics.record_trajectory(elapsed, results.len(), true);
@@ -248,26 +434,21 @@ impl OrbitalService for OrbitalServiceImpl {
 
                 let points: Vec<TrajectoryPoint> = results
                     .into_iter()
-                    .map(|(ts, result)| TrajectoryPoint {
-                        timestamp_unix: ts,
-                        position: Some(EciPosition {
-                            x_km: result.position_km[0],
-                            y_km: result.position_km[1],
-                            z_km: result.position_km[2],
-                        }),
-                        geodetic: Some(GeodeticPosition {
-                            latitude_deg: result.geodetic.latitude_deg,
-                            longitude_deg: result.geodetic.longitude_deg,
-                            altitude_km: result.geodetic.altitude_km,
-                        }),
-                    })
+                    .map(|(ts, result)| window.format.point(ts, &result))
                     .collect();
 
                 Ok(Response::new(TrajectoryResponse {
                     satellite_id,
+                    norad_id: window.norad_id,
                     points,
+                    time_scale: time_scale_to_proto(time_scale).into(),
+                    eop_fallback: window.eop_fallback(),
+                    decayed: decay.is_some(),
+                    decay: decay.map(decay_to_proto),
                     success: true,
                     error_message: String::new(),
+                    frame: frame_to_proto(window.format.frame).into(),
+                    element_epoch: window.element_epoch,
                 }))
             }
             Err(e) => {
@@ -284,16 +465,324 @@ impl OrbitalService for OrbitalServiceImpl {
                     "Trajectory propagation failed"
                 );
 
-                Ok(Response::new(TrajectoryResponse {
+                Err(e.into())
+            }
+        }
+    }
+
+    type StreamTrajectoryStream = ReceiverStream<Result<TrajectoryChunk, Status>>;
+
+    #[instrument(skip(self, request), fields(satellite_id))]
+    async fn stream_trajectory(
+        &self,
+        request: Request<TrajectoryRequest>,
+    ) -> Result<Response<Self::StreamTrajectoryStream>, Status> {
+        let start = Instant::now();
+        let mut req = request.into_inner();
+
+        let satellite_id = req.satellite_id.clone();
+        tracing::Span::current().record("satellite_id", &satellite_id);
+
+        debug!(
+            "StreamTrajectory request for {} from {} to {}",
+            satellite_id, req.start_timestamp_unix, req.end_timestamp_unix
+        );
+
+        let window = TrajectoryWindow::from_request(&mut req, &*self.catalog().await)?;
+
+        // Orbit errors fail the call before any point is sent
+        let orbit = window.orbit.clone();
+        let (window_start, window_end) = (window.start, window.end);
+        let (step_seconds, time_scale) = (window.step_seconds, window.format.time_scale);
+        let samples = match compute::run(move || {
+            propagator::trajectory_samples(
+                &orbit.as_source(),
+                window_start,
+                window_end,
+                step_seconds,
+                time_scale,
+            )
+        })
+        .await
+        {
+            Ok(samples) => samples,
+            Err(e) => {
+                let state = self.state.read().await;
+                state.metrics.record_trajectory(start.elapsed(), 0, false);
+                return Err(e.into());
+            }
+        };
+
+        let header = TrajectoryChunk {
+            satellite_id: satellite_id.clone(),
+            norad_id: window.norad_id,
+            frame: frame_to_proto(window.format.frame).into(),
+            time_scale: time_scale_to_proto(time_scale).into(),
+            eop_fallback: window.eop_fallback(),
+            element_epoch: window.element_epoch,
+            ..Default::default()
+        };
+        let format = window.format;
+        let state = Arc::clone(&self.state);
+        let (sender, receiver) = mpsc::channel(compute::STREAM_BUFFER_CHUNKS);
+
+        // Points are produced on the compute pool one chunk at a time; a slow
+        // client fills the channel and pauses production
+        tokio::spawn(async move {
+            let mut sent = 0;
+            let samples = compute::drain_chunks(
+                samples,
+                compute::STREAM_CHUNK_ITEMS,
+                move |(ts, result)| format.point(ts, &result),
+                |points| {
+                    sent += points.len();
+                    let sender = sender.clone();
+                    let chunk = TrajectoryChunk {
+                        points,
+                        ..header.clone()
+                    };
+                    async move { sender.send(Ok(chunk)).await.is_ok() }
+                },
+            )
+            .await;
+
+            let decay = samples.decay();
+            let _ = sender
+                .send(Ok(TrajectoryChunk {
+                    complete: true,
+                    decayed: decay.is_some(),
+                    decay: decay.map(decay_to_proto),
+                    ..header
+                }))
+                .await;
+
+            let elapsed = start.elapsed();
+            state.read().await.metrics.record_trajectory(elapsed, sent, true);
+
+            info!(
+                satellite_id = %satellite_id,
+                points = %sent,
+                elapsed_ms = %elapsed.as_millis(),
+                "Trajectory stream finished"
+            );
+        });
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    #[instrument(skip(self, request), fields(satellite_id))]
+    async fn calculate_eclipses(
+        &self,
+        request: Request<EclipseRequest>,
+    ) -> Result<Response<EclipseResponse>, Status> {
+        let start = Instant::now();
+        let mut req = request.into_inner();
+
+        let satellite_id = req.satellite_id.clone();
+        tracing::Span::current().record("satellite_id", &satellite_id);
+
+        debug!(
+            "CalculateEclipses request for {} from {} to {}",
+            satellite_id, req.start_timestamp_unix, req.end_timestamp_unix
+        );
+
+        let time_scale = time_scale_from_proto(req.time_scale());
+        let window_start = seconds_from_proto(req.start_time.as_ref(), req.start_timestamp_unix);
+        let window_end = seconds_from_proto(req.end_time.as_ref(), req.end_timestamp_unix);
+
+        // Validate request
+        let orbit = match req.orbit.take() {
+            Some(eclipse_request::Orbit::Tle(tle)) => Orbit::Tle(tle),
+            Some(eclipse_request::Orbit::Omm(omm)) => Orbit::Omm(omm),
+            None => Orbit::registered(
+                &*self.catalog().await,
+                &satellite_id,
+                timescale::to_utc(window_start, time_scale),
+                req.element_selection(),
+            )?,
+        };
+        let source = orbit.source()?;
+        let norad_id = source.norad_id().unwrap_or_default();
+        let element_epoch = orbit.element_epoch();
+
+        if window_end <= window_start {
+            return Err(Status::invalid_argument(
+                "end_timestamp must be after start_timestamp",
+            ));
+        }
+
+        let orbit = OwnedOrbitSource::from(source);
+        let outcome = compute::run(move || {
+            propagator::calculate_eclipses(&orbit.as_source(), window_start, window_end, time_scale)
+        })
+        .await;
+
+        match outcome {
+            Ok(intervals) => {
+                let elapsed = start.elapsed();
+
+                {
+                    let state = self.state.read().await;
+                    state.metrics.record_eclipses(elapsed, true);
+                }
+
+                info!(
+                    satellite_id = %satellite_id,
+                    intervals = %intervals.len(),
+                    elapsed_ms = %elapsed.as_millis(),
+                    "Eclipse calculation successful"
+                );
+
+                let eclipses = intervals
+                    .into_iter()
+                    .map(|interval| EclipseInterval {
+                        kind: match interval.kind {
+                            propagator::ShadowKind::Penumbra => ShadowKind::Penumbra,
+                            propagator::ShadowKind::Umbra => ShadowKind::Umbra,
+                        } as i32,
+                        entry_time_unix: interval.entry_time_unix,
+                        exit_time_unix: interval.exit_time_unix,
+                        duration_seconds: interval.exit_time_unix - interval.entry_time_unix,
+                    })
+                    .collect();
+
+                Ok(Response::new(EclipseResponse {
                     satellite_id,
-                    points: vec![],
-                    success: false,
-                    error_message: e.to_string(),
+                    norad_id,
+                    eclipses,
+                    success: true,
+                    error_message: String::new(),
+                    time_scale: time_scale_to_proto(time_scale).into(),
+                    element_epoch,
                 }))
             }
+            Err(e) => {
+                let elapsed = start.elapsed();
+
+                {
+                    let state = self.state.read().await;
+                    state.metrics.record_eclipses(elapsed, false);
+                }
+
+                warn!(
+                    satellite_id = %satellite_id,
+                    error = %e,
+                    "Eclipse calculation failed"
+                );
+
+                Err(e.into())
+            }
         }
     }
 
+    type LiveFeedStream = ReceiverStream<Result<LiveFeedUpdate, Status>>;
+
+    #[instrument(skip(self, request))]
+    async fn live_feed(
+        &self,
+        request: Request<Streaming<LiveFeedRequest>>,
+    ) -> Result<Response<Self::LiveFeedStream>, Status> {
+        let (sender, receiver) = mpsc::channel(compute::STREAM_BUFFER_CHUNKS);
+
+        info!("Live feed opened");
+        tokio::spawn(run_live_feed(
+            request.into_inner(),
+            sender,
+            Arc::clone(&self.state),
+        ));
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    #[instrument(skip(self, request), fields(satellite_id))]
+    async fn upsert_satellite(
+        &self,
+        request: Request<UpsertSatelliteRequest>,
+    ) -> Result<Response<CatalogSatellite>, Status> {
+        let req = request.into_inner();
+        tracing::Span::current().record("satellite_id", &req.satellite_id);
+
+        let tle = req
+            .tle
+            .ok_or_else(|| Status::invalid_argument("TLE is required"))?;
+        let (satellite_id, name) = (req.satellite_id, req.name);
+        let entry = compute::run(move || {
+            let norad_id = (!tle.norad_id.is_empty()).then_some(tle.norad_id.as_str());
+            CatalogEntry::new(&satellite_id, &name, &tle.line1, &tle.line2, norad_id)
+        })
+        .await?;
+
+        let catalog = self.catalog().await;
+        let replaced = {
+            let entry = entry.clone();
+            catalog::blocking_write(move || catalog.upsert(entry)).await?
+        };
+        info!(
+            satellite_id = %entry.satellite_id,
+            norad_id = entry.norad_id,
+            replaced = replaced.is_some(),
+            "Satellite registered"
+        );
+
+        Ok(Response::new(catalog_satellite_to_proto(&entry)))
+    }
+
+    async fn get_satellite(
+        &self,
+        request: Request<GetSatelliteRequest>,
+    ) -> Result<Response<CatalogSatellite>, Status> {
+        let req = request.into_inner();
+        let entry = self.catalog().await.lookup(&req.satellite_id)?;
+        Ok(Response::new(catalog_satellite_to_proto(&entry)))
+    }
+
+    async fn get_satellite_history(
+        &self,
+        request: Request<GetSatelliteRequest>,
+    ) -> Result<Response<SatelliteHistoryResponse>, Status> {
+        let req = request.into_inner();
+        let element_sets = self
+            .catalog()
+            .await
+            .history(&req.satellite_id)?
+            .iter()
+            .map(|entry| catalog_satellite_to_proto(entry))
+            .collect();
+        Ok(Response::new(SatelliteHistoryResponse { element_sets }))
+    }
+
+    async fn list_satellites(
+        &self,
+        _request: Request<ListSatellitesRequest>,
+    ) -> Result<Response<ListSatellitesResponse>, Status> {
+        let satellites = self
+            .catalog()
+            .await
+            .list()
+            .iter()
+            .map(|entry| catalog_satellite_to_proto(entry))
+            .collect();
+        Ok(Response::new(ListSatellitesResponse { satellites }))
+    }
+
+    #[instrument(skip(self, request), fields(satellite_id))]
+    async fn delete_satellite(
+        &self,
+        request: Request<DeleteSatelliteRequest>,
+    ) -> Result<Response<CatalogSatellite>, Status> {
+        let req = request.into_inner();
+        tracing::Span::current().record("satellite_id", &req.satellite_id);
+
+        let catalog = self.catalog().await;
+        let satellite_id = req.satellite_id.clone();
+        let entry = catalog::blocking_write(move || catalog.remove(&satellite_id))
+            .await?
+            .ok_or_else(|| PropagationError::SatelliteNotFound(req.satellite_id.clone()))?;
+        info!(satellite_id = %entry.satellite_id, "Satellite removed from the catalog");
+
+        Ok(Response::new(catalog_satellite_to_proto(&entry)))
+    }
+
     async fn health_check(
         &self,
         _request: Request<HealthCheckRequest>,
@@ -307,3 +796,652 @@ impl OrbitalService for OrbitalServiceImpl {
         }))
     }
 }
+
+/// Successful propagation of a single orbit
+fn propagate_response(
+    satellite_id: String,
+    norad_id: u64,
+    time: f64,
+    time_scale: Scale,
+    frame: Frame,
+    result: &propagator::PropagationResult,
+) -> PropagateResponse {
+    let utc = timescale::to_utc(time, time_scale);
+    let (position, velocity, geodetic) = state_to_proto(result, frame, utc);
+    let eop_fallback = frame.uses_eop() && !eop::covers(utc, utc);
+
+    PropagateResponse {
+        satellite_id,
+        norad_id,
+        timestamp_unix: time.floor() as i64,
+        timestamp: Some(timestamp_to_proto(time)),
+        position: Some(position),
+        velocity: Some(velocity),
+        geodetic: Some(geodetic),
+        time_scale: time_scale_to_proto(time_scale).into(),
+        eop_fallback,
+        success: true,
+        error_message: String::new(),
+        error_code: String::new(),
+        frame: frame_to_proto(frame).into(),
+        element_epoch: None,
+    }
+}
+
+/// One batch item; failures are reported in the item rather than failing the
+/// batch. `epoch` applies when the item has no time of its own.
+fn propagate_batch_item(
+    mut req: PropagateRequest,
+    epoch: Option<&prost_types::Timestamp>,
+    catalog: &Catalog,
+) -> PropagateResponse {
+    let frame = frame_from_proto(req.output_frame());
+    let time_scale = time_scale_from_proto(req.time_scale());
+    let time = match (&req.timestamp, epoch) {
+        (None, Some(epoch)) if req.timestamp_unix == 0 => seconds_from_proto(Some(epoch), 0),
+        _ => seconds_from_proto(req.timestamp.as_ref(), req.timestamp_unix),
+    };
+
+    let utc = timescale::to_utc(time, time_scale);
+
+    let orbit = match req.orbit.take() {
+        Some(propagate_request::Orbit::Tle(tle)) => Ok(Orbit::Tle(tle)),
+        Some(propagate_request::Orbit::Omm(omm)) => Ok(Orbit::Omm(omm)),
+        None => Orbit::registered(catalog, &req.satellite_id, utc, req.element_selection()),
+    };
+    let outcome = orbit.and_then(|orbit| {
+        let source = orbit.source()?;
+        let norad_id = source.norad_id().unwrap_or_default();
+        let result = propagator::propagate_at(&source, utc)?;
+        Ok(PropagateResponse {
+            element_epoch: orbit.element_epoch(),
+            ..propagate_response(
+                req.satellite_id.clone(),
+                norad_id,
+                time,
+                time_scale,
+                frame,
+                &result,
+            )
+        })
+    });
+
+    outcome.unwrap_or_else(|status| {
+        failed_response(req.satellite_id, time, time_scale, frame, &status)
+    })
+}
+
+/// Failed propagation of one orbit inside a batch or live feed
+fn failed_response(
+    satellite_id: String,
+    time: f64,
+    time_scale: Scale,
+    frame: Frame,
+    status: &Status,
+) -> PropagateResponse {
+    PropagateResponse {
+        satellite_id,
+        timestamp_unix: time.floor() as i64,
+        timestamp: Some(timestamp_to_proto(time)),
+        time_scale: time_scale_to_proto(time_scale).into(),
+        frame: frame_to_proto(frame).into(),
+        success: false,
+        error_message: status.message().to_string(),
+        // Only propagation errors carry a code; the rest are request checks
+        error_code: status
+            .metadata()
+            .get("error-code")
+            .and_then(|code| code.to_str().ok())
+            .unwrap_or(ErrorCode::InvalidArgument.as_str())
+            .to_string(),
+        ..Default::default()
+    }
+}
+
+/// Catalog entry as returned by the catalog RPCs
+fn catalog_satellite_to_proto(entry: &CatalogEntry) -> CatalogSatellite {
+    CatalogSatellite {
+        satellite_id: entry.satellite_id.clone(),
+        name: entry.name.clone(),
+        norad_id: entry.norad_id,
+        tle: Some(Tle {
+            line1: entry.tle_line1.clone(),
+            line2: entry.tle_line2.clone(),
+            norad_id: String::new(),
+        }),
+        epoch: Some(timestamp_to_proto(entry.epoch_unix)),
+        updated: Some(timestamp_to_proto(entry.updated_unix)),
+    }
+}
+
+/// Serve one live feed: apply client commands as they arrive and push the
+/// positions of all subscribed satellites every interval. Runs until the
+/// client stops reading; closing the command stream keeps the current
+/// subscriptions.
+pub async fn run_live_feed<S>(
+    commands: S,
+    updates: mpsc::Sender<Result<LiveFeedUpdate, Status>>,
+    state: Arc<RwLock<AppState>>,
+) where
+    S: Stream<Item = Result<LiveFeedRequest, Status>>,
+{
+    let mut commands = std::pin::pin!(commands);
+    let mut commands_open = true;
+    let mut feed = LiveFeed::new();
+    let mut ticker = live::ticker(feed.session.interval());
+
+    loop {
+        tokio::select! {
+            command = commands.next(), if commands_open => match command {
+                Some(Ok(command)) => {
+                    if feed.apply(command).await {
+                        ticker = live::ticker(feed.session.interval());
+                    }
+                }
+                Some(Err(status)) => {
+                    debug!("Live feed command stream failed: {}", status);
+                    commands_open = false;
+                }
+                None => commands_open = false,
+            },
+            _ = ticker.tick() => {
+                let start = Instant::now();
+                let Some(update) = feed.update().await else {
+                    continue;
+                };
+                let propagated = update
+                    .positions
+                    .iter()
+                    .filter(|position| position.success)
+                    .count();
+                state
+                    .read()
+                    .await
+                    .metrics
+                    .record_live_feed(start.elapsed(), propagated);
+
+                if updates.send(Ok(update)).await.is_err() {
+                    break;
+                }
+            }
+            _ = updates.closed() => break,
+        }
+    }
+
+    info!(satellites = %feed.session.len(), "Live feed closed");
+}
+
+/// Session of one gRPC live feed, with the refusals still to be reported
+struct LiveFeed {
+    session: LiveSession,
+    /// Refused subscriptions, reported with the next update
+    rejected: Vec<PropagateResponse>,
+}
+
+impl LiveFeed {
+    fn new() -> Self {
+        Self {
+            session: LiveSession::new(),
+            rejected: Vec::new(),
+        }
+    }
+
+    /// Apply a client command; returns true when the interval changed
+    async fn apply(&mut self, request: LiveFeedRequest) -> bool {
+        match request.command {
+            Some(live_feed_request::Command::Add(satellite)) => self.add(satellite).await,
+            Some(live_feed_request::Command::Remove(satellite_id)) => {
+                self.session.remove(&satellite_id);
+            }
+            Some(live_feed_request::Command::Settings(settings)) => {
+                if settings.output_frame() != ReferenceFrame::Unspecified {
+                    self.session.set_frame(frame_from_proto(settings.output_frame()));
+                }
+                if let Some(interval) = settings.interval {
+                    // Negative parts count as zero and the sum saturates, so
+                    // no request can overflow the Duration
+                    let interval = Duration::from_secs(interval.seconds.max(0) as u64)
+                        .saturating_add(Duration::from_nanos(interval.nanos.max(0) as u64));
+                    return self.session.set_interval(interval);
+                }
+            }
+            None => {}
+        }
+        false
+    }
+
+    /// Subscribe a satellite, or record why it was refused
+    async fn add(&mut self, satellite: LiveFeedSatellite) {
+        let satellite_id = satellite.satellite_id.clone();
+        let subscribed = self.subscribe(satellite).await;
+
+        match subscribed {
+            Ok(()) => {}
+            Err(status) if self.rejected.len() < live::MAX_REJECTED => {
+                self.rejected.push(failed_response(
+                    satellite_id,
+                    live::now_unix(),
+                    Scale::Utc,
+                    self.session.frame(),
+                    &status,
+                ))
+            }
+            Err(status) => debug!(
+                satellite_id = %satellite_id,
+                "Live feed refusal dropped: {}",
+                status.message()
+            ),
+        }
+    }
+
+    /// Validate a subscription and initialise its SGP4 state on the compute
+    /// pool, so a bad orbit is refused once, not on every update
+    async fn subscribe(&self, satellite: LiveFeedSatellite) -> Result<(), Status> {
+        let orbit = match satellite.orbit {
+            Some(live_feed_satellite::Orbit::Tle(tle)) => Orbit::Tle(tle),
+            Some(live_feed_satellite::Orbit::Omm(omm)) => Orbit::Omm(omm),
+            None => return Err(Status::invalid_argument("TLE or OMM is required")),
+        };
+        self.session
+            .check_capacity([satellite.satellite_id.as_str()], [])?;
+
+        let orbit = compute::run(move || {
+            orbit
+                .source()
+                .and_then(|source| LiveOrbit::new(source).map_err(Status::from))
+        })
+        .await?;
+        self.session.insert(satellite.satellite_id, orbit)?;
+        Ok(())
+    }
+
+    /// Current positions of every subscribed satellite with the pending
+    /// refusals; None when there is nothing to report
+    async fn update(&mut self) -> Option<LiveFeedUpdate> {
+        let positions = self.session.positions(|position| {
+            let norad_id = position.norad_id.unwrap_or_default();
+            match position.outcome {
+                Ok(result) => propagate_response(
+                    position.satellite_id,
+                    norad_id,
+                    position.time,
+                    Scale::Utc,
+                    position.frame,
+                    &result,
+                ),
+                Err(e) => failed_response(
+                    position.satellite_id,
+                    position.time,
+                    Scale::Utc,
+                    position.frame,
+                    &Status::from(e),
+                ),
+            }
+        });
+        let (time, positions) = match positions.await {
+            Some(update) => update,
+            None if self.rejected.is_empty() => return None,
+            None => (live::now_unix(), Vec::new()),
+        };
+
+        Some(LiveFeedUpdate {
+            timestamp: Some(timestamp_to_proto(time)),
+            positions,
+            rejected: std::mem::take(&mut self.rejected),
+        })
+    }
+}
+
+/// Validated orbit, window and output options of a trajectory request
+struct TrajectoryWindow {
+    orbit: OwnedOrbitSource,
+    norad_id: u64,
+    /// Epoch of the registered element set in use
+    element_epoch: Option<prost_types::Timestamp>,
+    start: f64,
+    end: f64,
+    step_seconds: f64,
+    format: PointFormat,
+}
+
+impl TrajectoryWindow {
+    fn from_request(req: &mut TrajectoryRequest, catalog: &Catalog) -> Result<Self, Status> {
+        let time_scale = time_scale_from_proto(req.time_scale());
+        let start = seconds_from_proto(req.start_time.as_ref(), req.start_timestamp_unix);
+        let end = seconds_from_proto(req.end_time.as_ref(), req.end_timestamp_unix);
+
+        let orbit = match req.orbit.take() {
+            Some(trajectory_request::Orbit::Tle(tle)) => Orbit::Tle(tle),
+            Some(trajectory_request::Orbit::Omm(omm)) => Orbit::Omm(omm),
+            None => Orbit::registered(
+                catalog,
+                &req.satellite_id,
+                timescale::to_utc(start, time_scale),
+                req.element_selection(),
+            )?,
+        };
+        let source = orbit.source()?;
+        let step_seconds = req
+            .step
+            .as_ref()
+            .map_or(req.step_seconds as f64, |step| step.seconds as f64 + step.nanos as f64 * 1e-9);
+
+        if !step_seconds.is_finite() || step_seconds <= 0.0 {
+            return Err(Status::invalid_argument("step_seconds must be positive"));
+        }
+
+        if end <= start {
+            return Err(Status::invalid_argument(
+                "end_timestamp must be after start_timestamp",
+            ));
+        }
+
+        Ok(Self {
+            orbit: OwnedOrbitSource::from(source),
+            norad_id: source.norad_id().unwrap_or_default(),
+            element_epoch: orbit.element_epoch(),
+            start,
+            end,
+            step_seconds,
+            format: PointFormat {
+                frame: frame_from_proto(req.output_frame()),
+                time_scale,
+                include_illumination: req.include_illumination,
+            },
+        })
+    }
+
+    /// Whole steps in the window, kept in f64 so limits are checked before
+    /// any integer conversion; the step is validated positive at construction
+    fn intervals(&self) -> f64 {
+        ((self.end - self.start) / self.step_seconds).floor()
+    }
+
+    fn point_count(&self) -> i64 {
+        (self.intervals() as i64).saturating_add(1)
+    }
+
+    fn eop_fallback(&self) -> bool {
+        self.format.frame.uses_eop()
+            && !eop::covers(
+                timescale::to_utc(self.start, self.format.time_scale),
+                timescale::to_utc(self.end, self.format.time_scale),
+            )
+    }
+}
+
+/// Output options applied to every point of a trajectory
+#[derive(Clone, Copy)]
+struct PointFormat {
+    frame: Frame,
+    time_scale: Scale,
+    include_illumination: bool,
+}
+
+impl PointFormat {
+    fn point(&self, timestamp: f64, result: &propagator::PropagationResult) -> TrajectoryPoint {
+        let utc = timescale::to_utc(timestamp, self.time_scale);
+        let (position, velocity, geodetic) = state_to_proto(result, self.frame, utc);
+        TrajectoryPoint {
+            timestamp_unix: timestamp.floor() as i64,
+            timestamp: Some(timestamp_to_proto(timestamp)),
+            position: Some(position),
+            velocity: Some(velocity),
+            geodetic: Some(geodetic),
+            illumination_fraction: self
+                .include_illumination
+                .then(|| propagator::illumination_fraction(&result.position_km, utc)),
+        }
+    }
+}
+
+/// Position, velocity and geodetic position of a propagated state; the single
+/// conversion behind every gRPC response, mirroring `state_vectors` for HTTP
+fn state_to_proto(
+    result: &propagator::PropagationResult,
+    frame: Frame,
+    utc: f64,
+) -> (EciPosition, EciVelocity, GeodeticPosition) {
+    let (position, velocity) = result.state_in(frame, utc);
+    (
+        EciPosition {
+            x_km: position[0],
+            y_km: position[1],
+            z_km: position[2],
+        },
+        EciVelocity {
+            vx_km_s: velocity[0],
+            vy_km_s: velocity[1],
+            vz_km_s: velocity[2],
+        },
+        GeodeticPosition {
+            latitude_deg: result.geodetic.latitude_deg,
+            longitude_deg: result.geodetic.longitude_deg,
+            altitude_km: result.geodetic.altitude_km,
+        },
+    )
+}
+
+fn decay_to_proto(decay: propagator::Decay) -> Decay {
+    Decay {
+        last_valid_time: Some(timestamp_to_proto(decay.last_valid_unix)),
+        decay_time: Some(timestamp_to_proto(decay.decay_time_unix)),
+    }
+}
+
+/// Orbit definition taken from a request's `orbit` oneof, or the catalog
+enum Orbit {
+    Tle(Tle),
+    Omm(Omm),
+    Registered(Arc<CatalogEntry>),
+}
+
+impl Orbit {
+    /// TLE of the satellite registered as `satellite_id` selected for the UTC
+    /// time `timestamp_unix`, for requests without an orbit of their own
+    fn registered(
+        catalog: &Catalog,
+        satellite_id: &str,
+        timestamp_unix: f64,
+        selection: ElementSelection,
+    ) -> Result<Self, Status> {
+        if satellite_id.is_empty() {
+            return Err(Status::invalid_argument(
+                "TLE, OMM or a registered satellite_id is required",
+            ));
+        }
+        let entry = catalog.elements_at(
+            satellite_id,
+            timestamp_unix,
+            selection_from_proto(selection),
+        )?;
+        Ok(Orbit::Registered(entry))
+    }
+
+    /// Epoch of the registered element set; None for an orbit in the request
+    fn element_epoch(&self) -> Option<prost_types::Timestamp> {
+        match self {
+            Orbit::Registered(entry) => Some(timestamp_to_proto(entry.epoch_unix)),
+            Orbit::Tle(_) | Orbit::Omm(_) => None,
+        }
+    }
+
+    /// Propagation source, rejecting empty TLE lines or OMM content and a
+    /// `norad_id` that disagrees with the TLE
+    fn source(&self) -> Result<OrbitSource<'_>, Status> {
+        match self {
+            Orbit::Tle(tle) if tle.line1.is_empty() || tle.line2.is_empty() => {
+                Err(Status::invalid_argument("TLE lines cannot be empty"))
+            }
+            Orbit::Tle(tle) => {
+                let source = OrbitSource::Tle {
+                    line1: &tle.line1,
+                    line2: &tle.line2,
+                };
+                if !tle.norad_id.is_empty() {
+                    source.check_norad_id(&tle.norad_id)?;
+                }
+                Ok(source)
+            }
+            Orbit::Omm(omm) if omm.content.trim().is_empty() => {
+                Err(Status::invalid_argument("OMM content cannot be empty"))
+            }
+            Orbit::Omm(omm) => Ok(OrbitSource::omm(&omm.content)),
+            Orbit::Registered(entry) => Ok(OrbitSource::Tle {
+                line1: &entry.tle_line1,
+                line2: &entry.tle_line2,
+            }),
+        }
+    }
+}
+
+/// Propagation failures become gRPC errors; the machine-readable code is
+/// sent in the `error-code` metadata entry, decay times in `last-valid-time`
+/// and `decay-time`
+impl From<PropagationError> for Status {
+    fn from(error: PropagationError) -> Self {
+        let code = error.code();
+        let mut status = Status::new(status_code(code), error.to_string());
+        let metadata = status.metadata_mut();
+        metadata.insert("error-code", MetadataValue::from_static(code.as_str()));
+        if let Some(decay) = error.decay() {
+            for (key, time) in [
+                ("last-valid-time", decay.last_valid_unix),
+                ("decay-time", decay.decay_time_unix),
+            ] {
+                if let Ok(value) = rfc3339(time).parse() {
+                    metadata.insert(key, value);
+                }
+            }
+        }
+        status
+    }
+}
+
+/// UTC Unix time as RFC 3339 with millisecond precision
+fn rfc3339(timestamp_unix: f64) -> String {
+    let timestamp = timestamp_to_proto(timestamp_unix);
+    chrono::DateTime::from_timestamp(timestamp.seconds, timestamp.nanos as u32)
+        .map(|time| time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+        .unwrap_or_default()
+}
+
+/// Client errors map to the matching 4xx-style codes; only service faults
+/// are `Internal`
+fn status_code(code: ErrorCode) -> Code {
+    match code {
+        ErrorCode::TleParseError | ErrorCode::InvalidArgument => Code::InvalidArgument,
+        ErrorCode::SatelliteNotFound
+        | ErrorCode::GroundStationNotFound
+        | ErrorCode::SessionNotFound => Code::NotFound,
+        ErrorCode::SatelliteDecayed => Code::FailedPrecondition,
+        ErrorCode::EccentricityOutOfRange | ErrorCode::TimeOutOfValidity => Code::OutOfRange,
+        ErrorCode::Unavailable => Code::Unavailable,
+        ErrorCode::Internal => Code::Internal,
+    }
+}
+
+/// Map the requested output frame; unspecified keeps native TEME
+fn frame_from_proto(frame: ReferenceFrame) -> Frame {
+    match frame {
+        ReferenceFrame::Unspecified | ReferenceFrame::Teme => Frame::Teme,
+        ReferenceFrame::Pef => Frame::Pef,
+        ReferenceFrame::Itrf => Frame::Itrf,
+        ReferenceFrame::J2000 => Frame::J2000,
+        ReferenceFrame::Gcrf => Frame::Gcrf,
+    }
+}
+
+fn frame_to_proto(frame: Frame) -> ReferenceFrame {
+    match frame {
+        Frame::Teme => ReferenceFrame::Teme,
+        Frame::Pef => ReferenceFrame::Pef,
+        Frame::Itrf => ReferenceFrame::Itrf,
+        Frame::J2000 => ReferenceFrame::J2000,
+        Frame::Gcrf => ReferenceFrame::Gcrf,
+    }
+}
+
+/// Map the requested element set selection; unspecified means nearest
+fn selection_from_proto(selection: ElementSelection) -> catalog::ElementSelection {
+    match selection {
+        ElementSelection::Unspecified | ElementSelection::Nearest => {
+            catalog::ElementSelection::Nearest
+        }
+        ElementSelection::LatestBefore => catalog::ElementSelection::LatestBefore,
+    }
+}
+
+/// Map the requested time scale; unspecified means UTC
+fn time_scale_from_proto(time_scale: TimeScale) -> Scale {
+    match time_scale {
+        TimeScale::Unspecified | TimeScale::Utc => Scale::Utc,
+        TimeScale::Tai => Scale::Tai,
+        TimeScale::Tt => Scale::Tt,
+        TimeScale::Gps => Scale::Gps,
+        TimeScale::Ut1 => Scale::Ut1,
+    }
+}
+
+fn time_scale_to_proto(time_scale: Scale) -> TimeScale {
+    match time_scale {
+        Scale::Utc => TimeScale::Utc,
+        Scale::Tai => TimeScale::Tai,
+        Scale::Tt => TimeScale::Tt,
+        Scale::Gps => TimeScale::Gps,
+        Scale::Ut1 => TimeScale::Ut1,
+    }
+}
+
+/// Fractional seconds from an optional Timestamp, else the whole-second field
+fn seconds_from_proto(timestamp: Option<&prost_types::Timestamp>, fallback_unix: i64) -> f64 {
+    timestamp.map_or(fallback_unix as f64, |t| t.seconds as f64 + t.nanos as f64 * 1e-9)
+}
+
+/// Timestamp for fractional seconds, rounded to the microsecond (about the
+/// resolution of an f64 Unix time)
+fn timestamp_to_proto(timestamp_unix: f64) -> prost_types::Timestamp {
+    let seconds = timestamp_unix.floor();
+    let micros = ((timestamp_unix - seconds) * 1e6).round().min(999_999.0);
+    prost_types::Timestamp {
+        seconds: seconds as i64,
+        nanos: micros as i32 * 1000,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generated::orbital::LiveFeedSettings;
+
+    #[tokio::test]
+    async fn test_live_feed_limits() {
+        let mut feed = LiveFeed::new();
+        let interval = |seconds, nanos| LiveFeedRequest {
+            command: Some(live_feed_request::Command::Settings(LiveFeedSettings {
+                interval: Some(prost_types::Duration { seconds, nanos }),
+                output_frame: ReferenceFrame::Unspecified.into(),
+            })),
+        };
+
+        // Out-of-range intervals are clamped rather than overflowing
+        assert!(feed.apply(interval(i64::MAX, i32::MAX)).await);
+        assert_eq!(feed.session.interval(), live::MAX_INTERVAL);
+        assert!(feed.apply(interval(-5, -1)).await);
+        assert_eq!(feed.session.interval(), live::MIN_INTERVAL);
+
+        // Refusals beyond the cap are dropped
+        for index in 0..live::MAX_REJECTED + 10 {
+            let add = LiveFeedRequest {
+                command: Some(live_feed_request::Command::Add(LiveFeedSatellite {
+                    satellite_id: format!("SAT-{}", index),
+                    orbit: None,
+                })),
+            };
+            assert!(!feed.apply(add).await);
+        }
+        let update = feed.update().await.unwrap();
+        assert_eq!(update.rejected.len(), live::MAX_REJECTED);
+        assert!(feed.rejected.is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+//! Low-precision solar ephemeris and Earth shadow geometry
+
+use std::f64::consts::PI;
+
+/// Astronomical unit in km
+const AU_KM: f64 = 149_597_870.7;
+
+/// Mean solar radius in km
+const SUN_RADIUS_KM: f64 = 696_000.0;
+
+/// Earth equatorial radius in km (spherical shadow model)
+const EARTH_RADIUS_KM: f64 = 6378.137;
+
+/// Julian date at Unix epoch (1970-01-01 00:00:00 UTC)
+const JD_UNIX_EPOCH: f64 = 2440587.5;
+
+/// Sun position in the ECI frame (km) at the given Unix time.
+///
+/// Uses the Astronomical Almanac low-precision series (~0.01 deg), which is
+/// ample for shadow and lighting predictions.
+pub fn sun_position_eci(timestamp_unix: f64) -> [f64; 3] {
+    let jd = JD_UNIX_EPOCH + timestamp_unix / 86400.0;
+    let t = (jd - 2451545.0) / 36525.0;
+
+    let mean_longitude_deg = 280.460 + 36000.771 * t;
+    let mean_anomaly = (357.529_109_2 + 35_999.050_34 * t).to_radians();
+
+    let ecliptic_longitude = (mean_longitude_deg
+        + 1.914_666_471 * mean_anomaly.sin()
+        + 0.019_994_643 * (2.0 * mean_anomaly).sin())
+    .to_radians();
+    let distance_au = 1.000_140_612
+        - 0.016_708_617 * mean_anomaly.cos()
+        - 0.000_139_589 * (2.0 * mean_anomaly).cos();
+    let obliquity = (23.439_291 - 0.013_004_2 * t).to_radians();
+
+    let distance_km = distance_au * AU_KM;
+    [
+        distance_km * ecliptic_longitude.cos(),
+        distance_km * obliquity.cos() * ecliptic_longitude.sin(),
+        distance_km * obliquity.sin() * ecliptic_longitude.sin(),
+    ]
+}
+
+/// Apparent geometry of the Sun and Earth discs seen from the satellite
+struct ShadowGeometry {
+    sun_radius: f64,   // Apparent solar radius (rad)
+    earth_radius: f64, // Apparent Earth radius (rad)
+    separation: f64,   // Angle between the disc centres (rad)
+}
+
+fn shadow_geometry(sat_eci: &[f64; 3], sun_eci: &[f64; 3]) -> ShadowGeometry {
+    let to_sun = [
+        sun_eci[0] - sat_eci[0],
+        sun_eci[1] - sat_eci[1],
+        sun_eci[2] - sat_eci[2],
+    ];
+    let to_earth = [-sat_eci[0], -sat_eci[1], -sat_eci[2]];
+
+    let sun_distance = norm(&to_sun);
+    let earth_distance = norm(&to_earth);
+
+    let cos_separation = (to_sun[0] * to_earth[0] + to_sun[1] * to_earth[1] + to_sun[2] * to_earth[2])
+        / (sun_distance * earth_distance);
+
+    ShadowGeometry {
+        sun_radius: (SUN_RADIUS_KM / sun_distance).asin(),
+        earth_radius: (EARTH_RADIUS_KM / earth_distance).min(1.0).asin(),
+        separation: cos_separation.clamp(-1.0, 1.0).acos(),
+    }
+}
+
+/// Angular margins (rad) to the penumbra and umbra cone boundaries.
+///
+/// Each value is negative while the satellite is inside the corresponding
+/// cone, so the boundaries are roots of a continuous function.
+pub fn shadow_margins(sat_eci: &[f64; 3], sun_eci: &[f64; 3]) -> (f64, f64) {
+    let geometry = shadow_geometry(sat_eci, sun_eci);
+    (
+        geometry.separation - (geometry.earth_radius + geometry.sun_radius),
+        geometry.separation - (geometry.earth_radius - geometry.sun_radius),
+    )
+}
+
+/// Fraction of the solar disc visible from the satellite (conical model).
+///
+/// Returns 1.0 in full sunlight, 0.0 in umbra and the unocculted disc area
+/// fraction in penumbra.
+pub fn illumination_fraction(sat_eci: &[f64; 3], sun_eci: &[f64; 3]) -> f64 {
+    let ShadowGeometry {
+        sun_radius: a,
+        earth_radius: b,
+        separation: c,
+    } = shadow_geometry(sat_eci, sun_eci);
+
+    if c >= a + b {
+        return 1.0;
+    }
+    if c <= b - a {
+        return 0.0;
+    }
+    if c <= a - b {
+        // Earth disc entirely inside the solar disc
+        return 1.0 - (b * b) / (a * a);
+    }
+
+    // Area of overlap between the two discs
+    let x = (c * c + a * a - b * b) / (2.0 * c);
+    let y = (a * a - x * x).max(0.0).sqrt();
+    let overlap = a * a * (x / a).clamp(-1.0, 1.0).acos()
+        + b * b * ((c - x) / b).clamp(-1.0, 1.0).acos()
+        - c * y;
+
+    (1.0 - overlap / (PI * a * a)).clamp(0.0, 1.0)
+}
+
+/// Estimated apparent visual magnitude of a sunlit satellite.
+///
+/// `standard_magnitude` is the brightness at 1000 km range and 90 deg phase
+/// angle; the phase law assumes a diffusely reflecting sphere.
+pub fn visual_magnitude(
+    standard_magnitude: f64,
+    sat_eci: &[f64; 3],
+    sun_eci: &[f64; 3],
+    observer_eci: &[f64; 3],
+) -> f64 {
+    let to_sun = [
+        sun_eci[0] - sat_eci[0],
+        sun_eci[1] - sat_eci[1],
+        sun_eci[2] - sat_eci[2],
+    ];
+    let to_observer = [
+        observer_eci[0] - sat_eci[0],
+        observer_eci[1] - sat_eci[1],
+        observer_eci[2] - sat_eci[2],
+    ];
+
+    let range_km = norm(&to_observer);
+    let cos_phase = (to_sun[0] * to_observer[0] + to_sun[1] * to_observer[1] + to_sun[2] * to_observer[2])
+        / (norm(&to_sun) * range_km);
+    let phase = cos_phase.clamp(-1.0, 1.0).acos();
+
+    // Diffuse sphere phase function, normalised to 1 at 90 deg
+    let phase_function = ((PI - phase) * phase.cos() + phase.sin()).max(1e-6);
+
+    standard_magnitude + 5.0 * (range_km / 1000.0).log10() - 2.5 * phase_function.log10()
+}
+
+fn norm(v: &[f64; 3]) -> f64 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sun_distance_and_declination() {
+        // 2024-06-20 20:51 UTC, June solstice
+        let sun = sun_position_eci(1718916660.0);
+        let distance_au = norm(&sun) / AU_KM;
+        let declination_deg = (sun[2] / norm(&sun)).asin().to_degrees();
+
+        assert!((distance_au - 1.016).abs() < 0.001, "got {}", distance_au);
+        assert!((declination_deg - 23.44).abs() < 0.05, "got {}", declination_deg);
+    }
+
+    #[test]
+    fn test_illumination_fraction_regions() {
+        let sun = [AU_KM, 0.0, 0.0];
+
+        // Between Earth and Sun: sunlit
+        assert_eq!(illumination_fraction(&[7000.0, 0.0, 0.0], &sun), 1.0);
+        // Directly behind Earth: umbra
+        assert_eq!(illumination_fraction(&[-7000.0, 0.0, 0.0], &sun), 0.0);
+
+        // Sweep across the shadow edge: fraction rises monotonically through penumbra
+        let mut previous = 0.0;
+        let mut saw_penumbra = false;
+        for step in 0..200 {
+            let y = 6300.0 + step as f64;
+            let fraction = illumination_fraction(&[-7000.0, y, 0.0], &sun);
+            assert!(fraction >= previous);
+            saw_penumbra |= fraction > 0.0 && fraction < 1.0;
+            previous = fraction;
+        }
+        assert!(saw_penumbra);
+        assert_eq!(previous, 1.0);
+    }
+
+    #[test]
+    fn test_visual_magnitude_reference_geometry() {
+        let sat = [7000.0, 0.0, 0.0];
+        let sun = [7000.0, AU_KM, 0.0];
+        let observer = [8000.0, 0.0, 0.0];
+
+        // 1000 km range at 90 deg phase gives the standard magnitude
+        assert!((visual_magnitude(4.0, &sat, &sun, &observer) - 4.0).abs() < 1e-6);
+
+        // Twice the range is 1.5 magnitudes fainter
+        let far_observer = [9000.0, 0.0, 0.0];
+        assert!((visual_magnitude(4.0, &sat, &sun, &far_observer) - (4.0 + 5.0 * 2.0_f64.log10())).abs() < 1e-6);
+
+        // Fully lit (observer near the Sun direction) is brighter
+        let front_observer = [7000.0, 1000.0, 0.0];
+        assert!(visual_magnitude(4.0, &sat, &sun, &front_observer) < 4.0);
+    }
+}
@@ -0,0 +1,302 @@
+//! File-backed persistence for the catalog
+//!
+//! Every change is appended to a JSON-lines journal and synced to disk before
+//! it is applied, so an acknowledged write survives a restart or crash. Once
+//! the journal outgrows the snapshot, the full contents are written to a new
+//! snapshot that is renamed into place and the journal starts over. A crash
+//! at any point leaves either the old or the new snapshot, and replaying
+//! journal records that are already in the snapshot gives the same state.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::catalog::CatalogEntry;
+use crate::propagator::GroundStation;
+
+const JOURNAL_FILE: &str = "journal.jsonl";
+const SNAPSHOT_FILE: &str = "snapshot.jsonl";
+const SNAPSHOT_TEMP_FILE: &str = "snapshot.jsonl.tmp";
+
+/// Journal records below which compaction is not worth a rewrite
+const MIN_COMPACTION_RECORDS: usize = 1000;
+
+/// One stored change, replayed in order on startup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Change {
+    /// Element set registered for a satellite; it becomes the current one
+    PutSatellite(CatalogEntry),
+    /// Satellite removed together with its history
+    DeleteSatellite {
+        satellite_id: String,
+    },
+    PutGroundStation(GroundStation),
+    DeleteGroundStation {
+        station_id: String,
+    },
+}
+
+/// Snapshot and journal in one data directory
+pub struct Store {
+    dir: PathBuf,
+    journal: File,
+    /// Length of the journal up to its last complete record
+    journal_len: u64,
+    journal_records: usize,
+    snapshot_records: usize,
+}
+
+impl Store {
+    /// Open the store in `dir`, creating it when missing, and return it with
+    /// the stored changes in the order they were made. A record cut short by
+    /// a crash is dropped.
+    pub fn open(dir: &Path) -> Result<(Self, Vec<Change>), StoreError> {
+        fs::create_dir_all(dir).map_err(|e| io_error(dir, e))?;
+
+        let (mut changes, _) = read_changes(&dir.join(SNAPSHOT_FILE))?;
+        let snapshot_records = changes.len();
+
+        let journal_path = dir.join(JOURNAL_FILE);
+        let (journal_changes, journal_len) = read_changes(&journal_path)?;
+        let journal = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&journal_path)
+            .map_err(|e| io_error(&journal_path, e))?;
+        let file_len = journal
+            .metadata()
+            .map_err(|e| io_error(&journal_path, e))?
+            .len();
+        if file_len > journal_len {
+            warn!(
+                path = %journal_path.display(),
+                bytes = file_len - journal_len,
+                "Dropping incomplete journal record"
+            );
+            journal
+                .set_len(journal_len)
+                .and_then(|()| journal.sync_data())
+                .map_err(|e| io_error(&journal_path, e))?;
+        }
+        sync_dir(dir);
+
+        let journal_records = journal_changes.len();
+        changes.extend(journal_changes);
+        info!(
+            path = %dir.display(),
+            snapshot_records,
+            journal_records,
+            "Opened data store"
+        );
+
+        let store = Self {
+            dir: dir.to_path_buf(),
+            journal,
+            journal_len,
+            journal_records,
+            snapshot_records,
+        };
+        Ok((store, changes))
+    }
+
+    /// Append `change` to the journal; it is on disk when this returns Ok
+    pub fn append(&mut self, change: &Change) -> Result<(), StoreError> {
+        let mut line = serde_json::to_vec(change).map_err(|e| StoreError::Io(e.to_string()))?;
+        line.push(b'\n');
+
+        let path = self.dir.join(JOURNAL_FILE);
+        if let Err(e) = self
+            .journal
+            .write_all(&line)
+            .and_then(|()| self.journal.sync_data())
+        {
+            // Cut off a partial record so later appends start on a new line
+            let _ = self.journal.set_len(self.journal_len);
+            return Err(io_error(&path, e));
+        }
+
+        self.journal_len += line.len() as u64;
+        self.journal_records += 1;
+        Ok(())
+    }
+
+    /// Whether the journal has grown enough to fold into the snapshot
+    pub fn needs_compaction(&self) -> bool {
+        self.journal_records >= MIN_COMPACTION_RECORDS
+            && self.journal_records > self.snapshot_records
+    }
+
+    /// Replace the snapshot with `changes`, the full current contents, and
+    /// empty the journal
+    pub fn compact(&mut self, changes: &[Change]) -> Result<(), StoreError> {
+        let temp_path = self.dir.join(SNAPSHOT_TEMP_FILE);
+        let snapshot_path = self.dir.join(SNAPSHOT_FILE);
+        write_snapshot(&temp_path, changes).map_err(|e| io_error(&temp_path, e))?;
+        fs::rename(&temp_path, &snapshot_path).map_err(|e| io_error(&snapshot_path, e))?;
+        sync_dir(&self.dir);
+
+        let journal_path = self.dir.join(JOURNAL_FILE);
+        self.journal
+            .set_len(0)
+            .and_then(|()| self.journal.sync_data())
+            .map_err(|e| io_error(&journal_path, e))?;
+
+        info!(
+            path = %self.dir.display(),
+            records = changes.len(),
+            compacted = self.journal_records,
+            "Compacted data store"
+        );
+        self.journal_len = 0;
+        self.journal_records = 0;
+        self.snapshot_records = changes.len();
+        Ok(())
+    }
+}
+
+/// Complete records of a file and the length they cover; a missing file is
+/// empty
+fn read_changes(path: &Path) -> Result<(Vec<Change>, u64), StoreError> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+        Err(e) => return Err(io_error(path, e)),
+    };
+
+    // Records end with a newline, so anything after the last one is a
+    // write interrupted by a crash
+    let complete = bytes
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |end| end + 1);
+
+    let mut changes = Vec::new();
+    for (index, line) in bytes[..complete].split(|&b| b == b'\n').enumerate() {
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        let change = serde_json::from_slice(line).map_err(|e| StoreError::Parse {
+            path: path.display().to_string(),
+            line: index + 1,
+            message: e.to_string(),
+        })?;
+        changes.push(change);
+    }
+
+    Ok((changes, complete as u64))
+}
+
+fn write_snapshot(path: &Path, changes: &[Change]) -> io::Result<()> {
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    for change in changes {
+        serde_json::to_writer(&mut writer, change)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.into_inner().map_err(|e| e.into_error())?.sync_all()
+}
+
+/// Make renames and new files in `dir` durable; not supported everywhere,
+/// so failures are ignored
+fn sync_dir(dir: &Path) {
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+}
+
+fn io_error(path: &Path, error: io::Error) -> StoreError {
+    StoreError::Io(format!("{}: {}", path.display(), error))
+}
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io(String),
+    Parse {
+        path: String,
+        line: usize,
+        message: String,
+    },
+}
+
+impl std::fmt::Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StoreError::Io(msg) => write!(f, "Data store error: {}", msg),
+            StoreError::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "Invalid record in {} line {}: {}", path, line, message),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("orbital-store-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn delete(satellite_id: &str) -> Change {
+        Change::DeleteSatellite {
+            satellite_id: satellite_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_changes_survive_reopen() {
+        let dir = temp_dir("reopen");
+        let (mut store, changes) = Store::open(&dir).unwrap();
+        assert!(changes.is_empty());
+        store.append(&delete("A")).unwrap();
+        store.append(&delete("B")).unwrap();
+        drop(store);
+
+        let (mut store, changes) = Store::open(&dir).unwrap();
+        assert_eq!(changes, vec![delete("A"), delete("B")]);
+
+        store.compact(&[delete("C")]).unwrap();
+        store.append(&delete("D")).unwrap();
+        drop(store);
+
+        let (_, changes) = Store::open(&dir).unwrap();
+        assert_eq!(changes, vec![delete("C"), delete("D")]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_incomplete_record_is_dropped() {
+        let dir = temp_dir("torn");
+        let (mut store, _) = Store::open(&dir).unwrap();
+        store.append(&delete("A")).unwrap();
+        drop(store);
+
+        // A crash part way through writing the second record
+        let mut journal = OpenOptions::new()
+            .append(true)
+            .open(dir.join(JOURNAL_FILE))
+            .unwrap();
+        journal.write_all(br#"{"op":"delete_sat"#).unwrap();
+        drop(journal);
+
+        let (mut store, changes) = Store::open(&dir).unwrap();
+        assert_eq!(changes, vec![delete("A")]);
+        store.append(&delete("B")).unwrap();
+        drop(store);
+
+        let (_, changes) = Store::open(&dir).unwrap();
+        assert_eq!(changes, vec![delete("A"), delete("B")]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -4,8 +4,8 @@ mod propagator_tests {
     use super::super::propagator::*;
 
     // Valid ISS TLE from January 2024
-    const ISS_TLE_LINE1: &str = "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9006";
-    const ISS_TLE_LINE2: &str = "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423094";
+    const ISS_TLE_LINE1: &str = "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008";
+    const ISS_TLE_LINE2: &str = "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096";
 
     #[test]
     fn test_propagate_valid_tle() {
@@ -99,18 +99,21 @@ mod propagator_tests {
         let end = start + 86400; // 24 hours
         
         // New York City ground station
-        let gs_lat = 40.7128;
-        let gs_lon = -74.0060;
-        let gs_alt_km = 0.01; // 10 meters
         let min_elevation = 5.0; // 5 degrees
+        let ground_station = GroundStation {
+            id: "NYC".to_string(),
+            name: "New York".to_string(),
+            latitude_deg: 40.7128,
+            longitude_deg: -74.0060,
+            altitude_m: 10.0,
+            min_elevation_deg: min_elevation,
+            horizon_mask: Vec::new(),
+        };
         
-        let result = calculate_visibility(
+        let result = calculate_visibility_passes(
             ISS_TLE_LINE1,
             ISS_TLE_LINE2,
-            &gs_lat,
-            &gs_lon,
-            gs_alt_km,
-            min_elevation,
+            &ground_station,
             start,
             end,
         );
@@ -120,7 +123,7 @@ mod propagator_tests {
         let passes = result.unwrap();
         
         // ISS should have multiple passes over 24 hours
-        assert!(passes.len() > 0, "Should have at least one pass");
+        assert!(!passes.is_empty(), "Should have at least one pass");
         
         // Verify pass structure
         for pass in &passes {
@@ -168,13 +171,20 @@ mod propagator_tests {
         let start = 1704067200;
         let end = start + 7200; // 2 hours
         
-        let result = calculate_visibility(
+        let ground_station = GroundStation {
+            id: "NULL".to_string(),
+            name: "Null Island".to_string(),
+            latitude_deg: 0.0,  // Equator
+            longitude_deg: 0.0, // Prime meridian
+            altitude_m: 0.0,    // Sea level
+            min_elevation_deg: 0.0, // Any elevation
+            horizon_mask: Vec::new(),
+        };
+        
+        let result = calculate_visibility_passes(
             ISS_TLE_LINE1,
             ISS_TLE_LINE2,
-            &0.0,  // Equator
-            &0.0,  // Prime meridian
-            0.0,   // Sea level
-            0.0,   // Any elevation
+            &ground_station,
             start,
             end,
         );
@@ -194,9 +204,15 @@ mod http_integration_tests {
         // For now, we test the request/response types
         let req = PropagateRequest {
             satellite_id: "ISS".to_string(),
-            tle_line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9006".to_string(),
-            tle_line2: "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423094".to_string(),
+            tle_line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008".to_string(),
+            tle_line2: "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096".to_string(),
+            omm: None,
+            norad_id: None,
             timestamp_unix: 1704067200,
+            timestamp: None,
+            output_frame: ReferenceFrame::Teme,
+            time_scale: TimeScale::Utc,
+            element_selection: ElementSelection::Nearest,
         };
         
         assert_eq!(req.satellite_id, "ISS");
@@ -210,15 +226,27 @@ mod http_integration_tests {
             requests: vec![
                 PropagateRequest {
                     satellite_id: "SAT1".to_string(),
-                    tle_line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9006".to_string(),
-                    tle_line2: "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423094".to_string(),
+                    tle_line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008".to_string(),
+                    tle_line2: "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096".to_string(),
+                    omm: None,
+                    norad_id: None,
                     timestamp_unix: 1704067200,
+                    timestamp: None,
+                    output_frame: ReferenceFrame::Teme,
+                    time_scale: TimeScale::Utc,
+                    element_selection: ElementSelection::Nearest,
                 },
                 PropagateRequest {
                     satellite_id: "SAT2".to_string(),
-                    tle_line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9006".to_string(),
-                    tle_line2: "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423094".to_string(),
+                    tle_line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008".to_string(),
+                    tle_line2: "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096".to_string(),
+                    omm: None,
+                    norad_id: None,
                     timestamp_unix: 1704067300,
+                    timestamp: None,
+                    output_frame: ReferenceFrame::Teme,
+                    time_scale: TimeScale::Utc,
+                    element_selection: ElementSelection::Nearest,
                 },
             ],
         };
@@ -230,37 +258,759 @@ mod http_integration_tests {
     async fn test_trajectory_request_structure() {
         let req = TrajectoryRequest {
             satellite_id: "ISS".to_string(),
-            tle_line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9006".to_string(),
-            tle_line2: "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423094".to_string(),
+            tle_line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008".to_string(),
+            tle_line2: "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096".to_string(),
+            omm: None,
+            norad_id: None,
             start_timestamp_unix: 1704067200,
             end_timestamp_unix: 1704070800,
-            step_seconds: 60,
+            start_time: None,
+            end_time: None,
+            step_seconds: 60.0,
+            include_illumination: false,
+            output_frame: ReferenceFrame::Itrf,
+            time_scale: TimeScale::Utc,
+            element_selection: ElementSelection::Nearest,
         };
         
         assert!(req.end_timestamp_unix > req.start_timestamp_unix);
-        assert!(req.step_seconds > 0);
+        assert!(req.step_seconds > 0.0);
     }
 
     #[tokio::test]
     async fn test_visibility_request_structure() {
         let req = VisibilityRequest {
             satellite_id: "ISS".to_string(),
-            tle_line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9006".to_string(),
-            tle_line2: "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423094".to_string(),
-            ground_station: GroundStation {
+            tle_line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008".to_string(),
+            tle_line2: "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096".to_string(),
+            omm: None,
+            norad_id: None,
+            ground_station: Some(GroundStation {
                 id: "GS1".to_string(),
                 name: "Test Station".to_string(),
                 latitude_deg: 40.7128,
                 longitude_deg: -74.0060,
                 altitude_m: 10.0,
                 min_elevation_deg: 5.0,
-            },
+                horizon_mask: vec![],
+            }),
+            ground_station_id: None,
             start_timestamp_unix: 1704067200,
             end_timestamp_unix: 1704153600,
+            start_time: None,
+            end_time: None,
+            pass_sampling: None,
+            visibility_mode: VisibilityMode::Radio,
+            twilight: Twilight::default(),
+            standard_magnitude: None,
+            time_scale: TimeScale::Tt,
+            element_selection: ElementSelection::Nearest,
         };
         
-        assert!(req.ground_station.latitude_deg.abs() <= 90.0);
-        assert!(req.ground_station.longitude_deg.abs() <= 180.0);
+        let ground_station = req.ground_station.unwrap();
+        assert!(ground_station.latitude_deg.abs() <= 90.0);
+        assert!(ground_station.longitude_deg.abs() <= 180.0);
+    }
+
+    #[tokio::test]
+    async fn test_visibility_window_is_checked() {
+        let request = |end_timestamp_unix: i64| VisibilityRequest {
+            satellite_id: "ISS".to_string(),
+            tle_line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008"
+                .to_string(),
+            tle_line2: "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096"
+                .to_string(),
+            omm: None,
+            norad_id: None,
+            ground_station: Some(GroundStation {
+                id: "GS1".to_string(),
+                name: "Test Station".to_string(),
+                latitude_deg: 40.7128,
+                longitude_deg: -74.0060,
+                altitude_m: 10.0,
+                min_elevation_deg: 5.0,
+                horizon_mask: vec![],
+            }),
+            ground_station_id: None,
+            start_timestamp_unix: 1704067200,
+            end_timestamp_unix,
+            start_time: None,
+            end_time: None,
+            pass_sampling: None,
+            visibility_mode: VisibilityMode::Radio,
+            twilight: Twilight::default(),
+            standard_magnitude: None,
+            time_scale: TimeScale::Utc,
+            element_selection: ElementSelection::Nearest,
+        };
+        let state = || State(Arc::new(RwLock::new(AppState::new())));
+
+        // Empty, reversed and overlong windows
+        for end_timestamp_unix in [1704067200, 1704067200 - 60, 1704067200 + 400 * 86400] {
+            let rejected = visibility_handler(state(), Json(request(end_timestamp_unix)))
+                .await
+                .unwrap_err();
+            assert_eq!((rejected.status, rejected.code), (400, "INVALID_ARGUMENT"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_eclipse_window_is_capped() {
+        let state = State(Arc::new(RwLock::new(AppState::new())));
+        let request: EclipseRequest = serde_json::from_value(serde_json::json!({
+            "satellite_id": "ISS",
+            "tle_line1": "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008",
+            "tle_line2": "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096",
+            "start_timestamp_unix": 1704067200,
+            "end_timestamp_unix": 1704067200 + 32 * 86400,
+        }))
+        .unwrap();
+
+        let rejected = eclipse_handler(state, Json(request)).await.unwrap_err();
+        assert_eq!((rejected.status, rejected.code), (400, "INVALID_ARGUMENT"));
+        assert!(rejected.detail.contains("31 days"), "{}", rejected.detail);
+    }
+
+    #[tokio::test]
+    async fn test_eclipse_window_from_iso_times() {
+        let state = State(Arc::new(RwLock::new(AppState::new())));
+        let request: EclipseRequest = serde_json::from_value(serde_json::json!({
+            "satellite_id": "ISS",
+            "tle_line1": "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008",
+            "tle_line2": "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096",
+            "start_time": "2024-01-01T00:00:00.250Z",
+            "end_time": 1704067200.25 + 86400.0,
+        }))
+        .unwrap();
+
+        let Json(response) = eclipse_handler(state, Json(request)).await.unwrap();
+        assert!(!response.eclipses.is_empty());
+        for eclipse in &response.eclipses {
+            assert!(eclipse.entry_time_unix >= 1704067200.25);
+            assert!(eclipse.exit_time_unix <= 1704067200.25 + 86400.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_propagate_age_check_uses_utc() {
+        // 30 s inside the one-year limit on the TT clock, but over it in UTC
+        let one_year = 365 * 24 * 3600;
+        let timestamp_unix = chrono::Utc::now().timestamp() - one_year + 30;
+        let rejected = propagate_handler(
+            State(Arc::new(RwLock::new(AppState::new()))),
+            Json(PropagateRequest {
+                satellite_id: "ISS".to_string(),
+                tle_line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008"
+                    .to_string(),
+                tle_line2: "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096"
+                    .to_string(),
+                omm: None,
+                norad_id: None,
+                timestamp_unix,
+                timestamp: None,
+                output_frame: ReferenceFrame::Teme,
+                time_scale: TimeScale::Tt,
+                element_selection: ElementSelection::Nearest,
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!((rejected.status, rejected.code), (422, "TIME_OUT_OF_VALIDITY"));
+    }
+
+    #[test]
+    fn test_ground_station_horizon_mask_json() {
+        let station: GroundStation = serde_json::from_value(serde_json::json!({
+            "id": "GS1",
+            "name": "Test Station",
+            "latitude_deg": 40.7128,
+            "longitude_deg": -74.0060,
+            "altitude_m": 10.0,
+            "min_elevation_deg": 5.0,
+            "horizon_mask": [
+                {"azimuth_deg": 0.0, "min_elevation_deg": 12.0},
+                {"azimuth_deg": 180.0, "min_elevation_deg": 3.0}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(station.horizon_mask.len(), 2);
+
+        // The mask is optional
+        let station: GroundStation = serde_json::from_value(serde_json::json!({
+            "id": "GS1",
+            "name": "Test Station",
+            "latitude_deg": 40.7128,
+            "longitude_deg": -74.0060,
+            "altitude_m": 10.0,
+            "min_elevation_deg": 5.0
+        }))
+        .unwrap();
+        assert!(station.horizon_mask.is_empty());
+    }
+
+    #[test]
+    fn test_output_frame_json() {
+        let req: PropagateRequest = serde_json::from_value(serde_json::json!({
+            "satellite_id": "ISS",
+            "tle_line1": "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008",
+            "tle_line2": "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096",
+            "timestamp_unix": 1704067200,
+            "output_frame": "gcrf"
+        }))
+        .unwrap();
+        assert!(matches!(req.output_frame, ReferenceFrame::Gcrf));
+
+        // Defaults to the native SGP4 frame and is reported in upper case
+        let req: PropagateRequest = serde_json::from_value(serde_json::json!({
+            "satellite_id": "ISS",
+            "tle_line1": "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008",
+            "tle_line2": "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096",
+            "timestamp_unix": 1704067200
+        }))
+        .unwrap();
+        assert_eq!(serde_json::to_value(req.output_frame).unwrap(), "TEME");
+    }
+
+    #[test]
+    fn test_sub_second_timestamps_json() {
+        let request = |timestamp: serde_json::Value| {
+            serde_json::from_value::<PropagateRequest>(serde_json::json!({
+                "satellite_id": "ISS",
+                "tle_line1": "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008",
+                "tle_line2": "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096",
+                "timestamp": timestamp
+            }))
+        };
+
+        let fractional = request(serde_json::json!(1704067200.25)).unwrap();
+        let iso = request(serde_json::json!("2024-01-01T00:00:00.250Z")).unwrap();
+        let offset = request(serde_json::json!("2024-01-01T01:00:00.25+01:00")).unwrap();
+        let naive = request(serde_json::json!("2024-01-01T00:00:00.25")).unwrap();
+        for req in [fractional, iso, offset, naive] {
+            assert_eq!(request_time(req.timestamp, req.timestamp_unix), 1704067200.25);
+        }
+        assert!(request(serde_json::json!("yesterday")).is_err());
+
+        // Fractional trajectory steps
+        let req: TrajectoryRequest = serde_json::from_value(serde_json::json!({
+            "satellite_id": "ISS",
+            "tle_line1": "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008",
+            "tle_line2": "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096",
+            "start_time": "2024-01-01T00:00:00Z",
+            "end_time": 1704067201,
+            "step_seconds": 0.1
+        }))
+        .unwrap();
+        assert_eq!(request_time(req.start_time, req.start_timestamp_unix), 1704067200.0);
+        assert_eq!(req.step_seconds, 0.1);
+
+        assert_eq!(iso_8601(1704067200.25, TimeScale::Utc), "2024-01-01T00:00:00.250000Z");
+        assert_eq!(iso_8601(1704067269.184, TimeScale::Tt), "2024-01-01T00:01:09.184000");
+    }
+
+    #[test]
+    fn test_omm_request_json() {
+        // JSON OMMs can be embedded as objects
+        let req: PropagateRequest = serde_json::from_value(serde_json::json!({
+            "satellite_id": "ISS",
+            "timestamp_unix": 1704067200,
+            "omm": {"EPOCH": "2024-01-01T12:00:00", "MEAN_MOTION": 15.50377579}
+        }))
+        .unwrap();
+        let omm = req.omm.as_ref().unwrap();
+        assert!(omm.0.starts_with('{'));
+        assert!(matches!(
+            orbit_source(&req.tle_line1, &req.tle_line2, req.omm.as_ref(), None),
+            Ok(OrbitSource::Omm { .. })
+        ));
+
+        // Exactly one orbit source
+        assert!(orbit_source("1 25544U", "2 25544", Some(omm), None).is_err());
+        assert!(orbit_source("", "", None, None).is_err());
+    }
+
+    #[test]
+    fn test_tle_validation_errors() {
+        let line1 = "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008";
+        let line2 = "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096";
+        assert!(orbit_source(line1, line2, None, None).is_ok());
+
+        // Errors name the line and column of the offending field
+        let bad_checksum = format!("{}0", &line1[..68]);
+        let error = orbit_source(&bad_checksum, line2, None, None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "TLE parse error: line 1, column 69 (checksum): 0 does not match computed checksum 8"
+        );
+
+        let error = orbit_source(line1, &line2[..60], None, None).unwrap_err();
+        assert!(error.to_string().contains("line 2, column 61"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_trajectory_stream_ndjson() {
+        let request = || TrajectoryRequest {
+            satellite_id: "ISS".to_string(),
+            tle_line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008".to_string(),
+            tle_line2: "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096".to_string(),
+            omm: None,
+            norad_id: None,
+            start_timestamp_unix: 1704067200,
+            end_timestamp_unix: 1704067200 + 3 * 3600,
+            start_time: None,
+            end_time: None,
+            step_seconds: 1.0,
+            include_illumination: false,
+            output_frame: ReferenceFrame::Teme,
+            time_scale: TimeScale::Utc,
+            element_selection: ElementSelection::Nearest,
+        };
+        let state = || State(Arc::new(RwLock::new(AppState::new())));
+
+        // Three hours at one-second steps is over the buffered limit
+        let buffered = trajectory_handler(state(), Json(request())).await;
+        assert_eq!(buffered.err().unwrap().status, 400);
+
+        // Step counts too large for an integer and non-positive steps are rejected
+        for step_seconds in [1e-300, 0.0, -1.0] {
+            let buffered =
+                trajectory_handler(state(), Json(TrajectoryRequest { step_seconds, ..request() })).await;
+            assert_eq!(buffered.err().unwrap().status, 400);
+        }
+
+        let response = trajectory_stream_handler(state(), Json(request())).await.unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/x-ndjson");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let lines: Vec<serde_json::Value> = std::str::from_utf8(&body)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 3 * 3600 + 2);
+        assert_eq!(lines[1]["timestamp_unix"], 1704067201);
+        let summary = lines.last().unwrap();
+        assert_eq!(summary["complete"], true);
+        assert_eq!(summary["point_count"], 3 * 3600 + 1);
+        assert_eq!(summary["decayed"], false);
+    }
+
+    #[tokio::test]
+    async fn test_satellite_catalog_endpoints() {
+        let state = Arc::new(RwLock::new(AppState::new()));
+        let upsert = |tle_line1: &str| {
+            catalog_upsert_handler(
+                State(Arc::clone(&state)),
+                Path("ISS".to_string()),
+                Json(CatalogUpsertRequest {
+                    name: "ISS (ZARYA)".to_string(),
+                    tle_line1: tle_line1.to_string(),
+                    tle_line2: "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096".to_string(),
+                    norad_id: None,
+                }),
+            )
+        };
+        let iss_line1 = "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008";
+
+        let (status, Json(object)) = upsert(iss_line1).await.unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(object.norad_id, 25544);
+        assert!(object.epoch.starts_with("2024-01-01T"));
+        let (status, _) = upsert(iss_line1).await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        let rejected = upsert(&iss_line1.replace("9008", "9000"))
+            .await
+            .unwrap_err();
+        assert_eq!(rejected.status, 400);
+
+        let Json(listed) = catalog_list_handler(State(Arc::clone(&state))).await;
+        assert_eq!(listed.count, 1);
+
+        // A registered satellite_id stands in for the TLE lines
+        let Json(response) = propagate_handler(
+            State(Arc::clone(&state)),
+            Json(PropagateRequest {
+                satellite_id: "ISS".to_string(),
+                tle_line1: String::new(),
+                tle_line2: String::new(),
+                omm: None,
+                norad_id: None,
+                timestamp_unix: chrono::Utc::now().timestamp(),
+                timestamp: None,
+                output_frame: ReferenceFrame::Teme,
+                time_scale: TimeScale::Utc,
+                element_selection: ElementSelection::Nearest,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.norad_id, Some(25544));
+
+        let status = catalog_delete_handler(State(Arc::clone(&state)), Path("ISS".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let missing = catalog_get_handler(State(Arc::clone(&state)), Path("ISS".to_string()))
+            .await
+            .unwrap_err();
+        assert_eq!((missing.status, missing.code), (404, "SATELLITE_NOT_FOUND"));
+    }
+
+    #[tokio::test]
+    async fn test_propagate_from_tle_history() {
+        let state = Arc::new(RwLock::new(AppState::new()));
+        let tle_line2 = "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096";
+        // Epochs at noon on 1 and 2 January 2024
+        let (first_epoch, second_epoch) = (1704110400.0, 1704196800.0);
+        // The older set is backfilled after the newer one
+        for (tle_line1, expected) in [
+            (
+                "1 25544U 98067A   24002.50000000  .00016717  00000+0  10270-3 0  9009",
+                StatusCode::CREATED,
+            ),
+            (
+                "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008",
+                StatusCode::OK,
+            ),
+        ] {
+            let (status, _) = catalog_upsert_handler(
+                State(Arc::clone(&state)),
+                Path("ISS".to_string()),
+                Json(CatalogUpsertRequest {
+                    name: "ISS (ZARYA)".to_string(),
+                    tle_line1: tle_line1.to_string(),
+                    tle_line2: tle_line2.to_string(),
+                    norad_id: None,
+                }),
+            )
+            .await
+            .unwrap();
+            assert_eq!(status, expected);
+        }
+
+        // The newest epoch stays current
+        let Json(current) =
+            catalog_get_handler(State(Arc::clone(&state)), Path("ISS".to_string()))
+                .await
+                .unwrap();
+        assert!((current.epoch_unix - second_epoch).abs() < 1e-3);
+
+        let Json(history) =
+            catalog_history_handler(State(Arc::clone(&state)), Path("ISS".to_string()))
+                .await
+                .unwrap();
+        assert_eq!(history.count, 2);
+        assert!((history.element_sets[0].epoch_unix - first_epoch).abs() < 1e-3);
+        assert!((history.element_sets[1].epoch_unix - second_epoch).abs() < 1e-3);
+
+        // Five hours before the second epoch: nearest picks it, latest
+        // before falls back to the first
+        let propagate = |element_selection: ElementSelection| {
+            propagate_handler(
+                State(Arc::clone(&state)),
+                Json(PropagateRequest {
+                    satellite_id: "ISS".to_string(),
+                    tle_line1: String::new(),
+                    tle_line2: String::new(),
+                    omm: None,
+                    norad_id: None,
+                    timestamp_unix: second_epoch as i64 - 5 * 3600,
+                    timestamp: None,
+                    output_frame: ReferenceFrame::Teme,
+                    time_scale: TimeScale::Utc,
+                    element_selection,
+                }),
+            )
+        };
+        let Json(nearest) = propagate(ElementSelection::Nearest).await.unwrap();
+        let json = serde_json::to_value(&nearest).unwrap();
+        assert!((json["element_epoch_unix"].as_f64().unwrap() - second_epoch).abs() < 1e-3);
+        assert!(json["element_epoch"]
+            .as_str()
+            .unwrap()
+            .starts_with("2024-01-02T"));
+
+        let Json(before) = propagate(ElementSelection::LatestBefore).await.unwrap();
+        let element_epoch = before.element_epoch.unwrap();
+        assert!((element_epoch.element_epoch_unix - first_epoch).abs() < 1e-3);
+        assert!(element_epoch.element_epoch.starts_with("2024-01-01T"));
+    }
+
+    #[tokio::test]
+    async fn test_ground_station_endpoints() {
+        let state = Arc::new(RwLock::new(AppState::new()));
+        let upsert = |horizon_mask: Vec<HorizonMaskPoint>| {
+            ground_station_upsert_handler(
+                State(Arc::clone(&state)),
+                Path("SVALBARD".to_string()),
+                Json(GroundStationUpsertRequest {
+                    name: "Svalbard".to_string(),
+                    latitude_deg: 78.23,
+                    longitude_deg: 15.39,
+                    altitude_m: 500.0,
+                    min_elevation_deg: 5.0,
+                    horizon_mask,
+                }),
+            )
+        };
+
+        let (status, Json(station)) = upsert(Vec::new()).await.unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(station.id, "SVALBARD");
+        let mask = vec![HorizonMaskPoint {
+            azimuth_deg: 180.0,
+            min_elevation_deg: 10.0,
+        }];
+        let (status, _) = upsert(mask).await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        let out_of_range = vec![HorizonMaskPoint {
+            azimuth_deg: 400.0,
+            min_elevation_deg: 10.0,
+        }];
+        let rejected = upsert(out_of_range).await.unwrap_err();
+        assert_eq!(rejected.status, 400);
+
+        let Json(listed) = ground_station_list_handler(State(Arc::clone(&state))).await;
+        assert_eq!(listed.count, 1);
+        assert_eq!(listed.ground_stations[0].horizon_mask.len(), 1);
+
+        // Visibility requests can name a registered station instead of describing one
+        let visibility = |ground_station_id: &str| {
+            let request: VisibilityRequest = serde_json::from_value(serde_json::json!({
+                "satellite_id": "ISS",
+                "tle_line1": "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008",
+                "tle_line2": "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096",
+                "ground_station_id": ground_station_id,
+                "start_timestamp_unix": 1704067200,
+                "end_timestamp_unix": 1704067200 + 86400,
+            }))
+            .unwrap();
+            visibility_handler(State(Arc::clone(&state)), Json(request))
+        };
+        let Json(response) = visibility("SVALBARD").await.unwrap();
+        assert_eq!(response.ground_station_id, "SVALBARD");
+        let missing = visibility("NOWHERE").await.unwrap_err();
+        assert_eq!(
+            (missing.status, missing.code),
+            (404, "GROUND_STATION_NOT_FOUND")
+        );
+
+        let status =
+            ground_station_delete_handler(State(Arc::clone(&state)), Path("SVALBARD".to_string()))
+                .await
+                .unwrap();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let missing =
+            ground_station_get_handler(State(Arc::clone(&state)), Path("SVALBARD".to_string()))
+                .await
+                .unwrap_err();
+        assert_eq!((missing.status, missing.code), (404, "GROUND_STATION_NOT_FOUND"));
+    }
+
+    // Next SSE event as (name, JSON payload), skipping keep-alive comments
+    async fn next_event(
+        body: &mut axum::body::BodyDataStream,
+        buffer: &mut String,
+    ) -> (String, serde_json::Value) {
+        use tokio_stream::StreamExt;
+
+        loop {
+            if let Some(end) = buffer.find("\n\n") {
+                let frame: String = buffer.drain(..end + 2).collect();
+                let mut name = "";
+                let mut data = serde_json::Value::Null;
+                for line in frame.lines() {
+                    if let Some(value) = line.strip_prefix("event: ") {
+                        name = value;
+                    } else if let Some(value) = line.strip_prefix("data: ") {
+                        data = serde_json::from_str(value).unwrap();
+                    }
+                }
+                if !name.is_empty() {
+                    return (name.to_string(), data);
+                }
+                continue;
+            }
+            let chunk = body.next().await.unwrap().unwrap();
+            buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_live_positions_sse() {
+        let state = Arc::new(RwLock::new(AppState::new()));
+        let response = live_handler(State(Arc::clone(&state)))
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/event-stream");
+        let mut body = response.into_body().into_data_stream();
+        let mut buffer = String::new();
+
+        let (name, opened) = next_event(&mut body, &mut buffer).await;
+        assert_eq!(name, "session");
+        let session_id = opened["session_id"].as_str().unwrap().to_string();
+        assert!(state.read().await.live_sessions.contains_key(&session_id));
+
+        let satellite = |satellite_id: &str, tle_line2: &str| LiveSatellite {
+            satellite_id: satellite_id.to_string(),
+            tle_line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008".to_string(),
+            tle_line2: tle_line2.to_string(),
+            omm: None,
+            norad_id: None,
+        };
+        let iss_line2 = "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096";
+        let subscribe = |add: Vec<LiveSatellite>, remove: Vec<String>| {
+            live_subscription_handler(
+                State(Arc::clone(&state)),
+                Path(session_id.clone()),
+                Json(LiveSubscriptionRequest {
+                    add,
+                    remove,
+                    interval_ms: Some(10),
+                    output_frame: Some(ReferenceFrame::Itrf),
+                }),
+            )
+        };
+
+        // Invalid orbits are refused once, when they are added
+        let Json(changed) = subscribe(
+            vec![satellite("ISS", iss_line2), satellite("BROKEN", "2 25544  51.6400")],
+            Vec::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(changed.satellite_count, 1);
+        assert_eq!(changed.interval_ms, 100);
+        assert_eq!(changed.rejected.len(), 1);
+        assert_eq!(changed.rejected[0].satellite_id, "BROKEN");
+        assert_eq!(changed.rejected[0].error_code, "TLE_PARSE_ERROR");
+
+        let update = next_event(&mut body, &mut buffer);
+        let (name, update) = tokio::time::timeout(Duration::from_secs(10), update)
+            .await
+            .unwrap();
+        assert_eq!(name, "positions");
+        let positions = update["positions"].as_array().unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0]["satellite_id"], "ISS");
+        assert_eq!(positions[0]["frame"], "ITRF");
+
+        let Json(changed) = subscribe(
+            vec![satellite("ISS-2", iss_line2)],
+            vec!["ISS".to_string()],
+        )
+        .await
+        .unwrap();
+        assert_eq!(changed.satellite_count, 1);
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let (_, update) = next_event(&mut body, &mut buffer).await;
+                if update["positions"][0]["satellite_id"] == "ISS-2" {
+                    assert_eq!(update["positions"].as_array().unwrap().len(), 1);
+                    break;
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        // Disconnecting ends the session
+        drop(body);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while state.read().await.live_sessions.contains_key(&session_id) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        let missing = subscribe(Vec::new(), Vec::new()).await.unwrap_err();
+        assert_eq!((missing.status, missing.code), (404, "SESSION_NOT_FOUND"));
+    }
+
+    #[tokio::test]
+    async fn test_live_session_limits() {
+        let state = Arc::new(RwLock::new(AppState::new()));
+        let _stream = live_handler(State(Arc::clone(&state))).await.unwrap();
+        let (session_id, session) = {
+            let app_state = state.read().await;
+            let (session_id, session) = app_state.live_sessions.iter().next().unwrap();
+            (session_id.clone(), Arc::clone(session))
+        };
+
+        // Oversized additions are refused as a whole, before any orbit is parsed
+        let add = (0..=live::MAX_SATELLITES)
+            .map(|index| LiveSatellite {
+                satellite_id: format!("SAT-{}", index),
+                tle_line1: String::new(),
+                tle_line2: String::new(),
+                omm: None,
+                norad_id: None,
+            })
+            .collect();
+        let rejected = live_subscription_handler(
+            State(Arc::clone(&state)),
+            Path(session_id),
+            Json(LiveSubscriptionRequest {
+                add,
+                remove: Vec::new(),
+                interval_ms: None,
+                output_frame: None,
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!((rejected.status, rejected.code), (400, "INVALID_ARGUMENT"));
+        assert_eq!(session.len(), 0);
+
+        // No more streams open once the limit is reached
+        {
+            let mut app_state = state.write().await;
+            for index in 1..live::MAX_SESSIONS {
+                app_state
+                    .live_sessions
+                    .insert(format!("filler-{}", index), Arc::clone(&session));
+            }
+        }
+        let Err(rejected) = live_handler(State(Arc::clone(&state))).await else {
+            panic!("live stream opened beyond the session limit");
+        };
+        assert_eq!((rejected.status, rejected.code), (503, "UNAVAILABLE"));
+    }
+
+    #[test]
+    fn test_problem_details() {
+        let problem = Problem::from(PropagationError::TleParseError("bad checksum".to_string()));
+        assert_eq!(problem.status, 400);
+        assert_eq!(problem.code, "TLE_PARSE_ERROR");
+        assert_eq!(problem.problem_type, "https://docs.stellarops.io/errors/tle-parse-error");
+        let response = problem.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/problem+json"
+        );
+
+        // Orbits that cannot reach the requested time are client errors too;
+        // only internal failures are 5xx
+        let decayed = Problem::from(PropagationError::Decayed(propagator::Decay {
+            last_valid_unix: 1704196799.5,
+            decay_time_unix: 1704196800.0,
+        }));
+        assert_eq!((decayed.status, decayed.code), (422, "SATELLITE_DECAYED"));
+        let decay = decayed.decay.as_ref().unwrap();
+        assert_eq!(decay.decay_timestamp, "2024-01-02T12:00:00.000000Z");
+        let stale = Problem::from(PropagationError::TimeOutOfValidity("too old".to_string()));
+        assert_eq!((stale.status, stale.code), (422, "TIME_OUT_OF_VALIDITY"));
+        let busy = Problem::from(PropagationError::Unavailable(
+            "too many streams".to_string(),
+        ));
+        assert_eq!((busy.status, busy.code), (503, "UNAVAILABLE"));
+        let internal = Problem::from(PropagationError::Internal("worker panicked".to_string()));
+        assert_eq!((internal.status, internal.code), (500, "INTERNAL"));
+        assert!(!ErrorCode::Internal.title().is_empty());
     }
 }
 
@@ -268,21 +1018,970 @@ mod http_integration_tests {
 #[cfg(test)]
 mod grpc_integration_tests {
     // gRPC integration tests would require setting up a test server
-    // These are placeholders for the structure
+    // These are placeholders for the structure, except where the service
+    // implementation can be driven directly
+    use std::sync::Arc;
+
+    use tokio::sync::RwLock;
+    use tonic::Request;
+
+    use super::super::generated::orbital::{
+        eclipse_request, live_feed_request, live_feed_satellite,
+        orbital_service_server::OrbitalService, propagate_request, trajectory_request,
+        visibility_request, BatchPropagateRequest, DeleteSatelliteRequest, EclipseRequest,
+        GetSatelliteRequest, GroundStation, ListSatellitesRequest, LiveFeedRequest,
+        LiveFeedSatellite, LiveFeedSettings, Omm, PassSampling, PropagateRequest, ReferenceFrame,
+        ShadowKind, TimeScale, Tle, TrajectoryChunk, TrajectoryRequest, Twilight,
+        UpsertSatelliteRequest, VisibilityMode, VisibilityRequest,
+    };
+    use super::super::service::{self, OrbitalServiceImpl};
+    use super::super::AppState;
+
+    const ISS_TLE_LINE1: &str = "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008";
+    const ISS_TLE_LINE2: &str = "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096";
+
+    fn test_service() -> OrbitalServiceImpl {
+        OrbitalServiceImpl::new(Arc::new(RwLock::new(AppState::new())))
+    }
+
+    fn iss_tle() -> Tle {
+        Tle {
+            line1: ISS_TLE_LINE1.to_string(),
+            line2: ISS_TLE_LINE2.to_string(),
+            norad_id: String::new(),
+        }
+    }
+
+    /// ISS elements with an extreme drag term (B* = 0.5) that decay within days
+    fn decaying_tle() -> Tle {
+        Tle {
+            line1: "1 25544U 98067A   24001.50000000  .00016717  00000+0  50000-0 0  9000"
+                .to_string(),
+            ..iss_tle()
+        }
+    }
+
+    fn nyc_station(min_elevation_deg: f64) -> GroundStation {
+        GroundStation {
+            id: "GS1".to_string(),
+            name: "Test Station".to_string(),
+            latitude_deg: 40.7128,
+            longitude_deg: -74.0060,
+            altitude_m: 10.0,
+            min_elevation_deg,
+            horizon_mask: vec![],
+        }
+    }
     
     #[tokio::test]
     async fn test_grpc_propagate_position() {
-        // Would test gRPC PropagatePosition method
-        // Requires gRPC client setup
+        let service = test_service();
+        let propagate = |output_frame: ReferenceFrame| {
+            service.propagate_position(Request::new(PropagateRequest {
+                orbit: Some(propagate_request::Orbit::Tle(iss_tle())),
+                timestamp_unix: 1704067200,
+                satellite_id: "ISS".to_string(),
+                output_frame: output_frame.into(),
+                ..Default::default()
+            }))
+        };
+
+        let teme = propagate(ReferenceFrame::Unspecified).await.unwrap().into_inner();
+        let itrf = propagate(ReferenceFrame::Itrf).await.unwrap().into_inner();
+        assert!(teme.success && itrf.success);
+        assert_eq!(teme.frame, ReferenceFrame::Teme as i32);
+        assert_eq!(itrf.frame, ReferenceFrame::Itrf as i32);
+
+        // No EOP table is loaded: only the Earth-fixed frame falls back
+        let j2000 = propagate(ReferenceFrame::J2000).await.unwrap().into_inner();
+        assert!(!teme.eop_fallback && !j2000.eop_fallback);
+        assert!(itrf.eop_fallback);
+
+        let teme_position = teme.position.unwrap();
+        let itrf_position = itrf.position.unwrap();
+        let magnitude = |x: f64, y: f64, z: f64| (x * x + y * y + z * z).sqrt();
+        assert!(
+            (magnitude(teme_position.x_km, teme_position.y_km, teme_position.z_km)
+                - magnitude(itrf_position.x_km, itrf_position.y_km, itrf_position.z_km))
+            .abs()
+                < 1e-6
+        );
+
+        // Earth-fixed position agrees with the reported longitude
+        let longitude_deg = itrf_position.y_km.atan2(itrf_position.x_km).to_degrees();
+        assert!((longitude_deg - itrf.geodetic.unwrap().longitude_deg).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_grpc_propagate_time_scales() {
+        let service = test_service();
+        let propagate = |timestamp_unix: i64, time_scale: TimeScale| {
+            service.propagate_position(Request::new(PropagateRequest {
+                orbit: Some(propagate_request::Orbit::Tle(iss_tle())),
+                timestamp_unix,
+                satellite_id: "ISS".to_string(),
+                time_scale: time_scale.into(),
+                ..Default::default()
+            }))
+        };
+
+        // GPS time runs 18 s ahead of UTC since 2017
+        let utc = propagate(1704067200, TimeScale::Utc).await.unwrap().into_inner();
+        let gps = propagate(1704067218, TimeScale::Gps).await.unwrap().into_inner();
+        assert_eq!(gps.time_scale, TimeScale::Gps as i32);
+        assert_eq!(gps.timestamp_unix, 1704067218);
+
+        let (utc_position, gps_position) = (utc.position.unwrap(), gps.position.unwrap());
+        assert!((utc_position.x_km - gps_position.x_km).abs() < 1e-9);
+        assert!((utc_position.y_km - gps_position.y_km).abs() < 1e-9);
+        assert!((utc_position.z_km - gps_position.z_km).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_grpc_propagate_omm() {
+        let service = test_service();
+        let propagate = |orbit: propagate_request::Orbit| {
+            service.propagate_position(Request::new(PropagateRequest {
+                orbit: Some(orbit),
+                timestamp_unix: 1704070800,
+                satellite_id: "ISS".to_string(),
+                ..Default::default()
+            }))
+        };
+
+        let omm = Omm {
+            content: "\
+CCSDS_OMM_VERS = 2.0
+OBJECT_NAME = ISS (ZARYA)
+OBJECT_ID = 1998-067A
+MEAN_ELEMENT_THEORY = SGP4
+EPOCH = 2024-001T12:00:00.000
+MEAN_MOTION = 15.50377579
+ECCENTRICITY = 0.0006703
+INCLINATION = 51.6400
+RA_OF_ASC_NODE = 208.9163
+ARG_OF_PERICENTER = 130.5360
+MEAN_ANOMALY = 325.0288
+NORAD_CAT_ID = 25544
+BSTAR = 0.0001027
+MEAN_MOTION_DOT = 0.00016717
+"
+            .to_string(),
+        };
+
+        let from_tle = propagate(propagate_request::Orbit::Tle(iss_tle())).await.unwrap().into_inner();
+        let from_omm = propagate(propagate_request::Orbit::Omm(omm)).await.unwrap().into_inner();
+        assert!(from_omm.success, "error: {}", from_omm.error_message);
+
+        let (tle_position, omm_position) = (from_tle.position.unwrap(), from_omm.position.unwrap());
+        assert!((tle_position.x_km - omm_position.x_km).abs() < 1e-6);
+        assert!((tle_position.y_km - omm_position.y_km).abs() < 1e-6);
+        assert!((tle_position.z_km - omm_position.z_km).abs() < 1e-6);
+
+        // An orbit is required
+        let error = service
+            .propagate_position(Request::new(PropagateRequest::default()))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_grpc_norad_id_cross_check() {
+        let service = test_service();
+        let propagate = |tle: Tle| {
+            service.propagate_position(Request::new(PropagateRequest {
+                orbit: Some(propagate_request::Orbit::Tle(tle)),
+                timestamp_unix: 1704067200,
+                ..Default::default()
+            }))
+        };
+
+        let response = propagate(iss_tle()).await.unwrap().into_inner();
+        assert_eq!(response.norad_id, 25544);
+
+        // Alpha-5 catalog numbers decode to six digits
+        let alpha5 = Tle {
+            line1: "1 T5544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9006"
+                .to_string(),
+            line2: "2 T5544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423094"
+                .to_string(),
+            norad_id: "275544".to_string(),
+        };
+        let response = propagate(alpha5).await.unwrap().into_inner();
+        assert!(response.success, "error: {}", response.error_message);
+        assert_eq!(response.norad_id, 275544);
+
+        let mismatch = Tle {
+            norad_id: "25545".to_string(),
+            ..iss_tle()
+        };
+        let error = propagate(mismatch).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+        assert!(error.message().contains("25545"));
+    }
+
+    #[tokio::test]
+    async fn test_grpc_satellite_catalog() {
+        let service = test_service();
+        let upsert = |satellite_id: &str, tle: Tle| {
+            service.upsert_satellite(Request::new(UpsertSatelliteRequest {
+                satellite_id: satellite_id.to_string(),
+                name: "ISS (ZARYA)".to_string(),
+                tle: Some(tle),
+            }))
+        };
+
+        let registered = upsert("ISS", iss_tle()).await.unwrap().into_inner();
+        assert_eq!(registered.norad_id, 25544);
+        assert_eq!(registered.epoch.unwrap().seconds, 1704110400);
+
+        let bad_checksum = Tle {
+            line1: ISS_TLE_LINE1.replace("9008", "9000"),
+            ..iss_tle()
+        };
+        let error = upsert("BROKEN", bad_checksum).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+
+        // Requests without an orbit use the registered TLE
+        let by_id = service
+            .propagate_position(Request::new(PropagateRequest {
+                satellite_id: "ISS".to_string(),
+                timestamp_unix: 1704067200,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let inline = service
+            .propagate_position(Request::new(PropagateRequest {
+                orbit: Some(propagate_request::Orbit::Tle(iss_tle())),
+                timestamp_unix: 1704067200,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(by_id.norad_id, 25544);
+        assert_eq!(by_id.position, inline.position);
+        assert_eq!(by_id.element_epoch.unwrap().seconds, 1704110400);
+        assert!(inline.element_epoch.is_none());
+
+        let trajectory = service
+            .propagate_trajectory(Request::new(TrajectoryRequest {
+                satellite_id: "ISS".to_string(),
+                start_timestamp_unix: 1704067200,
+                end_timestamp_unix: 1704067200 + 600,
+                step_seconds: 60,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(trajectory.points.len(), 11);
+        assert!(trajectory.element_epoch.is_some());
+
+        let history = service
+            .get_satellite_history(Request::new(GetSatelliteRequest {
+                satellite_id: "ISS".to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(history.element_sets.len(), 1);
+
+        let listed = service
+            .list_satellites(Request::new(ListSatellitesRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(listed.satellites.len(), 1);
+        assert_eq!(listed.satellites[0].name, "ISS (ZARYA)");
+
+        let deleted = service
+            .delete_satellite(Request::new(DeleteSatelliteRequest {
+                satellite_id: "ISS".to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(deleted.satellite_id, "ISS");
+
+        let missing = service
+            .get_satellite(Request::new(GetSatelliteRequest {
+                satellite_id: "ISS".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(missing.code(), tonic::Code::NotFound);
+        assert_eq!(
+            missing.metadata().get("error-code").unwrap(),
+            "SATELLITE_NOT_FOUND"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_grpc_propagate_batch() {
+        let service = test_service();
+        let item = |orbit: Option<propagate_request::Orbit>, timestamp_unix: i64| PropagateRequest {
+            orbit,
+            timestamp_unix,
+            satellite_id: format!("SAT-{}", timestamp_unix),
+            ..Default::default()
+        };
+        let iss = || Some(propagate_request::Orbit::Tle(iss_tle()));
+        let bad_checksum = Some(propagate_request::Orbit::Tle(Tle {
+            line1: ISS_TLE_LINE1.replace("9008", "9000"),
+            ..iss_tle()
+        }));
+
+        let response = service
+            .propagate_batch(Request::new(BatchPropagateRequest {
+                requests: vec![
+                    item(iss(), 1704067200),
+                    item(bad_checksum, 1704067260),
+                    item(None, 1704067320),
+                    item(iss(), 0),
+                ],
+                epoch: Some(prost_types::Timestamp {
+                    seconds: 1704067200,
+                    nanos: 0,
+                }),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(response.total_count, 4);
+        assert_eq!(response.success_count, 2);
+        assert_eq!(response.error_count, 2);
+
+        // Results keep request order
+        let results = &response.results;
+        assert_eq!(results[0].satellite_id, "SAT-1704067200");
+        assert_eq!(results[1].satellite_id, "SAT-1704067260");
+        assert!(results[0].success && results[0].error_code.is_empty());
+        assert!(!results[1].success);
+        assert_eq!(results[1].error_code, "TLE_PARSE_ERROR");
+        assert!(results[1].error_message.contains("checksum"));
+        // Without an orbit, the satellite_id must be registered
+        assert!(!results[2].success);
+        assert_eq!(results[2].error_code, "SATELLITE_NOT_FOUND");
+
+        // An item without a time of its own uses the common epoch
+        assert!(results[3].success);
+        assert_eq!(results[3].timestamp_unix, 1704067200);
+        assert_eq!(results[3].position, results[0].position);
     }
 
     #[tokio::test]
     async fn test_grpc_propagate_trajectory() {
-        // Would test gRPC PropagateTrajectory method
+        let start = prost_types::Timestamp {
+            seconds: 1704067200,
+            nanos: 500_000_000,
+        };
+        let response = test_service()
+            .propagate_trajectory(Request::new(TrajectoryRequest {
+                orbit: Some(trajectory_request::Orbit::Tle(iss_tle())),
+                start_time: Some(start),
+                end_time: Some(prost_types::Timestamp {
+                    seconds: 1704067201,
+                    nanos: 500_000_000,
+                }),
+                step: Some(prost_types::Duration {
+                    seconds: 0,
+                    nanos: 100_000_000,
+                }),
+                satellite_id: "ISS".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert!(response.success);
+        assert_eq!(response.points.len(), 11);
+
+        let last = response.points.last().unwrap();
+        assert_eq!(last.timestamp_unix, 1704067201);
+        assert_eq!(
+            last.timestamp,
+            Some(prost_types::Timestamp {
+                seconds: 1704067201,
+                nanos: 500_000_000,
+            })
+        );
+
+        // A zero step is rejected when no Duration is given
+        let error = test_service()
+            .propagate_trajectory(Request::new(TrajectoryRequest {
+                orbit: Some(trajectory_request::Orbit::Tle(iss_tle())),
+                start_time: Some(start),
+                end_time: Some(start),
+                step_seconds: 0,
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+
+        // A one-nanosecond step over centuries is refused, not overflowed
+        let error = test_service()
+            .propagate_trajectory(Request::new(TrajectoryRequest {
+                orbit: Some(trajectory_request::Orbit::Tle(iss_tle())),
+                start_timestamp_unix: 0,
+                end_timestamp_unix: 100_000_000_000,
+                step: Some(prost_types::Duration { seconds: 0, nanos: 1 }),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_grpc_trajectory_velocity() {
+        let service = test_service();
+        let trajectory = service
+            .propagate_trajectory(Request::new(TrajectoryRequest {
+                orbit: Some(trajectory_request::Orbit::Tle(iss_tle())),
+                start_timestamp_unix: 1704067200,
+                end_timestamp_unix: 1704067201,
+                step_seconds: 1,
+                output_frame: ReferenceFrame::Itrf.into(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let single = service
+            .propagate_position(Request::new(PropagateRequest {
+                orbit: Some(propagate_request::Orbit::Tle(iss_tle())),
+                timestamp_unix: 1704067200,
+                output_frame: ReferenceFrame::Itrf.into(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        // Points carry the same state as a single propagation
+        let first = &trajectory.points[0];
+        assert_eq!(first.position, single.position);
+        assert_eq!(first.velocity, single.velocity);
+
+        // and agree with the finite difference of consecutive positions
+        let velocity = first.velocity.unwrap();
+        let (p0, p1) = (
+            first.position.unwrap(),
+            trajectory.points[1].position.unwrap(),
+        );
+        assert!((p1.x_km - p0.x_km - velocity.vx_km_s).abs() < 0.01);
+        assert!((p1.y_km - p0.y_km - velocity.vy_km_s).abs() < 0.01);
+        assert!((p1.z_km - p0.z_km - velocity.vz_km_s).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_grpc_stream_trajectory() {
+        use tokio_stream::StreamExt;
+
+        let service = &test_service();
+        let stream = |tle: Tle, end_timestamp_unix: i64, step_seconds: i64| {
+            let request = TrajectoryRequest {
+                orbit: Some(trajectory_request::Orbit::Tle(tle)),
+                start_timestamp_unix: 1704067200,
+                end_timestamp_unix,
+                step_seconds,
+                satellite_id: "ISS".to_string(),
+                ..Default::default()
+            };
+            async move {
+                let response = service.stream_trajectory(Request::new(request)).await.unwrap();
+                let chunks: Vec<TrajectoryChunk> = response
+                    .into_inner()
+                    .map(Result::unwrap)
+                    .collect()
+                    .await;
+                chunks
+            }
+        };
+
+        // Three hours at one-second steps exceeds the PropagateTrajectory limit
+        let three_hours = 1704067200 + 3 * 3600;
+        let status = service
+            .propagate_trajectory(Request::new(TrajectoryRequest {
+                orbit: Some(trajectory_request::Orbit::Tle(iss_tle())),
+                start_timestamp_unix: 1704067200,
+                end_timestamp_unix: three_hours,
+                step_seconds: 1,
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let chunks = stream(iss_tle(), three_hours, 1).await;
+        let (last, data) = chunks.split_last().unwrap();
+        assert!(last.complete && last.points.is_empty() && !last.decayed);
+        assert!(data
+            .iter()
+            .all(|chunk| !chunk.complete && chunk.points.len() <= 1000 && chunk.norad_id == 25544));
+        let timestamps: Vec<i64> = data
+            .iter()
+            .flat_map(|chunk| chunk.points.iter().map(|point| point.timestamp_unix))
+            .collect();
+        assert_eq!(timestamps.len(), 3 * 3600 + 1);
+        assert!(timestamps.windows(2).all(|pair| pair[1] == pair[0] + 1));
+
+        // The final chunk reports a decay inside the range
+        let chunks = stream(decaying_tle(), 1704067200 + 365 * 86400, 3600).await;
+        let last = chunks.last().unwrap();
+        assert!(last.complete && last.decayed && last.decay.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_grpc_live_feed() {
+        use std::time::Duration;
+
+        use tokio::sync::mpsc;
+        use tokio_stream::wrappers::ReceiverStream;
+
+        let add = |satellite_id: &str, tle: Tle| LiveFeedRequest {
+            command: Some(live_feed_request::Command::Add(LiveFeedSatellite {
+                satellite_id: satellite_id.to_string(),
+                orbit: Some(live_feed_satellite::Orbit::Tle(tle)),
+            })),
+        };
+
+        let (commands, command_stream) = mpsc::channel(8);
+        let (updates, mut update_stream) = mpsc::channel(4);
+        let state = Arc::new(RwLock::new(AppState::new()));
+        let feed = tokio::spawn(service::run_live_feed(
+            ReceiverStream::new(command_stream),
+            updates,
+            state,
+        ));
+
+        commands.send(Ok(add("ISS", iss_tle()))).await.unwrap();
+        let broken = Tle {
+            line2: "2 25544  51.6400".to_string(),
+            ..iss_tle()
+        };
+        commands.send(Ok(add("BROKEN", broken))).await.unwrap();
+        commands
+            .send(Ok(LiveFeedRequest {
+                command: Some(live_feed_request::Command::Settings(LiveFeedSettings {
+                    interval: Some(prost_types::Duration {
+                        seconds: 0,
+                        nanos: 100_000_000,
+                    }),
+                    output_frame: ReferenceFrame::Itrf.into(),
+                })),
+            }))
+            .await
+            .unwrap();
+
+        // Updates arrive until both the subscription and the refusal are seen
+        let mut seen_iss = false;
+        let mut seen_rejected = false;
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !(seen_iss && seen_rejected) {
+                let update = update_stream.recv().await.unwrap().unwrap();
+                assert!(update.timestamp.is_some());
+                if let Some(position) = update.positions.first() {
+                    assert_eq!(position.satellite_id, "ISS");
+                    seen_iss = true;
+                    if position.success {
+                        assert_eq!(position.frame(), ReferenceFrame::Itrf);
+                        assert_eq!(position.norad_id, 25544);
+                    }
+                }
+                for rejected in &update.rejected {
+                    assert_eq!(rejected.satellite_id, "BROKEN");
+                    assert!(!rejected.success && !rejected.error_code.is_empty());
+                    seen_rejected = true;
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        commands.send(Ok(add("ISS-2", iss_tle()))).await.unwrap();
+        commands
+            .send(Ok(LiveFeedRequest {
+                command: Some(live_feed_request::Command::Remove("ISS".to_string())),
+            }))
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let update = update_stream.recv().await.unwrap().unwrap();
+                let ids: Vec<&str> = update
+                    .positions
+                    .iter()
+                    .map(|position| position.satellite_id.as_str())
+                    .collect();
+                if ids == ["ISS-2"] {
+                    break;
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        // Closing the command stream keeps the feed running
+        drop(commands);
+        let update = update_stream.recv().await.unwrap().unwrap();
+        assert_eq!(update.positions.len(), 1);
+
+        // The feed ends once the client stops reading
+        drop(update_stream);
+        tokio::time::timeout(Duration::from_secs(5), feed)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_grpc_decayed_orbit() {
+        let epoch = 1704110400; // 2024-01-01 12:00:00 UTC
+        let year_later = epoch + 365 * 86400;
+
+        // Trajectories stop at the decay and flag it
+        let response = test_service()
+            .propagate_trajectory(Request::new(TrajectoryRequest {
+                orbit: Some(trajectory_request::Orbit::Tle(decaying_tle())),
+                start_timestamp_unix: epoch,
+                end_timestamp_unix: year_later,
+                step_seconds: 86400,
+                satellite_id: "DECAYING".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.success && response.decayed);
+        let decay = response.decay.unwrap();
+        let decay_time = decay.decay_time.unwrap().seconds;
+        assert!(decay_time > epoch && decay_time < year_later);
+        assert!(response.points.last().unwrap().timestamp_unix <= decay_time);
+
+        // Single propagations past the decay fail with the decay details
+        let status = test_service()
+            .propagate_position(Request::new(PropagateRequest {
+                orbit: Some(propagate_request::Orbit::Tle(decaying_tle())),
+                timestamp_unix: year_later,
+                satellite_id: "DECAYING".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+        assert_eq!(status.metadata().get("error-code").unwrap(), "SATELLITE_DECAYED");
+        assert!(status.metadata().get("decay-time").is_some());
     }
 
     #[tokio::test]
     async fn test_grpc_calculate_visibility() {
-        // Would test gRPC CalculateVisibility method
+        let start = 1704067200;
+        let response = test_service()
+            .calculate_visibility(Request::new(VisibilityRequest {
+                orbit: Some(visibility_request::Orbit::Tle(iss_tle())),
+                ground_station: Some(nyc_station(10.0)),
+                start_timestamp_unix: start,
+                end_timestamp_unix: start + 86400,
+                satellite_id: "ISS".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .expect("CalculateVisibility should not fail")
+            .into_inner();
+
+        assert!(response.success, "error: {}", response.error_message);
+        assert_eq!(response.ground_station_id, "GS1");
+        assert!(!response.passes.is_empty(), "ISS should pass over NYC within a day");
+
+        for pass in &response.passes {
+            assert!(pass.los_timestamp > pass.aos_timestamp);
+            assert!(pass.max_elevation_timestamp >= pass.aos_timestamp);
+            assert!(pass.max_elevation_timestamp <= pass.los_timestamp);
+            assert!(pass.max_elevation_deg >= 10.0 && pass.max_elevation_deg <= 90.0);
+            assert!((0.0..360.0).contains(&pass.aos_azimuth_deg));
+            assert!((0.0..360.0).contains(&pass.los_azimuth_deg));
+            assert_eq!(pass.duration_seconds, pass.los_timestamp - pass.aos_timestamp);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_grpc_calculate_visibility_for_registered_station() {
+        let state = Arc::new(RwLock::new(AppState::new()));
+        state
+            .read()
+            .await
+            .catalog
+            .upsert_ground_station(super::super::propagator::GroundStation {
+                id: "NYC".to_string(),
+                name: "New York".to_string(),
+                latitude_deg: 40.7128,
+                longitude_deg: -74.0060,
+                altitude_m: 10.0,
+                min_elevation_deg: 10.0,
+                horizon_mask: Vec::new(),
+            })
+            .unwrap();
+        let service = OrbitalServiceImpl::new(state);
+        let start = 1704067200;
+        let request = |ground_station_id: &str| VisibilityRequest {
+            orbit: Some(visibility_request::Orbit::Tle(iss_tle())),
+            ground_station_id: ground_station_id.to_string(),
+            start_timestamp_unix: start,
+            end_timestamp_unix: start + 86400,
+            satellite_id: "ISS".to_string(),
+            ..Default::default()
+        };
+
+        let response = service
+            .calculate_visibility(Request::new(request("NYC")))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.ground_station_id, "NYC");
+        assert!(
+            !response.passes.is_empty(),
+            "ISS should pass over NYC within a day"
+        );
+
+        let status = service
+            .calculate_visibility(Request::new(request("NOWHERE")))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+        let status = service
+            .calculate_visibility(Request::new(request("")))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_grpc_calculate_visibility_honours_min_elevation() {
+        let start = 1704067200;
+        let count_passes = |min_elevation_deg: f64| async move {
+            test_service()
+                .calculate_visibility(Request::new(VisibilityRequest {
+                    orbit: Some(visibility_request::Orbit::Tle(iss_tle())),
+                    ground_station: Some(nyc_station(min_elevation_deg)),
+                    start_timestamp_unix: start,
+                    end_timestamp_unix: start + 86400,
+                    satellite_id: "ISS".to_string(),
+                    ..Default::default()
+                }))
+                .await
+                .unwrap()
+                .into_inner()
+                .passes
+                .len()
+        };
+
+        assert!(count_passes(0.0).await >= count_passes(30.0).await);
+    }
+
+    #[tokio::test]
+    async fn test_grpc_calculate_visibility_pass_sampling() {
+        let start = 1704067200;
+        let response = test_service()
+            .calculate_visibility(Request::new(VisibilityRequest {
+                orbit: Some(visibility_request::Orbit::Tle(iss_tle())),
+                ground_station: Some(nyc_station(10.0)),
+                start_timestamp_unix: start,
+                end_timestamp_unix: start + 86400,
+                satellite_id: "ISS".to_string(),
+                pass_sampling: Some(PassSampling {
+                    step_seconds: 5.0,
+                    downlink_frequency_hz: 2.2e9,
+                    uplink_frequency_hz: 2.0e9,
+                }),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert!(response.success, "error: {}", response.error_message);
+        for pass in &response.passes {
+            assert!(pass.samples.len() >= 2);
+            for sample in &pass.samples {
+                assert!(sample.range_km > 300.0);
+                // Downlink shift scales with the higher carrier
+                assert!(sample.downlink_doppler_hz.abs() >= sample.uplink_doppler_hz.abs());
+            }
+        }
+
+        let invalid = test_service()
+            .calculate_visibility(Request::new(VisibilityRequest {
+                orbit: Some(visibility_request::Orbit::Tle(iss_tle())),
+                ground_station: Some(nyc_station(10.0)),
+                start_timestamp_unix: start,
+                end_timestamp_unix: start + 86400,
+                satellite_id: "ISS".to_string(),
+                pass_sampling: Some(PassSampling {
+                    step_seconds: -1.0,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }))
+            .await;
+        assert_eq!(invalid.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+        // An unset step samples every 10 s, as over HTTP
+        let response = test_service()
+            .calculate_visibility(Request::new(VisibilityRequest {
+                orbit: Some(visibility_request::Orbit::Tle(iss_tle())),
+                ground_station: Some(nyc_station(10.0)),
+                start_timestamp_unix: start,
+                end_timestamp_unix: start + 86400,
+                satellite_id: "ISS".to_string(),
+                pass_sampling: Some(PassSampling::default()),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(!response.passes.is_empty());
+        for pass in &response.passes {
+            let steps: Vec<f64> = pass
+                .samples
+                .windows(2)
+                .map(|pair| pair[1].timestamp_unix - pair[0].timestamp_unix)
+                .collect();
+            assert!(!steps.is_empty());
+            // Every step is 10 s except a shorter final one ending at LOS
+            assert!(steps[..steps.len() - 1].iter().all(|step| (step - 10.0).abs() < 1e-6));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_grpc_calculate_visibility_optical_mode() {
+        let start = 1704067200;
+        let request =
+            |ground_station: GroundStation, visibility_mode: VisibilityMode| VisibilityRequest {
+                orbit: Some(visibility_request::Orbit::Tle(iss_tle())),
+                ground_station: Some(ground_station),
+                start_timestamp_unix: start,
+                end_timestamp_unix: start + 3 * 86400,
+                satellite_id: "ISS".to_string(),
+                visibility_mode: visibility_mode as i32,
+                twilight: Twilight::Nautical as i32,
+                standard_magnitude: Some(-1.8),
+                ..Default::default()
+            };
+        let passes = |request: VisibilityRequest| async move {
+            let response = test_service()
+                .calculate_visibility(Request::new(request))
+                .await
+                .unwrap()
+                .into_inner();
+            assert!(response.success, "error: {}", response.error_message);
+            response.passes
+        };
+
+        // The ISS crosses 50 deg South around local midnight in early
+        // January, sunlit against a dark sky
+        let south = GroundStation {
+            latitude_deg: -50.0,
+            longitude_deg: -70.0,
+            ..nyc_station(10.0)
+        };
+        let optical = passes(request(south, VisibilityMode::Optical)).await;
+        assert!(!optical.is_empty());
+        for pass in &optical {
+            let start = pass.sunlit_start_time_unix.expect("optical passes are sunlit");
+            let end = pass.sunlit_end_time_unix.expect("optical passes are sunlit");
+            assert!(start >= pass.aos_time_unix && end <= pass.los_time_unix);
+            assert!(pass.visual_magnitude.is_some());
+        }
+
+        // Over New York its passes come in daylight; the morning ones are
+        // dropped
+        let radio = passes(request(nyc_station(10.0), VisibilityMode::Radio)).await;
+        let optical = passes(request(nyc_station(10.0), VisibilityMode::Optical)).await;
+        assert!(optical.len() < radio.len());
+    }
+
+    #[tokio::test]
+    async fn test_grpc_calculate_eclipses() {
+        let start = 1704067200;
+        let response = test_service()
+            .calculate_eclipses(Request::new(EclipseRequest {
+                orbit: Some(eclipse_request::Orbit::Tle(iss_tle())),
+                start_timestamp_unix: start,
+                end_timestamp_unix: start + 2 * 86400,
+                satellite_id: "ISS".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert!(response.success, "error: {}", response.error_message);
+        assert!(response.eclipses.iter().any(|e| e.kind == ShadowKind::Umbra as i32));
+        for eclipse in &response.eclipses {
+            assert!(eclipse.exit_time_unix >= eclipse.entry_time_unix);
+            assert!(eclipse.duration_seconds >= 0.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_grpc_trajectory_illumination() {
+        let start = 1704067200;
+        let response = test_service()
+            .propagate_trajectory(Request::new(TrajectoryRequest {
+                orbit: Some(trajectory_request::Orbit::Tle(iss_tle())),
+                start_timestamp_unix: start,
+                end_timestamp_unix: start + 6000,
+                step_seconds: 60,
+                satellite_id: "ISS".to_string(),
+                include_illumination: true,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert!(response.success);
+        let fractions: Vec<f64> = response
+            .points
+            .iter()
+            .map(|p| p.illumination_fraction.expect("illumination requested"))
+            .collect();
+        // A full orbit covers both daylight and shadow
+        assert!(fractions.contains(&1.0));
+        assert!(fractions.contains(&0.0));
+    }
+
+    #[tokio::test]
+    async fn test_grpc_calculate_visibility_invalid_tle() {
+        let start = 1704067200;
+        let status = test_service()
+            .calculate_visibility(Request::new(VisibilityRequest {
+                orbit: Some(visibility_request::Orbit::Tle(Tle {
+                    line1: "INVALID TLE".to_string(),
+                    line2: "INVALID TLE".to_string(),
+                    norad_id: String::new(),
+                })),
+                ground_station: Some(nyc_station(10.0)),
+                start_timestamp_unix: start,
+                end_timestamp_unix: start + 3600,
+                satellite_id: "BAD".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+
+        // Bad elements are a client error, with the code in the metadata
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert!(status.message().contains("line 1, column 12"), "{}", status.message());
+        assert_eq!(status.metadata().get("error-code").unwrap(), "TLE_PARSE_ERROR");
     }
 }
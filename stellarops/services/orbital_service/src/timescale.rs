@@ -0,0 +1,286 @@
+//! Time scales (UTC, TAI, TT, GPS, UT1) and leap seconds
+//!
+//! Timestamps in every scale are expressed as seconds since
+//! 1970-01-01T00:00:00 on that scale's clock, so a UTC timestamp is ordinary
+//! Unix time. A leap second (23:59:60) has no Unix representation and maps onto
+//! the first second of the following day.
+
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use lazy_static::lazy_static;
+use tracing::info;
+
+use crate::eop;
+
+/// TT - TAI in seconds
+const TT_MINUS_TAI_SECONDS: f64 = 32.184;
+
+/// TAI - GPS in seconds (fixed at the GPS epoch, 1980-01-06)
+const TAI_MINUS_GPS_SECONDS: f64 = 19.0;
+
+/// Seconds between the NTP epoch (1900-01-01) and the Unix epoch
+const NTP_UNIX_OFFSET_SECONDS: i64 = 2_208_988_800;
+
+/// Bundled TAI - UTC history: (UTC Unix time the offset takes effect, offset)
+const BUNDLED_LEAP_SECONDS: [(i64, f64); 28] = [
+    (63072000, 10.0),   // 1972-01-01
+    (78796800, 11.0),   // 1972-07-01
+    (94694400, 12.0),   // 1973-01-01
+    (126230400, 13.0),  // 1974-01-01
+    (157766400, 14.0),  // 1975-01-01
+    (189302400, 15.0),  // 1976-01-01
+    (220924800, 16.0),  // 1977-01-01
+    (252460800, 17.0),  // 1978-01-01
+    (283996800, 18.0),  // 1979-01-01
+    (315532800, 19.0),  // 1980-01-01
+    (362793600, 20.0),  // 1981-07-01
+    (394329600, 21.0),  // 1982-07-01
+    (425865600, 22.0),  // 1983-07-01
+    (489024000, 23.0),  // 1985-07-01
+    (567993600, 24.0),  // 1988-01-01
+    (631152000, 25.0),  // 1990-01-01
+    (662688000, 26.0),  // 1991-01-01
+    (709948800, 27.0),  // 1992-07-01
+    (741484800, 28.0),  // 1993-07-01
+    (773020800, 29.0),  // 1994-07-01
+    (820454400, 30.0),  // 1996-01-01
+    (867715200, 31.0),  // 1997-07-01
+    (915148800, 32.0),  // 1999-01-01
+    (1136073600, 33.0), // 2006-01-01
+    (1230768000, 34.0), // 2009-01-01
+    (1341100800, 35.0), // 2012-07-01
+    (1435708800, 36.0), // 2015-07-01
+    (1483228800, 37.0), // 2017-01-01
+];
+
+lazy_static! {
+    static ref LEAP_SECONDS: RwLock<Arc<LeapSecondTable>> =
+        RwLock::new(Arc::new(LeapSecondTable::bundled()));
+}
+
+/// Supported time scales
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeScale {
+    #[default]
+    Utc,
+    Tai,
+    Tt,
+    Gps,
+    Ut1,
+}
+
+/// TAI - UTC steps, sorted by UTC time
+#[derive(Debug, Clone)]
+pub struct LeapSecondTable {
+    entries: Vec<(i64, f64)>,
+}
+
+impl LeapSecondTable {
+    pub fn bundled() -> Self {
+        Self {
+            entries: BUNDLED_LEAP_SECONDS.to_vec(),
+        }
+    }
+
+    /// Parse the IETF/IERS `leap-seconds.list` format
+    /// (`<NTP seconds> <TAI-UTC>` per line, `#` comments)
+    pub fn parse_list(text: &str) -> Result<Self, LeapSecondError> {
+        let mut entries: Vec<(i64, f64)> = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let data = line.split('#').next().unwrap_or("").trim();
+            if data.is_empty() {
+                continue;
+            }
+
+            let parse_error = |message: String| LeapSecondError::Parse {
+                line: index + 1,
+                message,
+            };
+
+            let mut fields = data.split_whitespace();
+            let ntp_seconds: i64 = fields
+                .next()
+                .unwrap_or_default()
+                .parse()
+                .map_err(|e| parse_error(format!("invalid NTP timestamp: {}", e)))?;
+            let offset: f64 = fields
+                .next()
+                .ok_or_else(|| parse_error("missing TAI-UTC offset".to_string()))?
+                .parse()
+                .map_err(|e| parse_error(format!("invalid TAI-UTC offset: {}", e)))?;
+
+            let utc_unix = ntp_seconds - NTP_UNIX_OFFSET_SECONDS;
+            if entries.last().is_some_and(|&(last, _)| utc_unix <= last) {
+                return Err(parse_error("entries are not in chronological order".to_string()));
+            }
+            entries.push((utc_unix, offset));
+        }
+
+        if entries.is_empty() {
+            return Err(LeapSecondError::Empty);
+        }
+
+        Ok(Self { entries })
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// TAI - UTC at a UTC instant (the 1972 offset is used for earlier times)
+    pub fn tai_minus_utc(&self, utc_unix: f64) -> f64 {
+        let index = self
+            .entries
+            .partition_point(|&(start, _)| start as f64 <= utc_unix);
+        self.entries[index.saturating_sub(1)].1
+    }
+
+    /// UTC instant for a TAI time
+    fn tai_to_utc(&self, tai_unix: f64) -> f64 {
+        let offset = self
+            .entries
+            .iter()
+            .rev()
+            .find(|&&(start, offset)| tai_unix - offset >= start as f64)
+            .or(self.entries.first())
+            .map_or(0.0, |&(_, offset)| offset);
+        tai_unix - offset
+    }
+}
+
+/// Replace the bundled leap-second table with a local `leap-seconds.list`
+pub fn load_leap_second_file(path: &Path) -> Result<usize, LeapSecondError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| LeapSecondError::Io(format!("{}: {}", path.display(), e)))?;
+    let table = LeapSecondTable::parse_list(&text)?;
+    let entries = table.entry_count();
+
+    *LEAP_SECONDS.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(table);
+
+    info!(path = %path.display(), entries, "Loaded leap second table");
+    Ok(entries)
+}
+
+fn leap_seconds() -> Arc<LeapSecondTable> {
+    LEAP_SECONDS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// TAI - UTC at a UTC instant
+pub fn tai_minus_utc(utc_unix: f64) -> f64 {
+    leap_seconds().tai_minus_utc(utc_unix)
+}
+
+/// Express a UTC instant in another time scale
+pub fn from_utc(utc_unix: f64, scale: TimeScale) -> f64 {
+    match scale {
+        TimeScale::Utc => utc_unix,
+        TimeScale::Tai => utc_unix + tai_minus_utc(utc_unix),
+        TimeScale::Tt => utc_unix + tai_minus_utc(utc_unix) + TT_MINUS_TAI_SECONDS,
+        TimeScale::Gps => utc_unix + tai_minus_utc(utc_unix) - TAI_MINUS_GPS_SECONDS,
+        TimeScale::Ut1 => utc_unix + eop::orientation_at(utc_unix).ut1_minus_utc_s,
+    }
+}
+
+/// Convert a timestamp in `scale` to UTC
+pub fn to_utc(timestamp: f64, scale: TimeScale) -> f64 {
+    match scale {
+        TimeScale::Utc => timestamp,
+        TimeScale::Tai => leap_seconds().tai_to_utc(timestamp),
+        TimeScale::Tt => leap_seconds().tai_to_utc(timestamp - TT_MINUS_TAI_SECONDS),
+        TimeScale::Gps => leap_seconds().tai_to_utc(timestamp + TAI_MINUS_GPS_SECONDS),
+        TimeScale::Ut1 => {
+            // UT1 - UTC changes by milliseconds per day; two iterations converge
+            let mut utc = timestamp;
+            for _ in 0..2 {
+                utc = timestamp - eop::orientation_at(utc).ut1_minus_utc_s;
+            }
+            utc
+        }
+    }
+}
+
+/// Leap second file errors
+#[derive(Debug, Clone)]
+pub enum LeapSecondError {
+    Io(String),
+    Parse { line: usize, message: String },
+    Empty,
+}
+
+impl std::fmt::Display for LeapSecondError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LeapSecondError::Io(msg) => write!(f, "Leap second file error: {}", msg),
+            LeapSecondError::Parse { line, message } => {
+                write!(f, "Leap second parse error on line {}: {}", line, message)
+            }
+            LeapSecondError::Empty => write!(f, "Leap second file contains no entries"),
+        }
+    }
+}
+
+impl std::error::Error for LeapSecondError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2016-12-31T23:59:59 UTC, one second before the last leap second
+    const BEFORE_LEAP: f64 = 1483228799.0;
+
+    #[test]
+    fn test_tai_minus_utc_steps() {
+        let table = LeapSecondTable::bundled();
+        assert_eq!(table.tai_minus_utc(BEFORE_LEAP), 36.0);
+        assert_eq!(table.tai_minus_utc(BEFORE_LEAP + 1.0), 37.0);
+        assert_eq!(table.tai_minus_utc(0.0), 10.0);
+    }
+
+    #[test]
+    fn test_conversions_round_trip() {
+        for utc in [BEFORE_LEAP - 0.5, BEFORE_LEAP + 1.0, 1704067200.25] {
+            for scale in [TimeScale::Tai, TimeScale::Tt, TimeScale::Gps] {
+                let converted = from_utc(utc, scale);
+                assert!((to_utc(converted, scale) - utc).abs() < 1e-9, "{:?} at {}", scale, utc);
+            }
+        }
+
+        // 2024: GPS runs 18 s ahead of UTC and TT 69.184 s ahead
+        assert_eq!(from_utc(1704067200.0, TimeScale::Gps) - 1704067200.0, 18.0);
+        assert!((from_utc(1704067200.0, TimeScale::Tt) - 1704067269.184).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_tai_across_leap_second() {
+        let table = LeapSecondTable::bundled();
+        let tai_before = BEFORE_LEAP + 36.0;
+
+        // TAI advances two seconds over one Unix second around the leap
+        assert_eq!(table.tai_to_utc(tai_before), BEFORE_LEAP);
+        assert_eq!(table.tai_to_utc(tai_before + 2.0), BEFORE_LEAP + 1.0);
+    }
+
+    #[test]
+    fn test_parse_leap_seconds_list() {
+        let text = "\
+#\tleap-seconds.list excerpt
+#@\t3991593600
+2272060800\t10\t# 1 Jan 1972
+3692217600\t37\t# 1 Jan 2017
+";
+        let table = LeapSecondTable::parse_list(text).unwrap();
+        assert_eq!(table.entry_count(), 2);
+        assert_eq!(table.tai_minus_utc(BEFORE_LEAP), 10.0);
+        assert_eq!(table.tai_minus_utc(BEFORE_LEAP + 1.0), 37.0);
+
+        assert!(matches!(
+            LeapSecondTable::parse_list("2272060800\n"),
+            Err(LeapSecondError::Parse { line: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,493 @@
+//! Two-line element set parsing
+//!
+//! Extends the SGP4 TLE reader with Alpha-5 catalog numbers: the first of
+//! the five catalog characters may be a letter (A-Z without I and O) worth
+//! 10-33 ten-thousands, covering catalog numbers 100000-339999.
+//!
+//! Every TLE is validated before it reaches SGP4 (line numbers, checksums,
+//! catalog numbers and element ranges); errors carry the 1-based line and
+//! column of the offending field.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use sgp4::Elements;
+
+/// Length of both TLE lines
+pub const LINE_LENGTH: usize = 69;
+
+/// Catalog field columns (0-based, end exclusive) on both lines
+const CATALOG_COLUMNS: Range<usize> = 2..7;
+
+/// Line 1 fields (0-based, end exclusive)
+const CLASSIFICATION_COLUMN: usize = 7;
+const EPOCH_DAY_COLUMNS: Range<usize> = 20..32;
+
+/// Line 2 fields (0-based, end exclusive)
+const INCLINATION_COLUMNS: Range<usize> = 8..16;
+const RIGHT_ASCENSION_COLUMNS: Range<usize> = 17..25;
+const ECCENTRICITY_COLUMNS: Range<usize> = 26..33;
+const ARGUMENT_OF_PERIGEE_COLUMNS: Range<usize> = 34..42;
+const MEAN_ANOMALY_COLUMNS: Range<usize> = 43..51;
+const MEAN_MOTION_COLUMNS: Range<usize> = 52..63;
+
+/// Checksum digit column on both lines
+const CHECKSUM_COLUMN: usize = 68;
+
+/// Mean motion above this (rev/day) would put perigee inside the Earth
+const MAX_MEAN_MOTION: f64 = 17.0;
+
+/// Parse a TLE, accepting Alpha-5 catalog numbers
+pub fn parse(line1: &str, line2: &str) -> Result<Elements, TleError> {
+    validate(line1, line2)?;
+    let norad_id = catalog_number(line1, 1)?;
+
+    let mut elements = Elements::from_tle(
+        None,
+        numeric_catalog(line1).as_bytes(),
+        numeric_catalog(line2).as_bytes(),
+    )
+    .map_err(|e| TleError::Parse(format!("{:?}", e)))?;
+    elements.norad_id = norad_id;
+    Ok(elements)
+}
+
+/// Check the layout, checksums, catalog numbers and element ranges of a TLE
+pub fn validate(line1: &str, line2: &str) -> Result<(), TleError> {
+    check_line(line1, 1)?;
+    check_line(line2, 2)?;
+
+    let catalog1 = catalog_number(line1, 1)?;
+    let catalog2 = catalog_number(line2, 2)?;
+    if catalog1 != catalog2 {
+        return Err(field_error(
+            2,
+            CATALOG_COLUMNS.start,
+            "catalog number",
+            format!("{} does not match {} on line 1", catalog2, catalog1),
+        ));
+    }
+
+    if !matches!(
+        line1.as_bytes()[CLASSIFICATION_COLUMN],
+        b'U' | b'C' | b'S' | b' '
+    ) {
+        return Err(field_error(
+            1,
+            CLASSIFICATION_COLUMN,
+            "classification",
+            format!(
+                "'{}' is not U, C or S",
+                &line1[CLASSIFICATION_COLUMN..=CLASSIFICATION_COLUMN]
+            ),
+        ));
+    }
+    let epoch_day = number(line1, 1, EPOCH_DAY_COLUMNS, "epoch day")?;
+    if !(1.0..367.0).contains(&epoch_day) {
+        return Err(field_error(
+            1,
+            EPOCH_DAY_COLUMNS.start,
+            "epoch day",
+            format!("{} outside [1, 367)", epoch_day),
+        ));
+    }
+
+    let angles = [
+        (INCLINATION_COLUMNS, "inclination", 0.0..=180.0),
+        (RIGHT_ASCENSION_COLUMNS, "right ascension", 0.0..=360.0),
+        (
+            ARGUMENT_OF_PERIGEE_COLUMNS,
+            "argument of perigee",
+            0.0..=360.0,
+        ),
+        (MEAN_ANOMALY_COLUMNS, "mean anomaly", 0.0..=360.0),
+    ];
+    for (columns, name, range) in angles {
+        let degrees = number(line2, 2, columns.clone(), name)?;
+        if !range.contains(&degrees) {
+            return Err(field_error(
+                2,
+                columns.start,
+                name,
+                format!(
+                    "{} outside [{}, {}] degrees",
+                    degrees,
+                    range.start(),
+                    range.end()
+                ),
+            ));
+        }
+    }
+
+    // Seven digits with an implied leading decimal point, so always in [0, 1)
+    let eccentricity = &line2[ECCENTRICITY_COLUMNS];
+    if !eccentricity.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(field_error(
+            2,
+            ECCENTRICITY_COLUMNS.start,
+            "eccentricity",
+            format!(
+                "'{}' is not seven digits (implied leading decimal point)",
+                eccentricity
+            ),
+        ));
+    }
+
+    let mean_motion = number(line2, 2, MEAN_MOTION_COLUMNS, "mean motion")?;
+    if mean_motion <= 0.0 || mean_motion > MAX_MEAN_MOTION {
+        return Err(field_error(
+            2,
+            MEAN_MOTION_COLUMNS.start,
+            "mean motion",
+            format!("{} outside (0, {}] rev/day", mean_motion, MAX_MEAN_MOTION),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Length, line number and checksum of one line
+fn check_line(line: &str, line_number: usize) -> Result<(), TleError> {
+    if let Some(column) = line
+        .bytes()
+        .position(|b| !b.is_ascii() || b.is_ascii_control())
+    {
+        return Err(field_error(
+            line_number,
+            column,
+            "line",
+            "non-ASCII or control character".to_string(),
+        ));
+    }
+    if line.len() != LINE_LENGTH {
+        return Err(field_error(
+            line_number,
+            line.len().min(LINE_LENGTH),
+            "line",
+            format!("{} characters, expected {}", line.len(), LINE_LENGTH),
+        ));
+    }
+
+    let bytes = line.as_bytes();
+    let expected_number = b'0' + line_number as u8;
+    if bytes[0] != expected_number {
+        return Err(field_error(
+            line_number,
+            0,
+            "line number",
+            format!("'{}', expected '{}'", &line[..1], line_number),
+        ));
+    }
+    if bytes[1] != b' ' {
+        return Err(field_error(
+            line_number,
+            1,
+            "line number",
+            "expected a space after the line number".to_string(),
+        ));
+    }
+
+    let checksum = checksum(&line[..CHECKSUM_COLUMN]);
+    match bytes[CHECKSUM_COLUMN] {
+        digit @ b'0'..=b'9' if digit - b'0' == checksum => Ok(()),
+        digit @ b'0'..=b'9' => Err(field_error(
+            line_number,
+            CHECKSUM_COLUMN,
+            "checksum",
+            format!(
+                "{} does not match computed checksum {}",
+                digit - b'0',
+                checksum
+            ),
+        )),
+        _ => Err(field_error(
+            line_number,
+            CHECKSUM_COLUMN,
+            "checksum",
+            format!("'{}' is not a digit", &line[CHECKSUM_COLUMN..]),
+        )),
+    }
+}
+
+/// Modulo-10 sum of the digits, with each '-' counting as one
+pub fn checksum(text: &str) -> u8 {
+    let sum: u32 = text
+        .bytes()
+        .map(|b| match b {
+            b'0'..=b'9' => u32::from(b - b'0'),
+            b'-' => 1,
+            _ => 0,
+        })
+        .sum();
+    (sum % 10) as u8
+}
+
+/// Decimal field of a validated (ASCII, full length) line
+fn number(
+    line: &str,
+    line_number: usize,
+    columns: Range<usize>,
+    name: &'static str,
+) -> Result<f64, TleError> {
+    let text = line[columns.clone()].trim();
+    text.parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| {
+            field_error(
+                line_number,
+                columns.start,
+                name,
+                format!("'{}' is not a number", text),
+            )
+        })
+}
+
+/// Error at a 0-based column, reported 1-based as in the TLE format description
+fn field_error(line: usize, column: usize, field: &'static str, message: String) -> TleError {
+    TleError::Field {
+        line,
+        column: column + 1,
+        field,
+        message,
+    }
+}
+
+/// Decoded catalog number of a TLE line
+pub fn catalog_number(line: &str, line_number: usize) -> Result<u64, TleError> {
+    let field = line.get(CATALOG_COLUMNS).unwrap_or_default();
+    parse_catalog_number(field.trim_start()).ok_or_else(|| {
+        field_error(
+            line_number,
+            CATALOG_COLUMNS.start,
+            "catalog number",
+            format!("'{}' is not a catalog number", field),
+        )
+    })
+}
+
+/// Catalog number written as digits (up to nine, as used by OMM) or in Alpha-5 form
+pub fn parse_catalog_number(text: &str) -> Option<u64> {
+    let bytes = text.as_bytes();
+    if bytes.is_empty() || bytes.len() > 9 {
+        return None;
+    }
+    if bytes.iter().all(u8::is_ascii_digit) {
+        return text.parse().ok();
+    }
+
+    let (&letter, digits) = bytes.split_first()?;
+    if bytes.len() != 5 || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let value = match letter {
+        b'A'..=b'H' => letter - b'A' + 10,
+        b'J'..=b'N' => letter - b'J' + 18,
+        b'P'..=b'Z' => letter - b'P' + 23,
+        _ => return None,
+    };
+    Some(u64::from(value) * 10_000 + text[1..].parse::<u64>().ok()?)
+}
+
+/// Line with an Alpha-5 letter replaced by '0' for the digits-only SGP4
+/// reader. Letters and '0' both add nothing to the checksum, so it still holds.
+fn numeric_catalog(line: &str) -> Cow<'_, str> {
+    match line.as_bytes().get(CATALOG_COLUMNS.start) {
+        Some(letter) if letter.is_ascii_alphabetic() => {
+            let mut numeric = line.to_string();
+            numeric.replace_range(CATALOG_COLUMNS.start..CATALOG_COLUMNS.start + 1, "0");
+            Cow::Owned(numeric)
+        }
+        _ => Cow::Borrowed(line),
+    }
+}
+
+/// TLE parsing errors
+#[derive(Debug, Clone)]
+pub enum TleError {
+    /// Offending field, with 1-based line and column
+    Field {
+        line: usize,
+        column: usize,
+        field: &'static str,
+        message: String,
+    },
+    Parse(String),
+}
+
+impl std::fmt::Display for TleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TleError::Field {
+                line,
+                column,
+                field,
+                message,
+            } => write!(
+                f,
+                "line {}, column {} ({}): {}",
+                line, column, field, message
+            ),
+            TleError::Parse(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TleError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISS_TLE_LINE1: &str = "1 25544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9008";
+    const ISS_TLE_LINE2: &str = "2 25544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423096";
+
+    #[test]
+    fn test_alpha5_catalog_numbers() {
+        assert_eq!(parse_catalog_number("25544"), Some(25544));
+        assert_eq!(parse_catalog_number("A0000"), Some(100000));
+        assert_eq!(parse_catalog_number("H9999"), Some(179999));
+        assert_eq!(parse_catalog_number("J0000"), Some(180000));
+        assert_eq!(parse_catalog_number("P0000"), Some(230000));
+        assert_eq!(parse_catalog_number("Z9999"), Some(339999));
+        assert_eq!(parse_catalog_number("270000123"), Some(270000123));
+
+        // I and O are not used; the letter must be followed by four digits
+        assert_eq!(parse_catalog_number("I0000"), None);
+        assert_eq!(parse_catalog_number("O1234"), None);
+        assert_eq!(parse_catalog_number("A123"), None);
+        assert_eq!(parse_catalog_number("a1234"), None);
+        assert_eq!(parse_catalog_number(""), None);
+    }
+
+    /// ISS lines with the catalog number written as Alpha-5 "T5544"; the letter
+    /// drops a 2 from the checksum
+    const ALPHA5_TLE_LINE1: &str = "1 T5544U 98067A   24001.50000000  .00016717  00000+0  10270-3 0  9006";
+    const ALPHA5_TLE_LINE2: &str = "2 T5544  51.6400 208.9163 0006703 130.5360 325.0288 15.50377579423094";
+
+    /// Replace the text at 1-based `column` and fix up the checksum
+    fn edit(line: &str, column: usize, text: &str) -> String {
+        let mut edited = line.to_string();
+        edited.replace_range(column - 1..column - 1 + text.len(), text);
+        let checksum = checksum(&edited[..68]);
+        edited.replace_range(68.., &checksum.to_string());
+        edited
+    }
+
+    fn field_error(result: Result<(), TleError>) -> (usize, usize, &'static str) {
+        match result {
+            Err(TleError::Field {
+                line,
+                column,
+                field,
+                ..
+            }) => (line, column, field),
+            other => panic!("expected a field error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_alpha5_tle() {
+        let numeric = parse(ISS_TLE_LINE1, ISS_TLE_LINE2).unwrap();
+        let alpha5 = parse(ALPHA5_TLE_LINE1, ALPHA5_TLE_LINE2).unwrap();
+        assert_eq!(numeric.norad_id, 25544);
+        assert_eq!(alpha5.norad_id, 275544);
+        assert_eq!(alpha5.mean_motion, numeric.mean_motion);
+
+        let bad = edit(ISS_TLE_LINE1, 3, "I");
+        assert!(matches!(
+            parse(&bad, ISS_TLE_LINE2),
+            Err(TleError::Field {
+                line: 1,
+                column: 3,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(checksum(&ISS_TLE_LINE1[..68]), 8);
+        assert_eq!(checksum(&ISS_TLE_LINE2[..68]), 6);
+        assert_eq!(checksum("1-1"), 3);
+        assert!(validate(ISS_TLE_LINE1, ISS_TLE_LINE2).is_ok());
+        assert!(validate(ALPHA5_TLE_LINE1, ALPHA5_TLE_LINE2).is_ok());
+
+        let wrong = format!("{}7", &ISS_TLE_LINE1[..68]);
+        assert_eq!(
+            field_error(validate(&wrong, ISS_TLE_LINE2)),
+            (1, 69, "checksum")
+        );
+        let err = validate(ISS_TLE_LINE1, &format!("{}X", &ISS_TLE_LINE2[..68])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 69 (checksum): 'X' is not a digit"
+        );
+    }
+
+    #[test]
+    fn test_validate_layout() {
+        assert_eq!(
+            field_error(validate(ISS_TLE_LINE2, ISS_TLE_LINE2)),
+            (1, 1, "line number")
+        );
+        assert_eq!(
+            field_error(validate(ISS_TLE_LINE1, ISS_TLE_LINE1)),
+            (2, 1, "line number")
+        );
+        assert_eq!(
+            field_error(validate(&ISS_TLE_LINE1[..68], ISS_TLE_LINE2)),
+            (1, 69, "line")
+        );
+        assert_eq!(
+            field_error(validate("INVALID TLE", "INVALID TLE")),
+            (1, 12, "line")
+        );
+
+        let other_object = edit(ISS_TLE_LINE2, 3, "25545");
+        let err = validate(ISS_TLE_LINE1, &other_object).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 3 (catalog number): 25545 does not match 25544 on line 1"
+        );
+    }
+
+    #[test]
+    fn test_validate_ranges() {
+        let cases = [
+            (edit(ISS_TLE_LINE2, 9, "181.0000"), (2, 9, "inclination")),
+            (
+                edit(ISS_TLE_LINE2, 18, "360.0001"),
+                (2, 18, "right ascension"),
+            ),
+            (edit(ISS_TLE_LINE2, 27, "0.00670"), (2, 27, "eccentricity")),
+            (
+                edit(ISS_TLE_LINE2, 35, "  -1.000"),
+                (2, 35, "argument of perigee"),
+            ),
+            (edit(ISS_TLE_LINE2, 44, "3x5.0288"), (2, 44, "mean anomaly")),
+            (
+                edit(ISS_TLE_LINE2, 53, " 0.00000000"),
+                (2, 53, "mean motion"),
+            ),
+            (
+                edit(ISS_TLE_LINE2, 53, "25.00000000"),
+                (2, 53, "mean motion"),
+            ),
+        ];
+        for (line2, expected) in cases {
+            assert_eq!(
+                field_error(validate(ISS_TLE_LINE1, &line2)),
+                expected,
+                "{}",
+                line2
+            );
+        }
+
+        let epoch = edit(ISS_TLE_LINE1, 21, "400.50000000");
+        assert_eq!(
+            field_error(validate(&epoch, ISS_TLE_LINE2)),
+            (1, 21, "epoch day")
+        );
+    }
+}